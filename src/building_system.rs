@@ -5,7 +5,7 @@ use crate::prelude::*;
 // BUILDING SYSTEM - AoE2 style with upgrades
 // ============================================================================
 
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Building {
     pub building_type: BuildingType,
     pub level: i32,
@@ -17,7 +17,7 @@ pub struct Building {
     pub max_garrison: i32,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum BuildingType {
     // Economy
     TownCenter,
@@ -131,6 +131,14 @@ impl BuildingType {
         }
     }
 
+    // Degrees added to the tile the building stands on each heat update.
+    pub fn heat_output(&self) -> f32 {
+        match self {
+            BuildingType::Furnace => 6.0,
+            _ => 0.0,
+        }
+    }
+
     pub fn provides_population(&self) -> i32 {
         match self {
             BuildingType::TownCenter => 5,
@@ -140,6 +148,32 @@ impl BuildingType {
         }
     }
 
+    pub const ALL: [BuildingType; 27] = [
+        BuildingType::TownCenter, BuildingType::House, BuildingType::Farm, BuildingType::Mill,
+        BuildingType::LumberCamp, BuildingType::MiningCamp, BuildingType::Market, BuildingType::Dock,
+        BuildingType::Barracks, BuildingType::FiringRange, BuildingType::VehicleBay, BuildingType::HeavyWeaponsFacility,
+        BuildingType::CommandCenter, BuildingType::DefenseTurret, BuildingType::Gate, BuildingType::Wall,
+        BuildingType::Blacksmith, BuildingType::University, BuildingType::ResearchStation,
+        BuildingType::Wonder, BuildingType::Workshop, BuildingType::ResearchLab, BuildingType::Furnace,
+        BuildingType::CookingStation, BuildingType::TanningRack, BuildingType::Loom, BuildingType::StorageWarehouse,
+    ];
+
+    // snake_case id used in data files, e.g. "heavy_weapons_facility".
+    pub fn id(&self) -> String {
+        let mut id = String::new();
+        for (i, c) in format!("{:?}", self).chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                id.push('_');
+            }
+            id.push(c.to_ascii_lowercase());
+        }
+        id
+    }
+
+    pub fn from_id(id: &str) -> Option<BuildingType> {
+        Self::ALL.iter().find(|building_type| building_type.id() == id).cloned()
+    }
+
     pub fn upgrade_cost(&self, current_level: i32) -> Vec<(String, u32)> {
         let multiplier = (current_level + 1) as u32;
         match self {
@@ -160,14 +194,14 @@ impl BuildingType {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ProductionItem {
     pub item_type: ProductionType,
     pub time_remaining: f32,
     pub progress: f32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ProductionType {
     Unit(String),
     Research(String),
@@ -258,7 +292,7 @@ impl ResourceStorage {
 // POPULATION SYSTEM
 // ============================================================================
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct Population {
     pub current: i32,
     pub capacity: i32,
@@ -283,16 +317,14 @@ impl Plugin for BuildingPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Population::default())
             .add_systems(
-                Update,
+                SimTick,
                 (
                     building_production_system,
                     building_upgrade_system,
                     building_garrison_system,
                     calculate_population_capacity,
                 )
-                    .run_if(bevy::time::common_conditions::on_timer(
-                        bevy::utils::Duration::from_secs(1),
-                    )),
+                    .run_if(every_seconds(1.0)),
             );
     }
 }
@@ -377,32 +409,25 @@ pub fn spawn_building(
     commands: &mut Commands,
     building_type: BuildingType,
     position: Position,
-    texture: &SpriteSheetHandle,
-    atlas_layout: &SpriteSheetAtlasLayout,
+    sprite_sheet: Option<&SpriteSheet>,
 ) -> Entity {
     let max_health = building_type.base_health();
     let max_garrison = building_type.garrison_capacity();
 
-    commands.spawn((
-        SpriteSheetBundle {
-            texture: texture.0.clone(),
-            atlas: TextureAtlas {
-                layout: atlas_layout.0.clone(),
-                index: 3 * 64 + 10, // Placeholder sprite
+    crate::spawn_sprite(commands, sprite_sheet, 3 * 64 + 10) // Placeholder sprite
+        .insert(position.to_transform_layer(1.0))
+        .insert((
+            Building {
+                building_type: building_type.clone(),
+                level: 1,
+                max_level: 5,
+                health: max_health,
+                max_health,
+                production_queue: vec![],
+                garrison: vec![],
+                max_garrison,
             },
-            transform: position.to_transform_layer(1.0),
-            ..default()
-        },
-        Building {
-            building_type: building_type.clone(),
-            level: 1,
-            max_level: 5,
-            health: max_health,
-            max_health,
-            production_queue: vec![],
-            garrison: vec![],
-            max_garrison,
-        },
-        position,
-    )).id()
+            position,
+        ))
+        .id()
 }
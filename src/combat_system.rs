@@ -10,25 +10,22 @@ impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(
-            Update,
+            SimTick,
             (
             melee::combat_system_melee
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(0.5))
             ,
             ranged::combat_system_ranged
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(0.5))
             ,
-            melee::attacked_entities_system
-            .run_if(in_state(GameState::InGame)),
-            melee::temporary_visual_elements_system
-            .run_if(in_state(GameState::InGame)),
+            melee::attacked_entities_system,
             )
         )
+        .add_systems(PostUpdate, death::death_system)
         .add_systems(
             Update,
-            death::death_system
+            melee::temporary_visual_elements_system
+            .run_if(in_state(GameState::InGame))
         )
         // .add_system(
         //     ranged::combat_system_ranged
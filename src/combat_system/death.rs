@@ -1,10 +1,12 @@
 use crate::prelude::*;
 
+// Runs once the tick's systems are all done, so none of them is left holding commands for an
+// entity that is already gone.
 pub fn death_system(
     mut commands: Commands,
-    mut entities: Query<(Entity, &mut Brain, &mut PhysicalBody, &Position), With<Dying>>,
+    entities: Query<Entity, With<Dying>>,
 ) {
-    for (entity, mut _brain, mut _physical_body, _position) in entities.iter_mut() {
+    for entity in entities.iter() {
         commands.entity(entity).despawn_recursive();
         // TO DO: Make a corpse and drop loot.
     }
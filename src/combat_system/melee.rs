@@ -4,7 +4,7 @@ pub fn combat_system_melee(
     mut commands: Commands,
     mut entities_that_might_fight: Query<(Entity, &mut Brain, &mut PhysicalBody, &Position, Option<&mut Pathing>, Option<&Targeting>)>,
     attackables: Query<(Entity, &Position), With<Brain>>,
-    sprite_sheet: Res<SpriteSheet>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
 ) {
     for (e, mut brain, mut physical_body, position, pathing, targeting) in entities_that_might_fight.iter_mut() {
         if brain.task != Some(Task::Fight) { continue; }
@@ -14,13 +14,7 @@ pub fn combat_system_melee(
                 if entity == targeting.target {
                     entity_found = true;
                     if position.distance(target_position) <= 1 {
-                        let sprite =  TextureAtlasSprite::new(StrikeType::Hit.sprite_index());
-                        commands
-                            .spawn(SpriteSheetBundle {
-                                sprite,
-                                texture_atlas: sprite_sheet.0.clone(),
-                                ..default()
-                            })
+                        crate::spawn_sprite(&mut commands, sprite_sheet.as_deref(), StrikeType::Hit.sprite_index())
                             .insert(target_position.clone())
                             .insert(target_position.to_transform_layer(1.1))
                             .insert( TemporaryVisualElement { duration: 0.2 } )
@@ -104,7 +98,7 @@ fn do_melee_damage(
     attacked_entity: Entity,
     body1: &PhysicalBody,
     body2: &mut PhysicalBody,
-    _asset_server: &Res<AssetServer>
+    _asset_server: Option<&AssetServer>
 ) {
     let damage =
         1 +
@@ -113,7 +107,7 @@ fn do_melee_damage(
         ;
     body2.attributes.health -= damage;
     if body2.attributes.health <= 0 {
-        commands.entity(attacked_entity).despawn_recursive();
+        commands.entity(attacked_entity).insert(Dying);
     }
     body2.danger = Some(Danger {
         danger_type: DangerType::Attacked,
@@ -133,7 +127,7 @@ pub fn attacked_entities_system(
     mut commands: Commands,
     attacked_query: Query<(Entity, &Attacked), With<Attacked>>,
     mut physical_bodies: Query<(Entity, &mut PhysicalBody)>,
-    asset_server: Res<AssetServer>
+    asset_server: Option<Res<AssetServer>>
 ) {
     for (attacked_entity, attack_info) in attacked_query.iter() {
         commands.entity(attacked_entity).remove::<Attacked>();
@@ -151,7 +145,7 @@ pub fn attacked_entities_system(
         // Now do the damage to the attacked body.
         for (entity, mut physical_body) in physical_bodies.iter_mut() {
             if entity == attacked_entity {
-                do_melee_damage(&mut commands, attacker_entity, attacked_entity, &attacker_physical_body, &mut physical_body, &asset_server);
+                do_melee_damage(&mut commands, attacker_entity, attacked_entity, &attacker_physical_body, &mut physical_body, asset_server.as_deref());
             }
         }
     }
@@ -59,8 +59,7 @@ pub fn ranged_attack_system(
     )>,
     target_query: Query<&Position, With<Attackable>>,
     time: Res<Time>,
-    texture: Res<SpriteSheetHandle>,
-    atlas_layout: Res<SpriteSheetAtlasLayout>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
 ) {
     for (shooter_entity, shooter_pos, mut equipment, mut inventory, targeting, body) in
         shooter_query.iter_mut()
@@ -99,33 +98,26 @@ pub fn ranged_attack_system(
                                         _ => 94 * 64 + 30,
                                     };
 
-                                    commands.spawn((
-                                        SpriteSheetBundle {
-                                            texture: texture.0.clone(),
-                                            atlas: TextureAtlas {
-                                                layout: atlas_layout.0.clone(),
-                                                index: projectile_sprite_index,
+                                    crate::spawn_sprite(&mut commands, sprite_sheet.as_deref(), projectile_sprite_index)
+                                        .insert(shooter_pos.to_transform_layer(5.0))
+                                        .insert((
+                                            Projectile {
+                                                damage: weapon_stats.damage
+                                                    + body.attributes.strength / 2,
+                                                owner: shooter_entity,
+                                                target: Some(targeting.target),
+                                                velocity,
+                                                lifetime: 5.0,
+                                                piercing: false,
+                                                explosive: false,
+                                                explosion_radius: 0.0,
                                             },
-                                            transform: shooter_pos.to_transform_layer(5.0),
-                                            ..default()
-                                        },
-                                        Projectile {
-                                            damage: weapon_stats.damage
-                                                + body.attributes.strength / 2,
-                                            owner: shooter_entity,
-                                            target: Some(targeting.target),
-                                            velocity,
-                                            lifetime: 5.0,
-                                            piercing: false,
-                                            explosive: false,
-                                            explosion_radius: 0.0,
-                                        },
-                                        Position {
-                                            x: shooter_pos.x,
-                                            y: shooter_pos.y,
-                                            z: 5,
-                                        },
-                                    ));
+                                            Position {
+                                                x: shooter_pos.x,
+                                                y: shooter_pos.y,
+                                                z: shooter_pos.z,
+                                            },
+                                        ));
                                 }
                             }
                         }
@@ -173,11 +165,11 @@ pub fn projectile_collision_system(
     mut commands: Commands,
     projectile_query: Query<(Entity, &Position, &Projectile)>,
     mut target_query: Query<(Entity, &Position, &mut PhysicalBody), With<Attackable>>,
-    tile_hash: Res<TileHash>,
+    tile_map: Res<TileMap>,
 ) {
     for (proj_entity, proj_pos, projectile) in projectile_query.iter() {
         // Check collision with walls
-        if let Some(tile) = tile_hash.hash.get(proj_pos) {
+        if let Some(tile) = tile_map.get(proj_pos) {
             if tile.is_wall() {
                 commands.entity(proj_entity).despawn();
                 continue;
@@ -185,6 +177,7 @@ pub fn projectile_collision_system(
         }
 
         // Check collision with targets
+        let mut explosions = vec![];
         for (target_entity, target_pos, mut target_body) in target_query.iter_mut() {
             // Don't hit the shooter
             if target_entity == projectile.owner {
@@ -205,13 +198,7 @@ pub fn projectile_collision_system(
 
                 // Handle explosive projectiles
                 if projectile.explosive {
-                    apply_explosion(
-                        &mut commands,
-                        target_pos,
-                        projectile.explosion_radius,
-                        projectile.damage / 2,
-                        &mut target_query,
-                    );
+                    explosions.push(*target_pos);
                 }
 
                 // Remove projectile unless it's piercing
@@ -220,6 +207,16 @@ pub fn projectile_collision_system(
                 }
             }
         }
+        // Explosions go off once the hits are in, as they damage the same targets.
+        for center in explosions {
+            apply_explosion(
+                &mut commands,
+                &center,
+                projectile.explosion_radius,
+                projectile.damage / 2,
+                &mut target_query,
+            );
+        }
     }
 }
 
@@ -231,13 +228,18 @@ fn apply_explosion(
     damage: i32,
     target_query: &mut Query<(Entity, &Position, &mut PhysicalBody), With<Attackable>>,
 ) {
-    for (_, target_pos, mut target_body) in target_query.iter_mut() {
+    let mut hits = vec![];
+    for (target_entity, target_pos, _) in target_query.iter() {
         let distance = center.distance(target_pos) as f32;
 
         if distance <= radius {
             // Damage falls off with distance
             let damage_multiplier = 1.0 - (distance / radius);
-            let final_damage = (damage as f32 * damage_multiplier) as i32;
+            hits.push((target_entity, (damage as f32 * damage_multiplier) as i32));
+        }
+    }
+    for (target_entity, final_damage) in hits {
+        if let Ok((_, _, mut target_body)) = target_query.get_mut(target_entity) {
             target_body.attributes.health -= final_damage;
         }
     }
@@ -398,8 +400,8 @@ pub fn apply_accuracy(
     max_range: f32,
     skill_level: i32,
     weather_modifier: f32,
+    rng: &mut impl Rng,
 ) -> bool {
-    let mut rng = rand::thread_rng();
 
     // Distance penalty
     let distance_modifier = 1.0 - (distance / max_range) * 0.5;
@@ -1,31 +1,33 @@
 use bevy::prelude::*;
-use crate::unitgenerator_system::UnitTemplate;
 
 use super::prelude::*;
 
-#[derive(Component, Copy, Clone, Debug, PartialEq, Hash, Eq)]
+#[derive(Component, Copy, Clone, Debug, PartialEq, Hash, Eq, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
+    #[serde(default)]
     pub z: i32,
 }
+// z is the map level: 0 is the surface and each level underground is one lower.
 impl Position {
+    // Only one level is drawn at a time, so z hides things rather than sorting them.
     pub fn to_transform(&self) -> Transform {
         Transform::from_xyz(
             self.x as f32 * TILE_SIZE,
             self.y as f32 * TILE_SIZE,
-            self.z as f32,
+            0.0,
         )
     }
     pub fn to_transform_layer(&self, layer: f32) -> Transform {
         Transform::from_xyz(
             self.x as f32 * TILE_SIZE,
             self.y as f32 * TILE_SIZE,
-            self.z as f32 + layer,
+            layer,
         )
     }
     pub fn distance(&self, other: &Position) -> i32 {
-        (((self.x - other.x).pow(2) + (self.y - other.y).pow(2)) as f32).sqrt() as i32
+        (((self.x - other.x).pow(2) + (self.y - other.y).pow(2) + (self.z - other.z).pow(2)) as f32).sqrt() as i32
     }
 }
 
@@ -69,7 +71,7 @@ impl StrikeType {
     }
 }
 
-#[derive(Component, PartialEq, Copy, Clone, Debug)]
+#[derive(Component, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum ActorType { // Entity? Character? Creature? Actor? Avatar? Unit? Agent?
     Miner,
     ManCrazy,
@@ -129,13 +131,15 @@ impl ActorType {
     }
 }
 
-#[derive(Component, PartialEq, Clone, Debug)]
+#[derive(Component, PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub enum TileType {
     Grass,
     Cave,
     Dirt,
     Gravel,
     Sand,
+    StairsDown,
+    StairsUp,
     Stone,
     Water,
     WallBrick,
@@ -153,6 +157,8 @@ impl TileType {
             TileType::Dirt => (1, 0),
             TileType::Gravel => (3, 1),
             TileType::Sand => (6, 42),
+            TileType::StairsDown => (13, 8),
+            TileType::StairsUp => (13, 9),
             TileType::Stone => (3, 61),
             TileType::Water => (5, 12),
             TileType::WallGame => (2, 10),
@@ -170,6 +176,36 @@ impl TileType {
         matches!(self, TileType::WallGame | TileType::WallStone | TileType::WallWood | TileType::WallBrick | TileType::WallMetal)
 
     }
+    // Path cost of stepping straight onto the tile; a diagonal step costs 1.4 times as much. Open
+    // ground is the minimum, and swimming costs the most, so paths go round water.
+    pub fn movement_cost(&self) -> i32 {
+        match self {
+            TileType::Gravel => 12,
+            TileType::Sand => 15,
+            TileType::StairsDown | TileType::StairsUp => 15,
+            TileType::Water => 50,
+            _ => BASE_MOVEMENT_COST,
+        }
+    }
+    // Ground the rain turns to mud.
+    pub fn is_muddy(&self) -> bool {
+        matches!(self, TileType::Grass | TileType::Dirt)
+    }
+    // Rocky ground, which is dug down into the level below, and walls other than the map border.
+    pub fn is_mineable(&self) -> bool {
+        matches!(self, TileType::Stone | TileType::Cave | TileType::WallStone | TileType::WallBrick | TileType::WallMetal)
+    }
+    // Work it takes to mine. An unskilled miner with a pickaxe of efficiency 1.0 does one unit per tick.
+    pub fn mining_work(&self) -> f32 {
+        match self {
+            TileType::Cave => 6.0,
+            TileType::Stone => 10.0,
+            TileType::WallBrick => 15.0,
+            TileType::WallStone => 20.0,
+            TileType::WallMetal => 40.0,
+            _ => 0.0,
+        }
+    }
 }
 
 #[derive(Component)]
@@ -182,7 +218,7 @@ pub trait HoverNote {
     fn hover_note(&self) -> String;
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Food {
     pub nutrition: f32,
     pub spoilage: f32,
@@ -206,7 +242,7 @@ impl HoverNote for Food {
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct HasName {
     pub name: String,
 }
@@ -233,12 +269,18 @@ pub struct ClickedOn;
 #[derive(Component)]
 pub struct InGameButton;
 
+// Speed 0 is the pause button.
+#[derive(Component)]
+pub struct SpeedButton {
+    pub speed: u32,
+}
+
 // #[derive(Component)]
 // pub struct CombatTarget {
 //     pub target: Entity,
 // }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
 pub struct Skill {
     pub experience: i32,
     pub exp_lost: i32, // Forgetting/atrophied skills. Easier to regain.
@@ -255,7 +297,7 @@ impl Skill {
     }
 }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
 pub struct Attributeset {
     pub health: i32,
     pub strength: i32,
@@ -278,8 +320,23 @@ impl Default for Attributeset {
         }
     }
 }
+impl Attributeset {
+    // Looks an attribute up by its field name, as used in data files.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut i32> {
+        match name {
+            "health" => Some(&mut self.health),
+            "strength" => Some(&mut self.strength),
+            "dexterity" => Some(&mut self.dexterity),
+            "constitution" => Some(&mut self.constitution),
+            "intelligence" => Some(&mut self.intelligence),
+            "wisdom" => Some(&mut self.wisdom),
+            "charisma" => Some(&mut self.charisma),
+            _ => None,
+        }
+    }
+}
 
-#[derive(Clone, Copy, Component)]
+#[derive(Clone, Copy, Component, Serialize, Deserialize)]
 pub enum AfflictionType {
     Bleeding,
     BrokenBone,
@@ -313,7 +370,7 @@ pub enum AfflictionType {
     WoundSwelling,
     WoundTrauma,
 }
-#[derive(Clone, Copy, Component)]
+#[derive(Clone, Copy, Component, Serialize, Deserialize)]
 pub enum AfflictionLocation {
     Head,
     Torso,
@@ -351,7 +408,7 @@ pub enum AfflictionLocation {
     Trunk,
 }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
 pub struct Affliction {
     pub affliction_type: AfflictionType,
     pub affliction_location: AfflictionLocation,
@@ -360,7 +417,7 @@ pub struct Affliction {
     pub worsening: bool,
 }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
 pub struct Skillset {
     pub animal_raising: Skill,
     pub brawling: Skill,
@@ -395,15 +452,62 @@ impl Default for Skillset {
         }
     }
 }
+impl Skillset {
+    // Looks a skill up by its field name, as used in data files.
+    pub fn get(&self, name: &str) -> Option<&Skill> {
+        match name {
+            "animal_raising" => Some(&self.animal_raising),
+            "brawling" => Some(&self.brawling),
+            "construction" => Some(&self.construction),
+            "cooking" => Some(&self.cooking),
+            "crafting" => Some(&self.crafting),
+            "doctoring" => Some(&self.doctoring),
+            "farming" => Some(&self.farming),
+            "fishing" => Some(&self.fishing),
+            "foraging" => Some(&self.foraging),
+            "hunting" => Some(&self.hunting),
+            "mining" => Some(&self.mining),
+            "social" => Some(&self.social),
+            "woodcutting" => Some(&self.woodcutting),
+            _ => None,
+        }
+    }
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Skill> {
+        match name {
+            "animal_raising" => Some(&mut self.animal_raising),
+            "brawling" => Some(&mut self.brawling),
+            "construction" => Some(&mut self.construction),
+            "cooking" => Some(&mut self.cooking),
+            "crafting" => Some(&mut self.crafting),
+            "doctoring" => Some(&mut self.doctoring),
+            "farming" => Some(&mut self.farming),
+            "fishing" => Some(&mut self.fishing),
+            "foraging" => Some(&mut self.foraging),
+            "hunting" => Some(&mut self.hunting),
+            "mining" => Some(&mut self.mining),
+            "social" => Some(&mut self.social),
+            "woodcutting" => Some(&mut self.woodcutting),
+            _ => None,
+        }
+    }
+    // In field order.
+    pub fn all_mut(&mut self) -> [&mut Skill; 13] {
+        [
+            &mut self.animal_raising, &mut self.brawling, &mut self.construction, &mut self.cooking,
+            &mut self.crafting, &mut self.doctoring, &mut self.farming, &mut self.fishing,
+            &mut self.foraging, &mut self.hunting, &mut self.mining, &mut self.social, &mut self.woodcutting,
+        ]
+    }
+}
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum DangerType {
     Attacked,
     Fire,
     Freezing,
     Overheating,
 }
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Danger {
     pub danger_type: DangerType,
     pub danger_source: Option<Entity>,
@@ -413,11 +517,13 @@ pub trait InfoPanel {
     fn info_panel(&self) -> Vec<String>;
 }
 
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct PhysicalBody {
     pub needs_food: Option<Need>,
     pub needs_entertainment: Option<Need>,
     pub needs_sleep: Option<Need>,
+    #[serde(default)]
+    pub needs_water: Option<Need>,
     pub index: usize,
     pub crisis: Option<String>,
     pub danger: Option<Danger>,
@@ -438,6 +544,9 @@ impl PhysicalBody {
         if let Some(needs_sleep) = &self.needs_sleep {
             info_lines.push(format!("Sleep: {:.2}%", needs_sleep.current / needs_sleep.max * 100.0));
         }
+        if let Some(needs_water) = &self.needs_water {
+            info_lines.push(format!("Water: {:.2}%", needs_water.current / needs_water.max * 100.0));
+        }
         info_lines
     }
     pub fn info_panel_attributes(&self) -> Vec<String> {
@@ -476,7 +585,7 @@ pub struct Attacked {
 #[derive(Component)]
 pub struct Dying;
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub enum Order {
     Eat,
     Hospital,
@@ -510,7 +619,7 @@ pub enum Order {
     None,
 }
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Serialize, Deserialize)]
 pub struct Brain {
     pub motivation: Option<Motivation>,
     pub task: Option<Task>,
@@ -564,7 +673,7 @@ impl InfoPanel for Brain {
     }
 }
 
-#[derive(Component, PartialEq, Copy, Clone)]
+#[derive(Component, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum PersonalityTrait {
     // Traits for People
     Adventurous, Ambitious, Analytical, Airheaded, Artistic, Brave, Calm, Charismatic, Confident, Cowardly,
@@ -576,7 +685,7 @@ pub enum PersonalityTrait {
     Creature, Social, Vicious, Territorial, Docile, 
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Nest {
     pub position: Position,
 }
@@ -590,8 +699,44 @@ pub struct Foragable;
 pub struct Choppable;
 #[derive(Component)]
 pub struct Huntable;
+// How a unit gets about, and so where it can go. Units without one walk and swim.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementProfile {
+    // Enters water.
+    pub swims: bool,
+    // Flies over water and anything standing on the ground, though not over walls.
+    pub flies: bool,
+    // Tunnels through rock.
+    pub burrows: bool,
+    // Keeps out of the colony's Avoid zones.
+    pub avoids_zones: bool,
+    // Goes through gates. Anyone else has to go round them, or smash through if they break doors.
+    pub opens_doors: bool,
+    pub breaks_doors: bool,
+}
+impl Default for MovementProfile {
+    fn default() -> Self {
+        MovementProfile {
+            swims: true,
+            flies: false,
+            burrows: false,
+            avoids_zones: false,
+            opens_doors: false,
+            breaks_doors: false,
+        }
+    }
+}
+// Work already put into a designated tile, kept if its miner is called away.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MiningProgress {
+    pub work_done: f32,
+}
+// The designated tile a miner has claimed and is walking to or digging at.
 #[derive(Component)]
-pub struct Mineable;
+pub struct MiningTarget {
+    pub position: Position,
+}
 
 #[derive(Component)]
 pub struct SetNest;
@@ -599,7 +744,7 @@ pub struct SetNest;
 #[derive(Component)]
 pub struct GiveMeAName;
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Plant {
     pub growth: f32,
     pub plant_type: ItemType,
@@ -613,11 +758,8 @@ impl HoverNote for Plant {
 #[derive(Component)]
 pub struct WorkMarker;
 
-#[derive(Component)]
-pub struct ZoneMarker;
-
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Zone {
     pub zone_type: ZoneType,
     pub item_type: ItemType,
@@ -638,15 +780,15 @@ pub struct NearestEntity {
     pub distance: i32,
 }
 
-#[derive(Component, PartialEq, Copy, Clone, Debug)]
+#[derive(Component, PartialEq, Eq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum ZoneType {
     Farm, Pasture, Storage, Fishing, Hospital, Party, Meeting, Construction, Avoid
 }
 
 
-#[derive(Component, PartialEq, Copy, Clone, Debug)]
+#[derive(Component, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Task { // Sorted in order of prioritization.
-    Crisis, Flee, Fight, Eat, Hospital, Sleep, Sleeping, Play, Order, Work, Personality, Meander, Idle,
+    Crisis, Flee, Fight, Firefight, Eat, Drink, Hospital, Sleep, Sleeping, Play, Order, Work, Personality, Meander, Idle,
     Doctor, Forage, Plant, Harvest, Mine, Chop, Construct, Hunt, Milk, Cook, Fish, Craft, Clean, Pickup, Carrying // Forms of work
 }
 impl Task {
@@ -655,7 +797,7 @@ impl Task {
     }
 }
 
-#[derive(Component, PartialEq, Copy, Clone, Debug)]
+#[derive(Component, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Motivation { // Sorted in order of prioritization.
     Crisis, Rage, Order, Danger, Hunger, Thirst, Tired, Injured, Sick, Bored, Happy, Sad, Angry, Lonely, Love, Fear, Hate, Work, Personality, Meander, Idle
 }
@@ -683,19 +825,9 @@ pub enum SelectableType {
 #[derive(Component)]
 pub struct WorkTarget;
 
-#[derive(Component)]
-pub struct Renderable {
-    //pub glyph: rltk::FontCharType,
-    pub fg: Color,
-    pub bg: Color,
-}
-
 #[derive(Component)]
 pub struct Bed;
 
-#[derive(Component, Debug)]
-pub struct Player {}
-
 #[derive(Component)]
 pub struct Viewshed {
     pub visible_tiles : Vec<Position>,
@@ -711,21 +843,21 @@ pub struct MoveRandom;
 
 #[derive(Component)]
 pub struct MonsterGenerator {
-    pub monsters: Vec<(UnitTemplate, u8)>,
+    pub monsters: Vec<(String, u8)>, // Unit template id and relative chance.
 }
 impl MonsterGenerator {
-    pub fn pick(&self) -> &UnitTemplate {
-        let mut rng = rand::thread_rng();
-        let mut total = 0;
+    // Chances are weights, so they are added up wider than u8 to allow any number of monsters.
+    pub fn pick(&self, rng: &mut impl Rng) -> &str {
+        let mut total: u32 = 0;
         for (_, chance) in self.monsters.iter() {
-            total += chance;
+            total += *chance as u32;
         }
         let mut roll = rng.gen_range(0..total);
         for (monster, chance) in self.monsters.iter() {
-            if roll < *chance {
+            if roll < *chance as u32 {
                 return monster;
             }
-            roll -= chance;
+            roll -= *chance as u32;
         }
         panic!("MonsterGenerator::pick failed");
     }
@@ -746,6 +878,8 @@ pub struct Pathing {
     pub destination: Position,
     pub unreachable: bool,
     pub moving_target: bool,
+    // Movement built up towards the next step, which is taken once it covers the step's cost.
+    pub progress: i32,
 }
 
 impl Default for Pathing {
@@ -755,6 +889,7 @@ impl Default for Pathing {
             destination: Position { x: 0, y: 0, z: 0 },
             unreachable: false,
             moving_target: false,
+            progress: 0,
         }
     }
 }
@@ -805,7 +940,7 @@ impl SizeXYZ {
 
 // NEEDS
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
 pub struct Need {
     pub current: f32,
     pub max: f32,
@@ -1,13 +1,13 @@
 
-// MAP CONSTANTS
-pub const MAP_WIDTH: i32 = 58;
-pub const MAP_LENGTH: i32 = 30;
-
 // VIEW CONSTANTS
 pub const VIEWAREA_WIDTH: u32 = 76;
 pub const VIEWAREA_HEIGHT: u32 = 40;
 pub const TILE_SIZE: f32 = 32.0;
 
+// MOVEMENT CONSTANTS
+// Cost of a straight step across open ground. A unit on the move covers this much each movement tick.
+pub const BASE_MOVEMENT_COST: i32 = 10;
+
 // UI CONSTANTS
 
 // pub trait Standards {
@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use bevy::prelude::*;
 use crate::prelude::*;
 use crate::inventory_system::*;
@@ -6,7 +7,7 @@ use crate::inventory_system::*;
 // CRAFTING SYSTEM - Raft/AoE2 style with tech tree requirements
 // ============================================================================
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CraftingRecipe {
     pub id: String,
     pub name: String,
@@ -21,7 +22,7 @@ pub struct CraftingRecipe {
     pub skill_xp_gained: i32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum CraftingStation {
     Workbench,
     Forge,
@@ -47,423 +48,115 @@ pub struct CraftingTask {
 }
 
 // ============================================================================
-// RECIPE DATABASE
+// RECIPE REGISTRY - Recipes loaded from assets/recipes
 // ============================================================================
 
-pub struct RecipeDatabase;
-
-impl RecipeDatabase {
-    pub fn get_all_recipes() -> Vec<CraftingRecipe> {
-        vec![
-            // BASIC TOOLS
-            CraftingRecipe {
-                id: "craft_wooden_club".to_string(),
-                name: "Wooden Club".to_string(),
-                description: "Craft a basic weapon".to_string(),
-                output_item: "wooden_club".to_string(),
-                output_quantity: 1,
-                required_items: vec![("wood".to_string(), 5)],
-                required_skill: None,
-                required_tech: None,
-                crafting_time: 10.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 20,
-            },
-            CraftingRecipe {
-                id: "craft_iron_axe".to_string(),
-                name: "Iron Axe".to_string(),
-                description: "Craft an efficient chopping tool".to_string(),
-                output_item: "iron_axe".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("iron_bar".to_string(), 3),
-                    ("wood".to_string(), 2),
-                ],
-                required_skill: Some(("crafting".to_string(), 3)),
-                required_tech: Some("ironworking".to_string()),
-                crafting_time: 30.0,
-                crafting_station: Some(CraftingStation::Forge),
-                skill_xp_gained: 50,
-            },
-            CraftingRecipe {
-                id: "craft_iron_pickaxe".to_string(),
-                name: "Iron Pickaxe".to_string(),
-                description: "Craft a mining tool".to_string(),
-                output_item: "iron_pickaxe".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("iron_bar".to_string(), 4),
-                    ("wood".to_string(), 2),
-                ],
-                required_skill: Some(("crafting".to_string(), 3)),
-                required_tech: Some("ironworking".to_string()),
-                crafting_time: 35.0,
-                crafting_station: Some(CraftingStation::Forge),
-                skill_xp_gained: 50,
-            },
-            CraftingRecipe {
-                id: "craft_fishing_rod".to_string(),
-                name: "Fishing Rod".to_string(),
-                description: "Craft a fishing tool".to_string(),
-                output_item: "fishing_rod".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("wood".to_string(), 3),
-                    ("cloth".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 1)),
-                required_tech: None,
-                crafting_time: 15.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 30,
-            },
-
-            // WEAPONS
-            CraftingRecipe {
-                id: "craft_plasma_lance".to_string(),
-                name: "Plasma Lance".to_string(),
-                description: "Craft a long-reach energy weapon".to_string(),
-                output_item: "plasma_lance".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("wood".to_string(), 2),
-                    ("iron_bar".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 2)),
-                required_tech: None,
-                crafting_time: 20.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 40,
-            },
-            CraftingRecipe {
-                id: "craft_combat_blade".to_string(),
-                name: "Combat Blade".to_string(),
-                description: "Craft a sturdy tactical blade".to_string(),
-                output_item: "combat_blade".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("iron_bar".to_string(), 5),
-                    ("leather".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 5)),
-                required_tech: Some("ironworking".to_string()),
-                crafting_time: 60.0,
-                crafting_station: Some(CraftingStation::Forge),
-                skill_xp_gained: 100,
-            },
-            CraftingRecipe {
-                id: "craft_power_axe".to_string(),
-                name: "Power Axe".to_string(),
-                description: "Craft a powered combat axe".to_string(),
-                output_item: "power_axe".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("iron_bar".to_string(), 6),
-                    ("wood".to_string(), 3),
-                ],
-                required_skill: Some(("crafting".to_string(), 7)),
-                required_tech: Some("advanced_weaponry".to_string()),
-                crafting_time: 80.0,
-                crafting_station: Some(CraftingStation::Forge),
-                skill_xp_gained: 150,
-            },
-            CraftingRecipe {
-                id: "craft_railgun".to_string(),
-                name: "Railgun".to_string(),
-                description: "Craft a ballistic ranged weapon".to_string(),
-                output_item: "railgun".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("wood".to_string(), 4),
-                    ("cloth".to_string(), 2),
-                ],
-                required_skill: Some(("crafting".to_string(), 4)),
-                required_tech: Some("marksmanship".to_string()),
-                crafting_time: 45.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 80,
-            },
-            CraftingRecipe {
-                id: "craft_gauss_rifle".to_string(),
-                name: "Gauss Rifle".to_string(),
-                description: "Craft a powerful gauss rifle".to_string(),
-                output_item: "gauss_rifle".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("wood".to_string(), 5),
-                    ("iron_bar".to_string(), 4),
-                    ("cloth".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 8)),
-                required_tech: Some("precision_weapons".to_string()),
-                crafting_time: 120.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 200,
-            },
-
-            // AMMO
-            CraftingRecipe {
-                id: "craft_railgun_slugs".to_string(),
-                name: "Railgun Slugs".to_string(),
-                description: "Craft ammunition for railguns".to_string(),
-                output_item: "railgun_slug".to_string(),
-                output_quantity: 10,
-                required_items: vec![
-                    ("wood".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 2)),
-                required_tech: Some("marksmanship".to_string()),
-                crafting_time: 10.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 10,
-            },
-            CraftingRecipe {
-                id: "craft_gauss_charges".to_string(),
-                name: "Gauss Charges".to_string(),
-                description: "Craft charges for gauss rifles".to_string(),
-                output_item: "gauss_charge".to_string(),
-                output_quantity: 10,
-                required_items: vec![
-                    ("wood".to_string(), 1),
-                    ("iron_bar".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 4)),
-                required_tech: Some("precision_weapons".to_string()),
-                crafting_time: 15.0,
-                crafting_station: Some(CraftingStation::Workbench),
-                skill_xp_gained: 15,
-            },
-
-            // ARMOR
-            CraftingRecipe {
-                id: "craft_leather_helmet".to_string(),
-                name: "Leather Helmet".to_string(),
-                description: "Craft basic head protection".to_string(),
-                output_item: "leather_helmet".to_string(),
-                output_quantity: 1,
-                required_items: vec![("leather".to_string(), 3)],
-                required_skill: Some(("crafting".to_string(), 2)),
-                required_tech: None,
-                crafting_time: 25.0,
-                crafting_station: Some(CraftingStation::TanningRack),
-                skill_xp_gained: 40,
-            },
-            CraftingRecipe {
-                id: "craft_leather_armor".to_string(),
-                name: "Leather Armor".to_string(),
-                description: "Craft light body armor".to_string(),
-                output_item: "leather_armor".to_string(),
-                output_quantity: 1,
-                required_items: vec![("leather".to_string(), 8)],
-                required_skill: Some(("crafting".to_string(), 3)),
-                required_tech: None,
-                crafting_time: 50.0,
-                crafting_station: Some(CraftingStation::TanningRack),
-                skill_xp_gained: 80,
-            },
-            CraftingRecipe {
-                id: "craft_iron_helmet".to_string(),
-                name: "Iron Helmet".to_string(),
-                description: "Craft strong head protection".to_string(),
-                output_item: "iron_helmet".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("iron_bar".to_string(), 5),
-                    ("leather".to_string(), 1),
-                ],
-                required_skill: Some(("crafting".to_string(), 6)),
-                required_tech: Some("ironworking".to_string()),
-                crafting_time: 70.0,
-                crafting_station: Some(CraftingStation::Forge),
-                skill_xp_gained: 120,
-            },
-            CraftingRecipe {
-                id: "craft_iron_armor".to_string(),
-                name: "Iron Armor".to_string(),
-                description: "Craft heavy body armor".to_string(),
-                output_item: "iron_armor".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("iron_bar".to_string(), 15),
-                    ("leather".to_string(), 3),
-                ],
-                required_skill: Some(("crafting".to_string(), 8)),
-                required_tech: Some("ironworking".to_string()),
-                crafting_time: 150.0,
-                crafting_station: Some(CraftingStation::Forge),
-                skill_xp_gained: 250,
-            },
-
-            // CLOTHING (The Long Dark style)
-            CraftingRecipe {
-                id: "craft_wool_hat".to_string(),
-                name: "Wool Hat".to_string(),
-                description: "Craft warm headwear".to_string(),
-                output_item: "wool_hat".to_string(),
-                output_quantity: 1,
-                required_items: vec![("cloth".to_string(), 3)],
-                required_skill: Some(("crafting".to_string(), 1)),
-                required_tech: None,
-                crafting_time: 30.0,
-                crafting_station: Some(CraftingStation::Loom),
-                skill_xp_gained: 30,
-            },
-            CraftingRecipe {
-                id: "craft_winter_coat".to_string(),
-                name: "Winter Coat".to_string(),
-                description: "Craft heavy winter protection".to_string(),
-                output_item: "winter_coat".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("cloth".to_string(), 10),
-                    ("leather".to_string(), 2),
-                ],
-                required_skill: Some(("crafting".to_string(), 5)),
-                required_tech: None,
-                crafting_time: 120.0,
-                crafting_station: Some(CraftingStation::Loom),
-                skill_xp_gained: 150,
-            },
-            CraftingRecipe {
-                id: "craft_leather_gloves".to_string(),
-                name: "Leather Gloves".to_string(),
-                description: "Craft hand protection".to_string(),
-                output_item: "leather_gloves".to_string(),
-                output_quantity: 1,
-                required_items: vec![("leather".to_string(), 2)],
-                required_skill: Some(("crafting".to_string(), 2)),
-                required_tech: None,
-                crafting_time: 20.0,
-                crafting_station: Some(CraftingStation::TanningRack),
-                skill_xp_gained: 30,
-            },
-            CraftingRecipe {
-                id: "craft_boots".to_string(),
-                name: "Leather Boots".to_string(),
-                description: "Craft sturdy footwear".to_string(),
-                output_item: "boots".to_string(),
-                output_quantity: 1,
-                required_items: vec![("leather".to_string(), 4)],
-                required_skill: Some(("crafting".to_string(), 3)),
-                required_tech: None,
-                crafting_time: 40.0,
-                crafting_station: Some(CraftingStation::TanningRack),
-                skill_xp_gained: 50,
-            },
-
-            // RESOURCES
-            CraftingRecipe {
-                id: "smelt_iron_bar".to_string(),
-                name: "Smelt Iron Bar".to_string(),
-                description: "Smelt ore into iron bars".to_string(),
-                output_item: "iron_bar".to_string(),
-                output_quantity: 1,
-                required_items: vec![("iron_ore".to_string(), 2)],
-                required_skill: Some(("crafting".to_string(), 2)),
-                required_tech: Some("smelting".to_string()),
-                crafting_time: 20.0,
-                crafting_station: Some(CraftingStation::Furnace),
-                skill_xp_gained: 20,
-            },
-            CraftingRecipe {
-                id: "craft_cloth".to_string(),
-                name: "Weave Cloth".to_string(),
-                description: "Weave plant fibers into cloth".to_string(),
-                output_item: "cloth".to_string(),
-                output_quantity: 1,
-                required_items: vec![("wood".to_string(), 3)], // Placeholder - should be plant fibers
-                required_skill: Some(("crafting".to_string(), 1)),
-                required_tech: None,
-                crafting_time: 15.0,
-                crafting_station: Some(CraftingStation::Loom),
-                skill_xp_gained: 15,
-            },
-
-            // FOOD/CONSUMABLES
-            CraftingRecipe {
-                id: "cook_meat".to_string(),
-                name: "Cook Meat".to_string(),
-                description: "Cook raw meat".to_string(),
-                output_item: "cooked_meat".to_string(),
-                output_quantity: 1,
-                required_items: vec![("raw_meat".to_string(), 1)],
-                required_skill: Some(("cooking".to_string(), 1)),
-                required_tech: None,
-                crafting_time: 10.0,
-                crafting_station: Some(CraftingStation::CookingPot),
-                skill_xp_gained: 10,
-            },
-            CraftingRecipe {
-                id: "craft_bandage".to_string(),
-                name: "Craft Bandage".to_string(),
-                description: "Make basic medical supplies".to_string(),
-                output_item: "bandage".to_string(),
-                output_quantity: 3,
-                required_items: vec![("cloth".to_string(), 1)],
-                required_skill: Some(("crafting".to_string(), 1)),
-                required_tech: None,
-                crafting_time: 5.0,
-                crafting_station: None,
-                skill_xp_gained: 10,
-            },
-            CraftingRecipe {
-                id: "craft_medkit".to_string(),
-                name: "Craft Medkit".to_string(),
-                description: "Synthesize a medical nanite injector".to_string(),
-                output_item: "medkit".to_string(),
-                output_quantity: 1,
-                required_items: vec![
-                    ("cloth".to_string(), 1),
-                    ("wood".to_string(), 2), // Placeholder - should be chemicals
-                ],
-                required_skill: Some(("crafting".to_string(), 5)),
-                required_tech: Some("biochemistry".to_string()),
-                crafting_time: 30.0,
-                crafting_station: Some(CraftingStation::ResearchLab),
-                skill_xp_gained: 50,
-            },
-            CraftingRecipe {
-                id: "bake_bread".to_string(),
-                name: "Bake Bread".to_string(),
-                description: "Bake bread from grain".to_string(),
-                output_item: "bread".to_string(),
-                output_quantity: 2,
-                required_items: vec![("wood".to_string(), 1)], // Placeholder - should be grain/flour
-                required_skill: Some(("cooking".to_string(), 2)),
-                required_tech: None,
-                crafting_time: 15.0,
-                crafting_station: Some(CraftingStation::CookingPot),
-                skill_xp_gained: 15,
-            },
-        ]
+pub const RECIPE_DIRECTORY: &str = "assets/recipes";
+
+#[derive(Resource, Default)]
+pub struct RecipeRegistry {
+    recipes: HashMap<String, CraftingRecipe>,
+    ids: Vec<String>,
+}
+
+impl RecipeRegistry {
+    // Every .ron file in the directory holds a list of recipes. Item, tech and skill
+    // references are checked here so a typo fails at startup instead of mid-game, and so
+    // are the recipes techs unlock.
+    pub fn load(directory: &Path, items: &ItemRegistry, tech_tree: &TechnologyTree) -> Result<Self, Vec<String>> {
+        let files = read_ron_files::<Vec<CraftingRecipe>>(directory)?;
+        let mut registry = RecipeRegistry::default();
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut errors = Vec::new();
+        for (path, recipes) in files {
+            for recipe in recipes {
+                let recipe_errors = recipe.validate(items, tech_tree);
+                if !recipe_errors.is_empty() {
+                    for e in recipe_errors {
+                        errors.push(format!("{}: recipe '{}': {}", path.display(), recipe.id, e));
+                    }
+                    continue;
+                }
+                if let Some(first) = sources.get(&recipe.id) {
+                    errors.push(format!("{}: recipe '{}': field `id` is already defined in {}", path.display(), recipe.id, first.display()));
+                    continue;
+                }
+                sources.insert(recipe.id.clone(), path.clone());
+                registry.ids.push(recipe.id.clone());
+                registry.recipes.insert(recipe.id.clone(), recipe);
+            }
+        }
+        for tech in &tech_tree.available_techs {
+            for unlock in &tech.unlocks {
+                if let Unlock::Recipe(recipe_id) = unlock {
+                    if registry.get(recipe_id).is_none() {
+                        errors.push(format!("{}: tech '{}': field `unlocks` names unknown recipe '{}'", TECH_DIRECTORY, tech.id, recipe_id));
+                    }
+                }
+            }
+        }
+        if errors.is_empty() { Ok(registry) } else { Err(errors) }
     }
 
-    pub fn get_recipe(recipe_id: &str) -> Option<CraftingRecipe> {
-        Self::get_all_recipes()
-            .into_iter()
-            .find(|r| r.id == recipe_id)
+    pub fn get(&self, recipe_id: &str) -> Option<&CraftingRecipe> {
+        self.recipes.get(recipe_id)
+    }
+
+    // In load order.
+    pub fn all(&self) -> impl Iterator<Item = &CraftingRecipe> {
+        self.ids.iter().map(|id| &self.recipes[id])
+    }
+}
+
+impl CraftingRecipe {
+    fn validate(&self, items: &ItemRegistry, tech_tree: &TechnologyTree) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.id.is_empty() {
+            errors.push("field `id` is empty".to_string());
+        }
+        if !items.contains(&self.output_item) {
+            errors.push(format!("field `output_item` names unknown item '{}'", self.output_item));
+        }
+        if self.output_quantity == 0 {
+            errors.push("field `output_quantity` must be at least 1".to_string());
+        }
+        for (item_id, _) in &self.required_items {
+            if !items.contains(item_id) {
+                errors.push(format!("field `required_items` names unknown item '{}'", item_id));
+            }
+        }
+        if let Some((skill_name, _)) = &self.required_skill {
+            if Skillset::default().get(skill_name).is_none() {
+                errors.push(format!("field `required_skill` names unknown skill '{}'", skill_name));
+            }
+        }
+        if let Some(tech_id) = &self.required_tech {
+            if tech_tree.get(tech_id).is_none() {
+                errors.push(format!("field `required_tech` names unknown tech '{}'", tech_id));
+            }
+        }
+        if self.crafting_time < 0.0 {
+            errors.push(format!("field `crafting_time` is {}, it can't be negative", self.crafting_time));
+        }
+        errors
     }
 
     pub fn can_craft(
-        recipe: &CraftingRecipe,
+        &self,
         inventory: &Inventory,
         skillset: &Skillset,
-        unlocked_techs: &Vec<String>,
+        unlocked_techs: &[String],
     ) -> (bool, String) {
         // Check tech requirement
-        if let Some(required_tech) = &recipe.required_tech {
+        if let Some(required_tech) = &self.required_tech {
             if !unlocked_techs.contains(required_tech) {
                 return (false, format!("Requires technology: {}", required_tech));
             }
         }
 
         // Check skill requirement
-        if let Some((skill_name, required_level)) = &recipe.required_skill {
-            let skill_level = match skill_name.as_str() {
-                "crafting" => skillset.crafting.level(),
-                "cooking" => skillset.cooking.level(),
-                _ => 0,
-            };
+        if let Some((skill_name, required_level)) = &self.required_skill {
+            let skill_level = skillset.get(skill_name).map_or(0, |skill| skill.level());
             if skill_level < *required_level {
                 return (
                     false,
@@ -473,7 +166,7 @@ impl RecipeDatabase {
         }
 
         // Check materials
-        for (item_id, required_qty) in &recipe.required_items {
+        for (item_id, required_qty) in &self.required_items {
             if !inventory.has_item(item_id, *required_qty) {
                 return (
                     false,
@@ -509,18 +202,22 @@ pub struct CraftingPlugin;
 
 impl Plugin for CraftingPlugin {
     fn build(&self, app: &mut App) {
+        let registry = RecipeRegistry::load(
+            &asset_path(RECIPE_DIRECTORY),
+            app.world.resource::<ItemRegistry>(),
+            app.world.resource::<TechnologyTree>(),
+        ).unwrap_or_else(|errors| exit_with_data_errors("recipes", &errors));
+        app.insert_resource(registry);
         app.add_systems(
-            Update,
-            crafting_progress_system.run_if(bevy::time::common_conditions::on_timer(
-                bevy::utils::Duration::from_secs_f32(1.0),
-            )),
+            SimTick,
+            crafting_progress_system.run_if(every_seconds(1.0)),
         );
     }
 }
 
 fn crafting_progress_system(
     mut query: Query<(&mut CraftingQueue, &mut Inventory, &mut PhysicalBody)>,
-    time: Res<Time>,
+    items: Res<ItemRegistry>,
 ) {
     for (mut queue, mut inventory, mut body) in query.iter_mut() {
         if queue.queue.is_empty() {
@@ -559,7 +256,7 @@ fn crafting_progress_system(
         // Complete the task
         if task.time_remaining <= 0.0 {
             // Add output item
-            if let Some(item) = ItemDatabase::create_item(
+            if let Some(item) = items.create_item(
                 &task.recipe.output_item,
                 task.recipe.output_quantity,
             ) {
@@ -574,3 +271,75 @@ fn crafting_progress_system(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe(id: &str, output_item: &str, output_quantity: u32, required_item: &str, skill: &str, tech: &str) -> String {
+        format!(
+            "(id: \"{}\", name: \"{}\", description: \"\", output_item: \"{}\", output_quantity: {}, \
+             required_items: [(\"{}\", 1)], required_skill: Some((\"{}\", 1)), required_tech: Some(\"{}\"), \
+             crafting_time: 5.0, crafting_station: None, skill_xp_gained: 5)",
+            id, id, output_item, output_quantity, required_item, skill, tech,
+        )
+    }
+
+    fn shipped_items_and_techs() -> (ItemRegistry, TechnologyTree) {
+        (
+            ItemRegistry::load(&asset_path(ITEM_DIRECTORY)).unwrap(),
+            TechnologyTree::load(&asset_path(TECH_DIRECTORY)).unwrap(),
+        )
+    }
+
+    #[test]
+    fn shipped_recipes_load() {
+        let (items, tech_tree) = shipped_items_and_techs();
+        let recipes = RecipeRegistry::load(&asset_path(RECIPE_DIRECTORY), &items, &tech_tree).unwrap();
+        assert!(recipes.all().next().is_some());
+    }
+
+    #[test]
+    fn recipe_references_are_checked() {
+        let (items, mut tech_tree) = shipped_items_and_techs();
+        // The shipped techs unlock shipped recipes, which aren't in the scratch directory.
+        for tech in &mut tech_tree.available_techs {
+            tech.unlocks.clear();
+        }
+        let directory = scratch_directory("recipes-bad", &[
+            ("a.ron", &format!("[{}, {}]",
+                recipe("slugs", "railgun_slug", 10, "wood", "crafting", "marksmanship"),
+                recipe("gold", "gold_bar", 0, "lead", "alchemy", "philosophy"),
+            )),
+            ("b.ron", &format!("[{}]", recipe("slugs", "railgun_slug", 5, "wood", "crafting", "marksmanship"))),
+        ]);
+        let errors = RecipeRegistry::load(&directory, &items, &tech_tree).err().expect("recipes should not load");
+        let expected = [
+            "a.ron: recipe 'gold': field `output_item` names unknown item 'gold_bar'",
+            "a.ron: recipe 'gold': field `output_quantity` must be at least 1",
+            "a.ron: recipe 'gold': field `required_items` names unknown item 'lead'",
+            "a.ron: recipe 'gold': field `required_skill` names unknown skill 'alchemy'",
+            "a.ron: recipe 'gold': field `required_tech` names unknown tech 'philosophy'",
+            "b.ron: recipe 'slugs': field `id` is already defined in",
+        ];
+        assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+        for error in expected {
+            assert!(errors.iter().any(|e| e.contains(error)), "missing {:?} in {:?}", error, errors);
+        }
+    }
+
+    #[test]
+    fn unknown_recipe_unlocks_are_rejected() {
+        let (items, mut tech_tree) = shipped_items_and_techs();
+        for tech in &mut tech_tree.available_techs {
+            tech.unlocks.clear();
+        }
+        tech_tree.available_techs[0].unlocks = vec![Unlock::Recipe("slugs".to_string()), Unlock::Recipe("craft_spear".to_string())];
+        let directory = scratch_directory("recipes-unlocked", &[
+            ("a.ron", &format!("[{}]", recipe("slugs", "railgun_slug", 10, "wood", "crafting", "marksmanship"))),
+        ]);
+        let errors = RecipeRegistry::load(&directory, &items, &tech_tree).err().expect("recipes should not load");
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("field `unlocks` names unknown recipe 'craft_spear'"), "{}", errors[0]);
+    }
+}
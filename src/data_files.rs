@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use serde::de::DeserializeOwned;
+
+// ============================================================================
+// DATA FILES - Game definitions loaded from assets/
+// ============================================================================
+
+// Finds a file shipped under assets/ much as Bevy's asset server does: under BEVY_ASSET_ROOT
+// or CARGO_MANIFEST_DIR if set, next to the executable, or else in the working directory.
+// The game can then be started from anywhere.
+pub fn asset_path(relative: &str) -> PathBuf {
+    let roots = [
+        std::env::var_os("BEVY_ASSET_ROOT").map(PathBuf::from),
+        std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from),
+        std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)),
+    ];
+    roots.into_iter().flatten()
+        .map(|root| root.join(relative))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from(relative))
+}
+
+// Parses every .ron file in `directory`, sorted by file name so the load order is stable.
+// Errors are collected across all files and name the file, line and column.
+pub fn read_ron_files<T: DeserializeOwned>(directory: &Path) -> Result<Vec<(PathBuf, T)>, Vec<String>> {
+    let entries = std::fs::read_dir(directory)
+        .map_err(|e| vec![format!("{}: {}", directory.display(), e)])?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        match read_ron_file(&path) {
+            Ok(contents) => files.push((path, contents)),
+            Err(e) => errors.push(e),
+        }
+    }
+    if errors.is_empty() { Ok(files) } else { Err(errors) }
+}
+
+pub fn read_ron_file<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    ron::from_str(&text).map_err(|e| format!("{}:{}", path.display(), e))
+}
+
+// Bad data is a startup error, not something to limp along with.
+pub fn exit_with_data_errors(what: &str, errors: &[String]) -> ! {
+    eprintln!("Could not load {}:", what);
+    for error in errors {
+        eprintln!("  {}", error);
+    }
+    std::process::exit(1);
+}
+
+// Writes `files` into a fresh directory under the system temp dir, for loading in tests.
+#[cfg(test)]
+pub fn scratch_directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("colony-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    for (file_name, contents) in files {
+        std::fs::write(directory.join(file_name), contents).unwrap();
+    }
+    directory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ron_errors_name_the_file_and_line() {
+        let directory = scratch_directory("ron-errors", &[
+            ("a.ron", "[1, 2, 3]"),
+            ("b.ron", "[1,\n 2,\n \"three\"]"),
+            ("notes.txt", "not ron at all"),
+        ]);
+        let errors = read_ron_files::<Vec<u32>>(&directory).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("b.ron:3:"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn ron_files_load_in_file_name_order() {
+        let directory = scratch_directory("ron-order", &[("b.ron", "[2]"), ("a.ron", "[1]")]);
+        let files = read_ron_files::<Vec<u32>>(&directory).unwrap();
+        assert_eq!(files.into_iter().flat_map(|(_, numbers)| numbers).collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
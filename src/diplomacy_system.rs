@@ -6,7 +6,7 @@ use std::collections::HashMap;
 // DIPLOMACY SYSTEM - MOO2/Civilization style
 // ============================================================================
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct DiplomacyState {
     pub factions: HashMap<u32, Faction>,
     pub relationships: HashMap<(u32, u32), Relationship>,
@@ -25,7 +25,7 @@ impl Default for DiplomacyState {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Faction {
     pub id: u32,
     pub name: String,
@@ -39,7 +39,7 @@ pub struct Faction {
     pub territory_size: i32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum AIPersonality {
     Aggressive,  // Likes war, dislikes peace
     Peaceful,    // Prefers diplomacy
@@ -49,7 +49,7 @@ pub enum AIPersonality {
     Opportunist, // Adapts to situation
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Relationship {
     pub faction1: u32,
     pub faction2: u32,
@@ -58,7 +58,7 @@ pub struct Relationship {
     pub history: Vec<DiplomaticEvent>,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum DiplomaticStatus {
     War,
     Hostile,
@@ -68,14 +68,14 @@ pub enum DiplomaticStatus {
     Vassal, // One faction is subordinate to another
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DiplomaticEvent {
     pub event_type: DiplomaticEventType,
     pub opinion_change: i32,
     pub turn: i32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum DiplomaticEventType {
     DeclaredWar,
     MadePeace,
@@ -93,7 +93,7 @@ pub enum DiplomaticEventType {
 // TREATIES
 // ============================================================================
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Treaty {
     pub treaty_id: u32,
     pub treaty_type: TreatyType,
@@ -103,7 +103,7 @@ pub struct Treaty {
     pub active: bool,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum TreatyType {
     Peace,
     Alliance,
@@ -115,7 +115,7 @@ pub enum TreatyType {
     Vassalization,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum TreatyTerm {
     NoCombat,
     SharedVision,
@@ -130,7 +130,7 @@ pub enum TreatyTerm {
 // TRADE SYSTEM
 // ============================================================================
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TradeRoute {
     pub route_id: u32,
     pub from_faction: u32,
@@ -194,15 +194,13 @@ impl Plugin for DiplomacyPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(DiplomacyState::default())
             .add_systems(
-                Update,
+                SimTick,
                 (
                     update_relationships_system,
                     process_trade_routes,
                     ai_diplomacy_decisions,
                 )
-                    .run_if(bevy::time::common_conditions::on_timer(
-                        bevy::utils::Duration::from_secs(5),
-                    )),
+                    .run_if(every_seconds(5.0)),
             );
     }
 }
@@ -232,12 +230,14 @@ fn process_trade_routes(
     mut diplomacy: ResMut<DiplomacyState>,
     mut market_query: Query<&mut Market>,
 ) {
+    let diplomacy = &mut *diplomacy;
     for route in &mut diplomacy.trade_routes {
         if !route.active {
             continue;
         }
 
         // Transfer resources
+        let mut shipped = false;
         if let Some(from_faction) = diplomacy.factions.get_mut(&route.from_faction) {
             if let Some(amount) = from_faction.resources.get_mut(&route.resource) {
                 if *amount >= route.amount_per_turn {
@@ -246,18 +246,21 @@ fn process_trade_routes(
                     // Add profit to from faction
                     let gold = from_faction.resources.entry("gold".to_string()).or_insert(0);
                     *gold += route.profit_per_turn;
-
-                    // Give resources to to faction
-                    if let Some(to_faction) = diplomacy.factions.get_mut(&route.to_faction) {
-                        let resource = to_faction
-                            .resources
-                            .entry(route.resource.clone())
-                            .or_insert(0);
-                        *resource += route.amount_per_turn;
-                    }
+                    shipped = true;
                 }
             }
         }
+
+        // Give resources to to faction
+        if shipped {
+            if let Some(to_faction) = diplomacy.factions.get_mut(&route.to_faction) {
+                let resource = to_faction
+                    .resources
+                    .entry(route.resource.clone())
+                    .or_insert(0);
+                *resource += route.amount_per_turn;
+            }
+        }
     }
 }
 
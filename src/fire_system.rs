@@ -0,0 +1,304 @@
+use std::collections::HashSet;
+use crate::prelude::*;
+
+// ============================================================================
+// FIRE - Burning trees, wood and buildings, and what sets them alight
+// ============================================================================
+
+pub const FIRE_UPDATE_SECONDS: f32 = 1.0;
+// Intensity of a fire that has just caught. It grows by FIRE_GROWTH each update up to 1.0.
+const IGNITION_INTENSITY: f32 = 0.2;
+const FIRE_GROWTH: f32 = 0.1;
+// Fuel burned each update by a fire at full intensity.
+const BURN_RATE: f32 = 2.0;
+// Intensity a downpour (precipitation 1.0) takes off each update. Roofs keep the rain off.
+const RAIN_SUPPRESSION: f32 = 0.4;
+// Chance each update that a full-intensity fire catches a flammable neighbour in still air.
+const SPREAD_CHANCE: f64 = 0.15;
+// Wind of this speed doubles the chance of catching straight downwind, and stops it upwind.
+const WIND_SPREAD_SPEED: f32 = 20.0;
+// Only a strong downwind alignment carries sparks to the second tile out.
+const LEAP_ALIGNMENT: f32 = 0.7;
+// Chance each update in a thunderstorm that lightning sets something outdoors alight.
+const LIGHTNING_CHANCE: f64 = 0.05;
+// Chance each update that a campfire catches something flammable right beside it.
+const CAMPFIRE_SPARK_CHANCE: f64 = 0.02;
+// Fires this strong burn anyone standing beside them, not just in them.
+pub const SCORCHING_INTENSITY: f32 = 0.6;
+const BURN_DAMAGE: f32 = 6.0;
+const MAX_BURN_SEVERITY: i32 = 10;
+// Degrees a full-intensity fire adds to its tile each heat update.
+pub const FIRE_HEAT: f32 = 8.0;
+const FIRE_TINT: Color = Color::rgb(1.0, 0.45, 0.2);
+
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub struct Fire {
+    pub fuel: f32,
+    pub intensity: f32, // 0.0 to 1.0
+}
+
+// Left on something once its fire is put out, so it doesn't catch again with all its fuel.
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub struct Charred {
+    pub fuel: f32,
+}
+
+// How much there is to burn in something, or None if it doesn't burn.
+pub fn fuel(object: Option<&Object>, building: Option<&Building>, logs: Option<&Logs>, charred: Option<&Charred>) -> Option<f32> {
+    if let Some(charred) = charred { return Some(charred.fuel); }
+    if building.is_some() { return Some(100.0); }
+    if logs.is_some() { return Some(20.0); }
+    let itemtype = object?.itemtype;
+    if itemtype == ItemType::WallWood { return Some(60.0); }
+    if itemtype.is_choppable().0.is_some() { return Some(40.0); }
+    None
+}
+
+pub struct FirePlugin;
+
+impl Plugin for FirePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(
+            SimTick,
+            (ignite_fires, burn, burn_units).chain()
+            .run_if(every_seconds(FIRE_UPDATE_SECONDS))
+        )
+        .add_systems(SimTick, restore_extinguished_sprites)
+        ;
+    }
+}
+
+type Flammables<'w, 's> = Query<'w, 's, (Entity, &'static Position, Option<&'static Object>, Option<&'static Building>, Option<&'static Logs>, Option<&'static Charred>), Without<Fire>>;
+
+fn ignite(commands: &mut Commands, entity: Entity, fuel: f32) {
+    commands.entity(entity).insert(Fire { fuel, intensity: IGNITION_INTENSITY });
+}
+
+pub fn put_out(commands: &mut Commands, entity: Entity, fire: &Fire) {
+    commands.entity(entity).remove::<Fire>().insert(Charred { fuel: fire.fuel });
+}
+
+// Lightning strikes something flammable out in the open; campfires built too close to
+// something flammable can set it alight.
+pub fn ignite_fires(
+    mut commands: Commands,
+    flammables: Flammables,
+    campfires: Query<(&Object, &Position)>,
+    weather: Res<Weather>,
+    rooms: Res<crate::Rooms>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = game_rng.stream(RngStream::Fire);
+    if weather.current_weather == WeatherType::Thunderstorm && rng.gen_bool(LIGHTNING_CHANCE) {
+        let outdoors = flammables.iter()
+            .filter(|(_, position, _, _, _, _)| position.z == 0 && !rooms.room_at(position).is_some_and(|room| room.roofed))
+            .filter_map(|(entity, _, object, building, logs, charred)| Some((entity, fuel(object, building, logs, charred)?)))
+            .collect::<Vec<_>>();
+        if let Some(&(entity, fuel)) = outdoors.choose(rng) {
+            ignite(&mut commands, entity, fuel);
+        }
+    }
+    let campfire_positions = campfires.iter()
+        .filter(|(object, _)| object.itemtype == ItemType::Campfire)
+        .map(|(_, position)| *position)
+        .collect::<Vec<_>>();
+    if campfire_positions.is_empty() { return; }
+    for (entity, position, object, building, logs, charred) in flammables.iter() {
+        let Some(fuel) = fuel(object, building, logs, charred) else { continue; };
+        let beside_campfire = campfire_positions.iter().any(|campfire| campfire.z == position.z && campfire.distance(position) <= 1);
+        if beside_campfire && rng.gen_bool(CAMPFIRE_SPARK_CHANCE) {
+            ignite(&mut commands, entity, fuel);
+        }
+    }
+}
+
+// Fires grow, eat their fuel and catch their neighbours, downwind most of all. Rain puts them
+// out; once the fuel is gone, whatever was burning is gone too.
+#[allow(clippy::too_many_arguments)]
+pub fn burn(
+    mut commands: Commands,
+    mut fires: Query<(Entity, &Position, &mut Fire)>,
+    flammables: Flammables,
+    mut sprites: Query<&mut TextureAtlasSprite>,
+    weather: Res<Weather>,
+    rooms: Res<crate::Rooms>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if fires.is_empty() { return; }
+    let rng = game_rng.stream(RngStream::Fire);
+    let wind = Vec2::from_angle(weather.wind_direction.to_radians());
+    let wind_strength = weather.wind_speed / WIND_SPREAD_SPEED;
+    let mut burning = vec![];
+    for (entity, position, mut fire) in fires.iter_mut() {
+        let sheltered = rooms.room_at(position).is_some_and(|room| room.roofed);
+        if !sheltered {
+            fire.intensity -= weather.precipitation * RAIN_SUPPRESSION;
+        }
+        if fire.intensity <= 0.0 {
+            put_out(&mut commands, entity, &fire);
+            continue;
+        }
+        fire.intensity = (fire.intensity + FIRE_GROWTH).min(1.0);
+        fire.fuel -= fire.intensity * BURN_RATE;
+        if fire.fuel <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color = FIRE_TINT;
+        }
+        burning.push((*position, fire.intensity));
+    }
+    let mut caught = HashSet::new();
+    for (entity, position, object, building, logs, charred) in flammables.iter() {
+        let Some(fuel) = fuel(object, building, logs, charred) else { continue; };
+        for (fire_position, intensity) in burning.iter() {
+            if fire_position.z != position.z || caught.contains(&entity) { continue; }
+            let offset = Vec2::new((position.x - fire_position.x) as f32, (position.y - fire_position.y) as f32);
+            let reach = offset.x.abs().max(offset.y.abs());
+            if reach == 0.0 || reach > 2.0 { continue; }
+            let alignment = offset.normalize().dot(wind);
+            if reach > 1.0 && alignment < LEAP_ALIGNMENT { continue; }
+            let wind_factor = (1.0 + alignment * wind_strength).max(0.0) / reach;
+            let chance = (SPREAD_CHANCE * (*intensity * wind_factor) as f64).min(1.0);
+            if rng.gen_bool(chance) {
+                caught.insert(entity);
+                ignite(&mut commands, entity, fuel);
+            }
+        }
+    }
+}
+
+// Anyone standing in a fire is burned, and so is anyone beside a scorching one. The burned
+// want to get away from it.
+pub fn burn_units(
+    fires: Query<(Entity, &Position, &Fire)>,
+    mut units: Query<(&Position, &mut PhysicalBody)>,
+) {
+    if fires.is_empty() { return; }
+    for (position, mut physical_body) in units.iter_mut() {
+        let mut heat: f32 = 0.0;
+        let mut source = None;
+        for (entity, fire_position, fire) in fires.iter() {
+            if fire_position.z != position.z { continue; }
+            let distance = fire_position.distance(position);
+            let exposure = match distance {
+                0 => fire.intensity,
+                1 if fire.intensity >= SCORCHING_INTENSITY => fire.intensity * 0.5,
+                _ => continue,
+            };
+            if exposure > heat {
+                heat = exposure;
+                source = Some(entity);
+            }
+        }
+        if source.is_none() { continue; }
+        physical_body.attributes.health -= (heat * BURN_DAMAGE).ceil() as i32;
+        let burn = physical_body.afflictions.iter_mut()
+            .find(|affliction| matches!(affliction.affliction_type, AfflictionType::Burned));
+        match burn {
+            Some(burn) => {
+                burn.severity = (burn.severity + 1).min(MAX_BURN_SEVERITY);
+                burn.worsening = true;
+            }
+            None => physical_body.afflictions.push(Affliction {
+                affliction_type: AfflictionType::Burned,
+                affliction_location: AfflictionLocation::Torso,
+                duration: 0,
+                severity: 1,
+                worsening: true,
+            }),
+        }
+        if physical_body.danger.as_ref().is_none_or(|danger| danger.danger_type == DangerType::Fire) {
+            physical_body.danger = Some(Danger { danger_type: DangerType::Fire, danger_source: source });
+        }
+    }
+}
+
+fn restore_extinguished_sprites(
+    mut extinguished: RemovedComponents<Fire>,
+    mut sprites: Query<&mut TextureAtlasSprite>,
+) {
+    for entity in extinguished.iter() {
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color = Color::WHITE;
+        }
+    }
+}
+
+// Where to stand to fight a fire: the closest open tile beside it that isn't burning.
+pub fn firefighting_spot(tile_map: &TileMap, burning: &[Position], from: &Position, fire: &Position) -> Option<Position> {
+    (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+        .filter(|&(dx, dy)| dx != 0 || dy != 0)
+        .map(|(dx, dy)| Position { x: fire.x + dx, y: fire.y + dy, z: fire.z })
+        .filter(|spot| !tile_map.is_wall(spot) && !burning.contains(spot))
+        .min_by_key(|spot| from.distance(spot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { x, y, z: 0 }
+    }
+
+    // A log pile burning at full strength at (5, 5), with more logs at `around`.
+    fn fire_among_logs(weather: Weather, around: &[Position], seed: u64) -> (World, Entity, Vec<Entity>) {
+        let mut world = World::new();
+        world.insert_resource(weather);
+        world.init_resource::<crate::Rooms>();
+        world.insert_resource(GameRng::new(seed));
+        let fire = world.spawn((Logs, at(5, 5), Fire { fuel: 1000.0, intensity: 1.0 })).id();
+        let logs = around.iter().map(|position| world.spawn((Logs, *position)).id()).collect();
+        (world, fire, logs)
+    }
+
+    fn burn_once(world: &mut World) {
+        let mut schedule = Schedule::new();
+        schedule.add_systems(burn);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn fire_spreads_to_what_burns_beside_it() {
+        let still_air = Weather { wind_speed: 0.0, ..default() };
+        let (mut world, _, logs) = fire_among_logs(still_air, &[at(5, 6)], 1);
+        let mut updates = 0;
+        while world.get::<Fire>(logs[0]).is_none() {
+            burn_once(&mut world);
+            updates += 1;
+            assert!(updates < 100, "the fire never spread");
+        }
+    }
+
+    #[test]
+    fn rain_puts_fires_out() {
+        let downpour = Weather { precipitation: 1.0, ..default() };
+        let (mut world, fire, _) = fire_among_logs(downpour, &[], 1);
+        world.get_mut::<Fire>(fire).unwrap().intensity = 0.3;
+        burn_once(&mut world);
+        assert!(world.get::<Fire>(fire).is_none());
+        assert_eq!(world.get::<Charred>(fire).map(|charred| charred.fuel), Some(1000.0));
+    }
+
+    #[test]
+    fn wind_carries_fire_downwind() {
+        // Blowing towards +x at the speed that doubles the chance downwind.
+        let gale = Weather { wind_speed: WIND_SPREAD_SPEED, wind_direction: 0.0, ..default() };
+        let around = [at(6, 5), at(4, 5), at(7, 5), at(3, 5)];
+        let mut caught = [0; 4];
+        for seed in 0..200 {
+            let (mut world, _, logs) = fire_among_logs(gale.clone(), &around, seed);
+            burn_once(&mut world);
+            for (count, log) in caught.iter_mut().zip(logs) {
+                if world.get::<Fire>(log).is_some() { *count += 1; }
+            }
+        }
+        let [downwind, upwind, leap_downwind, leap_upwind] = caught;
+        assert!(downwind > 30, "{:?}", caught);
+        assert!(leap_downwind > 0, "{:?}", caught);
+        assert_eq!((upwind, leap_upwind), (0, 0));
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+use crate::prelude::*;
+
+// ============================================================================
+// FLOW FIELDS - Shared routes for crowds heading to the same place
+// ============================================================================
+
+pub const FLOW_FIELD_UPDATE_SECONDS: f32 = 1.0;
+// Hostiles are on the move all the time, so their field is only built again once one of them
+// has got this many tiles from anywhere it was counted, or one has come or gone.
+const HOSTILE_DRIFT: i32 = 3;
+
+// Somewhere many units may be heading at once.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FlowGoal {
+    // A single spot, such as a squad's rally point.
+    Point(Position),
+    // Any tile of a kind of zone: Storage for the stockpile, Meeting for a safe place to gather.
+    Zone(ZoneType),
+    // Whoever can be attacked, wherever they are.
+    NearestHostile,
+}
+
+impl FlowGoal {
+    // How close a unit has to get. Hostiles are walked up to, not onto.
+    fn reach(&self) -> i32 {
+        match self {
+            FlowGoal::NearestHostile => 1,
+            _ => 0,
+        }
+    }
+}
+
+// Follows the shared field to a goal instead of planning its own path. Pathing, if the unit
+// has any, comes first. Taken off once the unit arrives or the goal is out of reach.
+#[derive(Component)]
+pub struct FollowFlowField {
+    pub goal: FlowGoal,
+    // Movement built up towards the next step, as in Pathing.
+    pub progress: i32,
+}
+
+impl FollowFlowField {
+    pub fn new(goal: FlowGoal) -> Self {
+        FollowFlowField { goal, progress: 0 }
+    }
+}
+
+struct BuiltField {
+    field: crate::FlowField,
+    goals: Vec<Position>,
+}
+
+// One field per goal and way of getting about, built while anyone is following it.
+#[derive(Resource, Default)]
+pub struct FlowFields {
+    fields: HashMap<(FlowGoal, MovementProfile), BuiltField>,
+}
+
+impl FlowFields {
+    fn get(&self, goal: FlowGoal, movement: &MovementProfile) -> Option<&BuiltField> {
+        self.fields.get(&(goal, *movement))
+    }
+}
+
+pub struct FlowFieldPlugin;
+
+impl Plugin for FlowFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<FlowFields>()
+        .add_systems(
+            SimTick,
+            update_flow_fields
+            .after(crate::update_passability)
+            .run_if(every_seconds(FLOW_FIELD_UPDATE_SECONDS))
+        )
+        .add_systems(
+            SimTick,
+            movement_along_flow_fields
+            .after(update_flow_fields)
+            .run_if(every_seconds(0.5))
+        )
+        ;
+    }
+}
+
+fn goal_positions(
+    goal: FlowGoal,
+    zones: &Query<(&Zone, &Position)>,
+    hostiles: &Query<&Position, With<Attackable>>,
+) -> Vec<Position> {
+    let mut positions = match goal {
+        FlowGoal::Point(position) => vec![position],
+        FlowGoal::Zone(zone_type) => zones.iter()
+            .filter(|(zone, _)| zone.zone_type == zone_type)
+            .map(|(_, position)| *position)
+            .collect(),
+        FlowGoal::NearestHostile => hostiles.iter().copied().collect(),
+    };
+    positions.sort_by_key(|position| (position.z, position.y, position.x));
+    positions
+}
+
+// Whether the goals have moved enough since the field was built to be worth building it again.
+fn goals_moved(goal: FlowGoal, built: &[Position], goals: &[Position]) -> bool {
+    match goal {
+        FlowGoal::NearestHostile => built.len() != goals.len() || goals.iter()
+            .any(|position| built.iter().all(|old| old.z != position.z || old.distance(position) > HOSTILE_DRIFT)),
+        _ => built != goals,
+    }
+}
+
+// Builds a field for every goal someone is following, and builds it again once the goals have
+// moved or the ground has changed. Fields nobody follows any more are dropped.
+pub fn update_flow_fields(
+    mut fields: ResMut<FlowFields>,
+    followers: Query<(&FollowFlowField, Option<&MovementProfile>)>,
+    zones: Query<(&Zone, &Position)>,
+    hostiles: Query<&Position, With<Attackable>>,
+    grid: Res<crate::PassabilityGrid>,
+    tile_map: Res<TileMap>,
+) {
+    let wanted = followers.iter()
+        .map(|(follower, movement)| (follower.goal, movement.copied().unwrap_or_default()))
+        .collect::<HashSet<_>>();
+    fields.fields.retain(|key, _| wanted.contains(key));
+    for (goal, movement) in wanted {
+        let goals = goal_positions(goal, &zones, &hostiles);
+        if let Some(built) = fields.get(goal, &movement) {
+            if !grid.is_changed() && !goals_moved(goal, &built.goals, &goals) { continue; }
+        }
+        let field = grid.flow_field(&tile_map, &goals, &movement);
+        fields.fields.insert((goal, movement), BuiltField { field, goals });
+    }
+}
+
+// Followers step down their field towards the nearest goal, paying for the ground and
+// battering gates just like units on a path.
+#[allow(clippy::too_many_arguments)]
+pub fn movement_along_flow_fields(
+    mut commands: Commands,
+    mut followers: Query<(Entity, &mut Position, &mut FollowFlowField, &mut Transform, Option<&MovementProfile>, Option<&PhysicalBody>), Without<Pathing>>,
+    mut gates: Query<(Entity, &Position, &mut Building), Without<FollowFlowField>>,
+    fields: Res<FlowFields>,
+    grid: Res<crate::PassabilityGrid>,
+    tile_map: Res<TileMap>,
+) {
+    for (entity, mut position, mut follower, mut transform, movement, physical_body) in followers.iter_mut() {
+        let movement = movement.copied().unwrap_or_default();
+        // Not built yet; it will be by the next update.
+        let Some(built) = fields.get(follower.goal, &movement) else { continue; };
+        let reach = follower.goal.reach();
+        let arrived = built.goals.iter()
+            .any(|goal| goal.z == position.z && (goal.x - position.x).abs().max((goal.y - position.y).abs()) <= reach);
+        let next = grid.flow_step(&tile_map, &built.field, &position, &movement);
+        let Some(next) = next.filter(|_| !arrived) else {
+            commands.entity(entity).remove::<FollowFlowField>();
+            continue;
+        };
+        let strength = physical_body.map_or(1, |body| body.attributes.strength);
+        crate::advance(&mut commands, &mut gates, &grid, &mut position, &mut transform, &mut follower.progress, next, &movement, strength);
+    }
+}
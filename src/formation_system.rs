@@ -5,7 +5,7 @@ use crate::prelude::*;
 // FORMATION & SQUAD SYSTEM - AoE2 military tactics
 // ============================================================================
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Squad {
     pub squad_id: u32,
     pub members: Vec<Entity>,
@@ -15,7 +15,7 @@ pub struct Squad {
     pub leader: Option<Entity>,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum FormationType {
     Line,         // ---- Single line
     Box,          // █ Box formation
@@ -28,7 +28,7 @@ pub enum FormationType {
     Skirmish,     // ·· Loose formation for ranged
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum CombatStance {
     Aggressive,   // Attack everything
     Defensive,    // Only defend when attacked
@@ -75,6 +75,7 @@ impl FormationCalculator {
         formation: &FormationType,
         num_units: usize,
         center: &Position,
+        rng: &mut impl Rng,
     ) -> Vec<Position> {
         match formation {
             FormationType::Line => Self::line_formation(num_units, center),
@@ -85,7 +86,7 @@ impl FormationCalculator {
             FormationType::Phalanx => Self::phalanx_formation(num_units, center),
             FormationType::Flank => Self::flank_formation(num_units, center),
             FormationType::Skirmish => Self::skirmish_formation(num_units, center),
-            FormationType::Scattered => Self::scattered_formation(num_units, center),
+            FormationType::Scattered => Self::scattered_formation(num_units, center, rng),
         }
     }
 
@@ -256,9 +257,8 @@ impl FormationCalculator {
         positions
     }
 
-    fn scattered_formation(num_units: usize, center: &Position) -> Vec<Position> {
+    fn scattered_formation(num_units: usize, center: &Position, rng: &mut impl Rng) -> Vec<Position> {
         let mut positions = Vec::new();
-        let mut rng = rand::thread_rng();
 
         for _ in 0..num_units {
             positions.push(Position {
@@ -318,49 +318,71 @@ pub struct FormationPlugin;
 impl Plugin for FormationPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
+            SimTick,
             (
                 formation_movement_system,
                 squad_cohesion_system,
                 formation_combat_bonuses,
             )
-                .run_if(bevy::time::common_conditions::on_timer(
-                    bevy::utils::Duration::from_secs_f32(0.5),
-                )),
+                .run_if(every_seconds(0.5)),
         );
     }
 }
 
+// Squads heading for a rally point share one flow field there until they are close enough
+// to form up.
+const FORM_UP_DISTANCE: i32 = 6;
+
 fn formation_movement_system(
     mut commands: Commands,
     squad_query: Query<&Squad>,
-    mut unit_query: Query<(Entity, &mut Position, &FormationPosition)>,
+    unit_query: Query<(Entity, &mut Position, &FormationPosition, Option<&Pathing>, Option<&crate::FollowFlowField>)>,
+    mut game_rng: ResMut<GameRng>,
 ) {
+    let rng = game_rng.stream(RngStream::Formation);
     for squad in squad_query.iter() {
         if squad.members.is_empty() {
             continue;
         }
 
-        // Calculate formation center (use first member for now)
+        // Calculate formation center (the rally point, or the first member)
         if let Some(&first) = squad.members.first() {
-            if let Ok((_, center_pos, _)) = unit_query.get(first) {
+            if let Ok((_, leader_pos, _, _, _)) = unit_query.get(first) {
+                let center_pos = squad.rally_point.unwrap_or(*leader_pos);
                 // Calculate formation positions
                 let positions = FormationCalculator::calculate_positions(
                     &squad.formation,
                     squad.members.len(),
-                    center_pos,
+                    &center_pos,
+                    rng,
                 );
 
                 // Assign positions to units
                 for (i, &member) in squad.members.iter().enumerate() {
                     if i < positions.len() {
-                        if let Ok((entity, _, _)) = unit_query.get(member) {
-                            // Set pathfinding target to formation position
+                        if let Ok((entity, position, _, pathing, follower)) = unit_query.get(member) {
+                            if let Some(rally_point) = squad.rally_point {
+                                if position.distance(&rally_point) > FORM_UP_DISTANCE {
+                                    let goal = crate::FlowGoal::Point(rally_point);
+                                    if follower.is_none_or(|follower| follower.goal != goal) {
+                                        commands.entity(entity).remove::<Pathing>().insert(crate::FollowFlowField::new(goal));
+                                    }
+                                    continue;
+                                }
+                            }
+                            if follower.is_some() {
+                                commands.entity(entity).remove::<crate::FollowFlowField>();
+                            }
+                            // Set pathfinding target to formation position, unless already on the way
+                            if pathing.is_some_and(|pathing| pathing.destination == positions[i]) {
+                                continue;
+                            }
                             commands.entity(entity).insert(Pathing {
                                 destination: positions[i],
                                 path: vec![],
                                 unreachable: false,
                                 moving_target: false,
+                                progress: 0,
                             });
                         }
                     }
@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use crate::prelude::*;
+
+// ============================================================================
+// FIELD OF VIEW - Shadowcasting from every colonist, and the colony's fog of war
+// ============================================================================
+
+pub const VIEW_RANGE: i32 = 12;
+pub const MIN_VIEW_RANGE: i32 = 2;
+// Share of the full range left at midnight. Underground it is always this dark.
+const NIGHT_SIGHT: f32 = 0.5;
+// Tiles that were seen before but aren't in sight right now are drawn this dark.
+const REMEMBERED_TINT: Color = Color::rgb(0.45, 0.45, 0.5);
+
+// What the whole colony has seen (`revealed`) and can see right now (`visible`), one grid per
+// map level, laid out like the TileMap's levels.
+#[derive(Resource, Clone, Default, Serialize, Deserialize)]
+pub struct ColonyVision {
+    width: i32,
+    length: i32,
+    revealed: Vec<Vec<bool>>,
+    #[serde(skip)]
+    visible: Vec<Vec<bool>>,
+}
+
+impl ColonyVision {
+    pub fn is_revealed(&self, position: &Position) -> bool {
+        self.index(position).is_some_and(|(level, index)| self.revealed[level][index])
+    }
+
+    pub fn is_visible(&self, position: &Position) -> bool {
+        self.index(position).and_then(|(level, index)| self.visible.get(level).map(|tiles| tiles[index])).unwrap_or(false)
+    }
+
+    // Starts over if the map was replaced by one of another size, e.g. by loading a save.
+    fn fit(&mut self, tile_map: &TileMap) {
+        let levels = (1 - tile_map.lowest_level()) as usize;
+        let size = (tile_map.width() * tile_map.length()) as usize;
+        if self.width != tile_map.width() || self.length != tile_map.length() || self.revealed.len() != levels {
+            self.width = tile_map.width();
+            self.length = tile_map.length();
+            self.revealed = vec![vec![false; size]; levels];
+        }
+        if self.visible.len() != levels {
+            self.visible = vec![vec![false; size]; levels];
+        }
+    }
+
+    fn index(&self, position: &Position) -> Option<(usize, usize)> {
+        if position.x < 0 || position.x >= self.width || position.y < 0 || position.y >= self.length { return None; }
+        let level = (-position.z) as usize;
+        if position.z > 0 || level >= self.revealed.len() { return None; }
+        Some((level, (position.y * self.width + position.x) as usize))
+    }
+}
+
+pub struct FovPlugin;
+
+impl Plugin for FovPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<ColonyVision>()
+        .add_systems(
+            SimTick,
+            (add_viewsheds, update_viewsheds).chain()
+            .run_if(every_seconds(0.5))
+        )
+        ;
+    }
+}
+
+// Hides what the colony can't see. Only the windowed game needs this.
+pub struct FogOfWarPlugin;
+
+impl Plugin for FogOfWarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_fog_of_war);
+    }
+}
+
+// Every colonist sees; creatures don't share what they see with the colony.
+pub fn add_viewsheds(
+    mut commands: Commands,
+    units: Query<(Entity, &Brain), Without<Viewshed>>,
+) {
+    for (entity, brain) in units.iter() {
+        if brain.personality.contains(&PersonalityTrait::Creature) { continue; }
+        commands.entity(entity).insert(Viewshed { visible_tiles: vec![], range: VIEW_RANGE, dirty: true });
+    }
+}
+
+pub fn update_viewsheds(
+    mut viewers: Query<(Ref<Position>, &mut Viewshed)>,
+    tile_map: Res<TileMap>,
+    weather: Res<Weather>,
+    time_of_day: Res<TimeOfDay>,
+    mut vision: ResMut<ColonyVision>,
+) {
+    vision.fit(&tile_map);
+    let light = NIGHT_SIGHT + (1.0 - NIGHT_SIGHT) * crate::calculate_brightness(time_of_day.hour);
+    let surface_range = ((VIEW_RANGE as f32 * weather.visibility * light).round() as i32).max(MIN_VIEW_RANGE);
+    let underground_range = ((VIEW_RANGE as f32 * NIGHT_SIGHT).round() as i32).max(MIN_VIEW_RANGE);
+    let vision = &mut *vision;
+    vision.visible.iter_mut().for_each(|tiles| tiles.fill(false));
+    for (position, mut viewshed) in viewers.iter_mut() {
+        let range = if position.z == 0 { surface_range } else { underground_range };
+        if viewshed.dirty || viewshed.range != range || position.is_changed() || tile_map.is_changed() {
+            viewshed.range = range;
+            viewshed.visible_tiles = field_of_view(&tile_map, &position, range);
+            viewshed.dirty = false;
+        }
+        for seen in viewshed.visible_tiles.iter() {
+            let Some((level, index)) = vision.index(seen) else { continue; };
+            vision.visible[level][index] = true;
+            vision.revealed[level][index] = true;
+        }
+    }
+}
+
+// Recursive shadowcasting: each octant is scanned row by row outwards, and walls cut the
+// visible arc into narrower slopes for the rows behind them. Walls themselves are seen.
+pub fn field_of_view(tile_map: &TileMap, origin: &Position, range: i32) -> Vec<Position> {
+    let mut visible = HashSet::from([*origin]);
+    for octant in OCTANTS {
+        cast_light(tile_map, origin, range, 1, 1.0, 0.0, octant, &mut visible);
+    }
+    visible.into_iter().collect()
+}
+
+// Multipliers that turn (column, row) within an octant into map offsets.
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1), (0, 1, 1, 0), (0, -1, 1, 0), (-1, 0, 0, 1),
+    (-1, 0, 0, -1), (0, -1, -1, 0), (0, 1, -1, 0), (1, 0, 0, -1),
+];
+
+#[allow(clippy::too_many_arguments)]
+fn cast_light(
+    tile_map: &TileMap,
+    origin: &Position,
+    range: i32,
+    first_row: i32,
+    mut start_slope: f32,
+    end_slope: f32,
+    octant: (i32, i32, i32, i32),
+    visible: &mut HashSet<Position>,
+) {
+    if start_slope < end_slope { return; }
+    let (xx, xy, yx, yy) = octant;
+    let mut next_start_slope = start_slope;
+    for distance in first_row..=range {
+        let mut blocked = false;
+        let dy = -distance;
+        for dx in -distance..=0 {
+            let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+            let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+            if start_slope < right_slope { continue; }
+            if end_slope > left_slope { break; }
+            let position = Position { x: origin.x + dx * xx + dy * xy, y: origin.y + dx * yx + dy * yy, z: origin.z };
+            if dx * dx + dy * dy <= range * range {
+                visible.insert(position);
+            }
+            let opaque = tile_map.is_wall(&position);
+            if blocked {
+                if opaque {
+                    next_start_slope = right_slope;
+                } else {
+                    blocked = false;
+                    start_slope = next_start_slope;
+                }
+            } else if opaque && distance < range {
+                blocked = true;
+                cast_light(tile_map, origin, range, distance + 1, start_slope, left_slope, octant, visible);
+                next_start_slope = right_slope;
+            }
+        }
+        if blocked { break; }
+    }
+}
+
+// Unexplored tiles and anything on them stay hidden. Explored tiles out of sight are drawn dark,
+// and creatures only show while a colonist can see them. Other map levels are hidden entirely.
+pub fn apply_fog_of_war(
+    view_level: Res<ViewLevel>,
+    vision: Res<ColonyVision>,
+    mut entities: Query<(&Position, &mut Visibility, Option<&mut TextureAtlasSprite>, Option<&MapTile>, Option<&Brain>, Option<&Viewshed>)>,
+) {
+    for (position, mut visibility, sprite, tile, brain, viewshed) in entities.iter_mut() {
+        let seen = match (tile, brain, viewshed) {
+            (_, Some(_), Some(_)) => true,
+            (_, Some(_), None) => vision.is_visible(position),
+            _ => vision.is_revealed(position),
+        };
+        let shown = if position.z == view_level.z && seen { Visibility::Inherited } else { Visibility::Hidden };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+        if let (Some(_), Some(mut sprite)) = (tile, sprite) {
+            let tint = if vision.is_visible(position) { Color::WHITE } else { REMEMBERED_TINT };
+            if sprite.color != tint {
+                sprite.color = tint;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_map(width: i32, length: i32) -> TileMap {
+        TileMap::from_levels(width, length, vec![vec![TileType::Grass; (width * length) as usize]])
+    }
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { x, y, z: 0 }
+    }
+
+    #[test]
+    fn field_of_view_is_a_circle_in_the_open() {
+        let tile_map = open_map(11, 11);
+        let visible: HashSet<Position> = field_of_view(&tile_map, &at(5, 5), 3).into_iter().collect();
+        for seen in [at(5, 5), at(5, 8), at(2, 5), at(7, 7), at(3, 3)] {
+            assert!(visible.contains(&seen), "{:?} should be in view", seen);
+        }
+        for unseen in [at(5, 9), at(8, 8), at(1, 5)] {
+            assert!(!visible.contains(&unseen), "{:?} should be out of range", unseen);
+        }
+    }
+
+    #[test]
+    fn walls_are_seen_but_hide_what_is_behind_them() {
+        let mut tile_map = open_map(11, 11);
+        tile_map.set(&at(5, 7), TileType::WallStone);
+        let visible: HashSet<Position> = field_of_view(&tile_map, &at(5, 5), 5).into_iter().collect();
+        assert!(visible.contains(&at(5, 7)));
+        assert!(!visible.contains(&at(5, 8)));
+        assert!(!visible.contains(&at(5, 10)));
+        assert!(visible.contains(&at(5, 2)));
+        assert!(visible.contains(&at(8, 8)));
+    }
+
+    #[test]
+    fn field_of_view_stays_on_its_level() {
+        let tile_map = TileMap::from_levels(5, 5, vec![vec![TileType::Grass; 25], vec![TileType::Cave; 25]]);
+        let origin = Position { x: 2, y: 2, z: -1 };
+        assert!(field_of_view(&tile_map, &origin, 4).iter().all(|position| position.z == -1));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::prelude::*;
+use crate::{Scenario, SimulationPlugin};
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Duration;
+
+pub const HEADLESS_DEFAULT_TICKS: u32 = 6000;
+
+pub struct HeadlessOptions {
+    pub ticks: u32,
+}
+
+impl HeadlessOptions {
+    // Returns None unless `--headless` was passed. `--ticks N` sets the run length.
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        if !args.iter().any(|arg| arg == "--headless") {
+            return None;
+        }
+        let ticks = args.iter()
+            .position(|arg| arg == "--ticks")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|ticks| ticks.parse().ok())
+            .unwrap_or(HEADLESS_DEFAULT_TICKS);
+        Some(HeadlessOptions { ticks })
+    }
+}
+
+pub fn run_headless(options: HeadlessOptions, seed: u64, scenario: Scenario) {
+    let mut app = headless_app(seed, scenario);
+    run_ticks(&mut app, options.ticks as u64);
+    print_summary(&mut app.world);
+}
+
+// The simulation without a window, renderer or input.
+pub fn headless_app(seed: u64, scenario: Scenario) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin, SimulationPlugin { seed, scenario }))
+        // Each update advances time by exactly one tick's worth.
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(SECONDS_PER_TICK)));
+    app.finish();
+    app.cleanup();
+    app
+}
+
+pub fn run_ticks(app: &mut App, ticks: u64) {
+    while app.world.resource::<SimClock>().tick < ticks {
+        app.update();
+    }
+}
+
+fn print_summary(world: &mut World) {
+    let mut colonists = 0;
+    let mut creatures = 0;
+    let mut injured = 0;
+    let mut bodies = world.query::<(&Brain, &PhysicalBody)>();
+    for (brain, body) in bodies.iter(world) {
+        if brain.personality.contains(&PersonalityTrait::Creature) {
+            creatures += 1;
+        } else {
+            colonists += 1;
+        }
+        if body.injured || !body.afflictions.is_empty() {
+            injured += 1;
+        }
+    }
+    let plants = world.query::<&Plant>().iter(world).count();
+    let food = world.query::<&Food>().iter(world).count();
+    let logs = world.query::<&Logs>().iter(world).count();
+
+    let clock = world.resource::<SimClock>();
+    println!("=== Headless run: {} ticks ({:.0} simulated seconds) ===", clock.tick, clock.seconds());
+    if let Some(game_rng) = world.get_resource::<GameRng>() {
+        println!("Seed: {}", game_rng.seed);
+    }
+    if let Some(time_of_day) = world.get_resource::<TimeOfDay>() {
+        println!("Day {} ({:?}), hour {:.1}", time_of_day.day, time_of_day.season, time_of_day.hour);
+    }
+    if let Some(weather) = world.get_resource::<Weather>() {
+        println!("Weather: {:?}, {:.1}C", weather.current_weather, weather.temperature);
+    }
+    println!("Colonists: {} ({} injured or afflicted)", colonists, injured);
+    println!("Creatures: {}", creatures);
+    println!("Plants: {}  Food: {}  Logs: {}", plants, food, logs);
+    if let Some(tech_tree) = world.get_resource::<TechnologyTree>() {
+        println!("Technologies unlocked: {}", tech_tree.unlocked_techs.len());
+    }
+    if let Some(population) = world.get_resource::<Population>() {
+        println!("Population: {}/{}", population.current, population.capacity);
+    }
+}
+
+// The default scenario on a small map, so tests run quickly.
+#[cfg(test)]
+pub fn small_scenario() -> Scenario {
+    let map = crate::ScenarioMap::parse("48x48").unwrap();
+    Scenario::load(&asset_path(crate::DEFAULT_SCENARIO), None, Some(map)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless_run_reaches_its_tick_count() {
+        let mut app = headless_app(7, small_scenario());
+        run_ticks(&mut app, 600);
+        assert_eq!(app.world.resource::<SimClock>().tick, 600);
+        let colonists = app.world.query::<&Brain>().iter(&app.world)
+            .filter(|brain| !brain.personality.contains(&PersonalityTrait::Creature))
+            .count();
+        assert!(colonists > 0);
+    }
+
+    #[test]
+    fn headless_options_read_the_tick_count() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(HeadlessOptions::from_args(&args(&["colony"])).is_none());
+        assert_eq!(HeadlessOptions::from_args(&args(&["colony", "--headless"])).unwrap().ticks, HEADLESS_DEFAULT_TICKS);
+        assert_eq!(HeadlessOptions::from_args(&args(&["colony", "--headless", "--ticks", "50"])).unwrap().ticks, 50);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::prelude::*;
+
+// ============================================================================
+// HEAT - Air temperature on every tile, spreading between neighbours
+// ============================================================================
+
+pub const HEAT_UPDATE_SECONDS: f32 = 1.0;
+// Caves stay this cool all year round.
+pub const UNDERGROUND_TEMPERATURE: f32 = 10.0;
+// Share of the difference with a neighbour that evens out each update. Kept under 0.25 so the
+// field settles instead of oscillating.
+const DIFFUSION: f32 = 0.2;
+// Walls and doors pass on this much of the heat open ground does.
+const WALL_CONDUCTIVITY: f32 = 0.05;
+// Share of the difference with the level's ambient temperature lost each update: out in the
+// open, under a roof and inside a wall.
+const OPEN_AIR_EXCHANGE: f32 = 0.5;
+const ROOFED_EXCHANGE: f32 = 0.01;
+const WALL_EXCHANGE: f32 = 0.02;
+const MIN_TEMPERATURE: f32 = -60.0;
+const MAX_TEMPERATURE: f32 = 150.0;
+
+// Warms the tile it stands on by `output` degrees each update; a negative output cools it.
+#[derive(Component, Clone, Copy)]
+pub struct HeatSource {
+    pub output: f32,
+}
+
+// Air temperature of every tile, one grid per map level laid out like the TileMap's levels.
+// Saves from before temperatures were kept load with an empty grid, which is re-seeded at
+// ambient temperature on the next heat update.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct TileTemperatures {
+    width: i32,
+    length: i32,
+    levels: Vec<Vec<f32>>,
+}
+
+impl TileTemperatures {
+    pub fn at(&self, position: &Position) -> Option<f32> {
+        self.index(position).map(|(level, index)| self.levels[level][index])
+    }
+
+    // Starts every tile at its level's ambient temperature if the map changed size.
+    fn fit(&mut self, tile_map: &TileMap, outdoor: f32) {
+        let levels = (1 - tile_map.lowest_level()) as usize;
+        if self.width == tile_map.width() && self.length == tile_map.length() && self.levels.len() == levels { return; }
+        self.width = tile_map.width();
+        self.length = tile_map.length();
+        let size = (self.width * self.length) as usize;
+        self.levels = (0..levels).map(|level| vec![ambient_temperature(-(level as i32), outdoor); size]).collect();
+    }
+
+    fn index(&self, position: &Position) -> Option<(usize, usize)> {
+        if position.x < 0 || position.x >= self.width || position.y < 0 || position.y >= self.length { return None; }
+        let level = (-position.z) as usize;
+        if position.z > 0 || level >= self.levels.len() { return None; }
+        Some((level, (position.y * self.width + position.x) as usize))
+    }
+}
+
+fn ambient_temperature(z: i32, outdoor: f32) -> f32 {
+    if z == 0 { outdoor } else { UNDERGROUND_TEMPERATURE }
+}
+
+pub struct HeatPlugin;
+
+impl Plugin for HeatPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<TileTemperatures>()
+        .add_systems(SimTick, add_heat_sources)
+        .add_systems(
+            SimTick,
+            spread_heat
+            .after(crate::detect_rooms)
+            .run_if(every_seconds(HEAT_UPDATE_SECONDS))
+        )
+        ;
+    }
+}
+
+// Campfires, coolers and furnaces start heating or cooling as soon as they are placed.
+pub fn add_heat_sources(
+    mut commands: Commands,
+    objects: Query<(Entity, &Object), Added<Object>>,
+    buildings: Query<(Entity, &Building), Added<Building>>,
+) {
+    let outputs = objects.iter().map(|(entity, object)| (entity, object.itemtype.heat_output()))
+        .chain(buildings.iter().map(|(entity, building)| (entity, building.building_type.heat_output())));
+    for (entity, output) in outputs {
+        if output == 0.0 { continue; }
+        commands.entity(entity).insert(HeatSource { output });
+    }
+}
+
+// Heat flows between neighbouring tiles, barely through walls, and every tile drifts back
+// towards the weather outside: quickly in the open, slowly under a roof.
+pub fn spread_heat(
+    mut temperatures: ResMut<TileTemperatures>,
+    tile_map: Res<TileMap>,
+    rooms: Res<crate::Rooms>,
+    weather: Res<Weather>,
+    time_of_day: Res<TimeOfDay>,
+    sources: Query<(&HeatSource, &Position)>,
+    fires: Query<(&crate::Fire, &Position)>,
+) {
+    let outdoor = outdoor_temperature(&weather, &time_of_day);
+    temperatures.fit(&tile_map, outdoor);
+    let (width, length) = (temperatures.width, temperatures.length);
+    for (level, tiles) in temperatures.levels.iter_mut().enumerate() {
+        let z = -(level as i32);
+        let ambient = ambient_temperature(z, outdoor);
+        let mut conductivity = Vec::with_capacity(tiles.len());
+        let mut exchange = Vec::with_capacity(tiles.len());
+        for y in 0..length {
+            for x in 0..width {
+                let (tile_conductivity, tile_exchange) = match rooms.room_at(&Position { x, y, z }) {
+                    Some(room) if room.roofed => (1.0, ROOFED_EXCHANGE),
+                    Some(_) => (1.0, OPEN_AIR_EXCHANGE),
+                    None => (WALL_CONDUCTIVITY, WALL_EXCHANGE),
+                };
+                conductivity.push(tile_conductivity);
+                exchange.push(tile_exchange);
+            }
+        }
+        let previous = tiles.clone();
+        for y in 0..length {
+            for x in 0..width {
+                let index = (y * width + x) as usize;
+                let mut flow = 0.0;
+                for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || nx >= width || ny < 0 || ny >= length { continue; }
+                    let neighbor = (ny * width + nx) as usize;
+                    let conductance: f32 = conductivity[index].min(conductivity[neighbor]);
+                    flow += conductance * (previous[neighbor] - previous[index]);
+                }
+                // Evened out with the neighbours first, then with the ambient temperature, so
+                // neither step overshoots and the field can't flip back and forth.
+                let diffused = previous[index] + DIFFUSION * flow;
+                tiles[index] = diffused + exchange[index] * (ambient - diffused);
+            }
+        }
+    }
+    let outputs = sources.iter().map(|(source, position)| (source.output, position))
+        .chain(fires.iter().map(|(fire, position)| (fire.intensity * crate::FIRE_HEAT, position)));
+    for (output, position) in outputs {
+        let Some((level, index)) = temperatures.index(position) else { continue; };
+        let temperature = &mut temperatures.levels[level][index];
+        *temperature = (*temperature + output).clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { x, y, z: 0 }
+    }
+
+    // Runs the heat for a while on an 11x11 map with a walled room from (1, 1) to (5, 5), and the
+    // same heater inside the room at (3, 3) and out in the open at (8, 8).
+    fn heated_room() -> (TileTemperatures, f32) {
+        let mut world = World::new();
+        let mut tiles = vec![TileType::Grass; 121];
+        for (x, y) in (1..=5).flat_map(|x| (1..=5).map(move |y| (x, y))) {
+            if x == 1 || x == 5 || y == 1 || y == 5 {
+                tiles[y * 11 + x] = TileType::WallStone;
+            }
+        }
+        world.insert_resource(TileMap::from_levels(11, 11, vec![tiles]));
+        world.init_resource::<TileTemperatures>();
+        world.init_resource::<crate::Rooms>();
+        world.init_resource::<Weather>();
+        world.init_resource::<TimeOfDay>();
+        world.init_resource::<Events<TileChangedEvent>>();
+        world.spawn((HeatSource { output: 2.0 }, at(3, 3)));
+        world.spawn((HeatSource { output: 2.0 }, at(8, 8)));
+        let mut schedule = Schedule::new();
+        schedule.add_systems((crate::detect_rooms, spread_heat).chain());
+        for _ in 0..200 {
+            schedule.run(&mut world);
+        }
+        let outdoor = outdoor_temperature(world.resource::<Weather>(), world.resource::<TimeOfDay>());
+        (world.remove_resource::<TileTemperatures>().unwrap(), outdoor)
+    }
+
+    #[test]
+    fn heat_spreads_and_walls_keep_it_in() {
+        let (temperatures, outdoor) = heated_room();
+        let warmth = |x, y| temperatures.at(&at(x, y)).unwrap() - outdoor;
+        // The heater warms the whole room, while the walls keep the outside at ambient.
+        assert!(warmth(3, 3) > 10.0);
+        assert!(warmth(2, 2) > 5.0);
+        assert!(warmth(0, 3) < 0.1);
+        // Out in the open the same heater only takes the edge off, and the heat fades with distance.
+        assert!(warmth(8, 8) < warmth(3, 3) / 4.0);
+        assert!(warmth(8, 8) > warmth(7, 8) && warmth(7, 8) > warmth(6, 8));
+        // Nothing ends up colder than the weather.
+        assert!((0..11).all(|x| (0..11).all(|y| warmth(x, y) > -0.01)));
+    }
+}
@@ -4,7 +4,11 @@ pub mod load;
 pub use load::*;
 pub mod map;
 pub use map::*;
+pub mod scenario;
+pub use scenario::*;
 pub mod startup;
 pub use startup::*;
 pub mod window_system;
-pub use window_system::*;
\ No newline at end of file
+pub use window_system::*;
+pub mod worldgen;
+pub use worldgen::*;
\ No newline at end of file
@@ -1,27 +1,162 @@
+use std::path::{Path, PathBuf};
 use crate::prelude::*;
+use crate::Scenario;
+
+pub const BIOME_DIRECTORY: &str = "assets/biomes";
 
 // Make Plugin
 pub struct BiomePlugin;
 
 impl Plugin for BiomePlugin {
     fn build(&self, app: &mut App) {
+        let registry = BiomeRegistry::load(&asset_path(BIOME_DIRECTORY))
+            .unwrap_or_else(|errors| exit_with_data_errors("biomes", &errors));
+        let name = app.world.get_resource::<Scenario>().map(|scenario| scenario.biome.clone()).unwrap_or_default();
+        let biome = registry.get(&name)
+            .or_else(|| registry.biomes.first())
+            .cloned()
+            .unwrap_or_else(|| exit_with_data_errors("biomes", &[format!("{}: no biomes defined", BIOME_DIRECTORY)]));
         app
-        .insert_resource(starting_biome())
+        .insert_resource(biome)
+        .insert_resource(registry)
         ;
     }
 }
 
-pub fn starting_biome() -> Biome {
-    Biome {
-        name: "Forest".to_string(),
-        plants: vec![ItemType::Cabbage, ItemType::Carrot, ItemType::PineTree, ItemType::PineTree, ItemType::PineTree, ItemType::PineTree, ItemType::PineTree,
-        ItemType::CedarTree, ItemType::ThornBush, ItemType::Weed, ItemType::CactusRound],
-        plant_scarcity: vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        plant_overall_scarcity: 10,
-        tiles: vec![TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass,
-        TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Dirt, TileType::Gravel],
-        objects: vec![ItemType::StatuePillar3,ItemType::StatueCat,ItemType::StatueMech, ItemType::Moss1, ItemType::Moss2, ItemType::LeafyDebris1],
-        objects_scarcity: vec![1, 1, 1],
-        objects_overall_scarcity: 200,
-    }
-}
\ No newline at end of file
+#[derive(Resource, Default)]
+pub struct BiomeRegistry {
+    biomes: Vec<Biome>,
+}
+
+impl BiomeRegistry {
+    // Every .ron file in the directory holds a list of biomes.
+    pub fn load(directory: &Path) -> Result<Self, Vec<String>> {
+        let files = read_ron_files::<Vec<Biome>>(directory)?;
+        let mut registry = BiomeRegistry::default();
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut errors = Vec::new();
+        for (path, biomes) in files {
+            for biome in biomes {
+                if let Err(e) = biome.validate() {
+                    errors.push(format!("{}: biome '{}': {}", path.display(), biome.id, e));
+                    continue;
+                }
+                if let Some(first) = sources.get(&biome.id) {
+                    errors.push(format!("{}: biome '{}': field `id` is already defined in {}", path.display(), biome.id, first.display()));
+                    continue;
+                }
+                sources.insert(biome.id.clone(), path.clone());
+                registry.biomes.push(biome);
+            }
+        }
+        if errors.is_empty() { Ok(registry) } else { Err(errors) }
+    }
+
+    // Matches the id or the display name, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&Biome> {
+        self.biomes.iter().find(|biome| biome.id.eq_ignore_ascii_case(name) || biome.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn patch_biomes(&self) -> impl Iterator<Item = &Biome> {
+        self.biomes.iter().filter(|biome| biome.patch.is_some())
+    }
+}
+
+impl Biome {
+    fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("field `id` is empty".to_string());
+        }
+        for (field, total) in [
+            ("tiles", self.tiles.iter().map(|(_, weight)| weight).sum::<u32>()),
+            ("plants", self.plants.iter().map(|(_, weight)| weight).sum()),
+            ("objects", self.objects.iter().map(|(_, weight)| weight).sum()),
+            ("climate.weather", self.climate.weather.iter().map(|(_, weight)| weight).sum()),
+        ] {
+            if total == 0 {
+                return Err(format!("field `{}` needs at least one entry with a weight above 0", field));
+            }
+        }
+        if let Some((tile_type, _)) = self.tiles.iter().find(|(tile_type, _)| tile_type.is_wall()) {
+            return Err(format!("field `tiles` has {:?}, walls can't be ground tiles", tile_type));
+        }
+        if self.plant_overall_scarcity < 1 {
+            return Err(format!("field `plant_overall_scarcity` is {}, it must be at least 1", self.plant_overall_scarcity));
+        }
+        if self.objects_overall_scarcity < 1 {
+            return Err(format!("field `objects_overall_scarcity` is {}, it must be at least 1", self.objects_overall_scarcity));
+        }
+        if let Some(patch) = &self.patch {
+            if !(-1.0..=1.0).contains(&patch.temperature) || !(-1.0..=1.0).contains(&patch.moisture) {
+                return Err("field `patch` must have temperature and moisture between -1.0 and 1.0".to_string());
+            }
+        }
+        if self.climate.wind_multiplier < 0.0 {
+            return Err(format!("field `climate.wind_multiplier` is {}, it can't be negative", self.climate.wind_multiplier));
+        }
+        Ok(())
+    }
+
+    pub fn pick_tile(&self, rng: &mut impl Rng) -> TileType {
+        self.tiles.choose_weighted(rng, |(_, weight)| *weight).map(|(tile_type, _)| tile_type.clone()).unwrap_or(TileType::Grass)
+    }
+
+    pub fn pick_plant(&self, rng: &mut impl Rng) -> Option<ItemType> {
+        self.plants.choose_weighted(rng, |(_, weight)| *weight).ok().map(|(plant_type, _)| *plant_type)
+    }
+
+    pub fn pick_object(&self, rng: &mut impl Rng) -> Option<ItemType> {
+        self.objects.choose_weighted(rng, |(_, weight)| *weight).ok().map(|(object_type, _)| *object_type)
+    }
+
+    pub fn pick_weather(&self, rng: &mut impl Rng) -> WeatherType {
+        self.climate.weather.choose_weighted(rng, |(_, weight)| *weight).map(|(weather_type, _)| weather_type.clone()).unwrap_or(WeatherType::Clear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn biome(id: &str, tiles: &str, plant_overall_scarcity: i32, wind_multiplier: f32) -> String {
+        format!(
+            "(id: \"{}\", name: \"{}\", tiles: [{}], plants: [(PineTree, 1)], plant_overall_scarcity: {}, \
+             objects: [(Moss1, 1)], objects_overall_scarcity: 10, \
+             climate: (temperature_offset: 0.0, wind_multiplier: {:?}, weather: [(Clear, 1)]))",
+            id, id, tiles, plant_overall_scarcity, wind_multiplier,
+        )
+    }
+
+    #[test]
+    fn shipped_biomes_load() {
+        let registry = BiomeRegistry::load(&asset_path(BIOME_DIRECTORY)).unwrap();
+        assert_eq!(registry.get("FOREST").map(|biome| biome.id.as_str()), Some("forest"));
+        assert!(registry.patch_biomes().next().is_some());
+    }
+
+    #[test]
+    fn bad_biomes_are_rejected() {
+        let directory = scratch_directory("biomes-bad", &[
+            ("a.ron", &format!("[{}, {}, {}, {}, {}]",
+                biome("meadow", "(Grass, 1)", 10, 1.0),
+                biome("void", "(Grass, 0)", 10, 1.0),
+                biome("ruins", "(Grass, 1), (WallStone, 1)", 10, 1.0),
+                biome("jungle", "(Grass, 1)", 0, 1.0),
+                biome("calm", "(Grass, 1)", 10, -1.0),
+            )),
+            ("b.ron", &format!("[{}]", biome("meadow", "(Dirt, 1)", 10, 1.0))),
+        ]);
+        let errors = BiomeRegistry::load(&directory).err().expect("biomes should not load");
+        let expected = [
+            "a.ron: biome 'void': field `tiles` needs at least one entry with a weight above 0",
+            "a.ron: biome 'ruins': field `tiles` has WallStone, walls can't be ground tiles",
+            "a.ron: biome 'jungle': field `plant_overall_scarcity` is 0, it must be at least 1",
+            "a.ron: biome 'calm': field `climate.wind_multiplier` is -1, it can't be negative",
+            "b.ron: biome 'meadow': field `id` is already defined in",
+        ];
+        assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+        for error in expected {
+            assert!(errors.iter().any(|e| e.contains(error)), "missing {:?} in {:?}", error, errors);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use bevy::ecs::system::EntityCommands;
 
 pub fn load_sprites(
     mut commands: Commands,
@@ -11,6 +12,23 @@ pub fn load_sprites(
     commands.insert_resource(SpriteSheet(texture_atlas_handle));
 }
 
+// Spawns an entity showing the given sprite. Without a sprite sheet (headless runs) the
+// entity still gets a Transform so gameplay systems that move things keep working.
+pub fn spawn_sprite<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    sprite_sheet: Option<&SpriteSheet>,
+    sprite_index: usize,
+) -> EntityCommands<'w, 's, 'a> {
+    match sprite_sheet {
+        Some(sprite_sheet) => commands.spawn(SpriteSheetBundle {
+            sprite: TextureAtlasSprite::new(sprite_index),
+            texture_atlas: sprite_sheet.0.clone(),
+            ..default()
+        }),
+        None => commands.spawn(SpatialBundle::default()),
+    }
+}
+
 pub fn load_font(
     asset_server: Res<AssetServer>,
     mut font_handle: ResMut<MyFont>,
@@ -30,5 +48,7 @@ pub fn load_sfx(
     // ));
 }
 
+// For the sound effects above, once they are played again.
+#[allow(dead_code)]
 #[derive(Component)]
-pub struct SoundEffect;
\ No newline at end of file
+pub struct SoundEffect;
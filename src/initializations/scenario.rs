@@ -0,0 +1,265 @@
+use std::path::{Path, PathBuf};
+use crate::prelude::*;
+use crate::{BiomeRegistry, UnitMarker, UnitRegistry, UnitTemplate};
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+pub const DEFAULT_SCENARIO: &str = "assets/scenarios/default.ron";
+
+// Everything the colony starts with. Loaded from a RON file before the app is built,
+// then read by `generate_map` and `startup`.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub map: ScenarioMap,
+    pub biome: String,
+    #[serde(default)]
+    pub units: Vec<ScenarioUnit>,
+    #[serde(default)]
+    pub items: Vec<ScenarioItem>,
+    #[serde(default)]
+    pub buildings: Vec<ScenarioBuilding>,
+    #[serde(default)]
+    pub monster_generators: Vec<ScenarioMonsterGenerator>,
+    #[serde(default)]
+    pub starting_tech: Vec<String>,
+    #[serde(default = "default_weather")]
+    pub weather: WeatherType,
+    #[serde(default = "default_season")]
+    pub season: Season,
+    // Lets units step diagonally as well as straight.
+    #[serde(default = "default_diagonal_movement")]
+    pub diagonal_movement: bool,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ScenarioMap {
+    pub width: i32,
+    pub length: i32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioUnit {
+    pub template: String,
+    pub position: Position,
+    #[serde(default)]
+    pub overrides: UnitOverrides,
+}
+
+// Any field left out keeps whatever the template rolled.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct UnitOverrides {
+    pub name: Option<String>,
+    pub personality: Option<Vec<PersonalityTrait>>,
+    pub attributes: Option<Attributeset>,
+    pub afflictions: Option<Vec<Affliction>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioItem {
+    pub item: ItemType,
+    pub position: Position,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioBuilding {
+    pub building: BuildingType,
+    pub position: Position,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioMonsterGenerator {
+    pub position: Position,
+    pub monsters: Vec<(String, u8)>,
+}
+
+impl ScenarioMap {
+    // Parses "WIDTHxLENGTH", e.g. "256x128".
+    pub fn parse(size: &str) -> Result<Self, String> {
+        let parsed = size.split_once('x')
+            .and_then(|(width, length)| Some(ScenarioMap { width: width.parse().ok()?, length: length.parse().ok()? }));
+        parsed.ok_or_else(|| format!("Could not parse map size '{}', expected WIDTHxLENGTH like 256x128", size))
+    }
+}
+
+fn default_weather() -> WeatherType { WeatherType::Clear }
+fn default_season() -> Season { Season::Spring }
+fn default_diagonal_movement() -> bool { true }
+
+impl Scenario {
+    // Reads `--scenario PATH`, falling back to the default scenario. `--biome NAME`
+    // replaces the scenario's biome and `--map-size WIDTHxLENGTH` its map size.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let arg_value = |flag: &str| args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1));
+        let path = arg_value("--scenario")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| asset_path(DEFAULT_SCENARIO));
+        let map = arg_value("--map-size").map(|size| ScenarioMap::parse(size)).transpose()?;
+        Self::load(&path, arg_value("--biome").cloned(), map)
+    }
+
+    pub fn load(path: &Path, biome: Option<String>, map: Option<ScenarioMap>) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read scenario {}: {}", path.display(), e))?;
+        let mut scenario: Scenario = ron::from_str(&text)
+            .map_err(|e| format!("Could not parse scenario {}: {}", path.display(), e))?;
+        if let Some(biome) = biome {
+            scenario.biome = biome;
+        }
+        if let Some(map) = map {
+            scenario.map = map;
+        }
+        scenario.validate()
+            .map_err(|e| format!("Invalid scenario {}: {}", path.display(), e))?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.map.width < 3 || self.map.length < 3 {
+            return Err(format!("map is {}x{}, it must be at least 3x3", self.map.width, self.map.length));
+        }
+        if self.map.width > MAX_MAP_SIZE || self.map.length > MAX_MAP_SIZE {
+            return Err(format!("map is {}x{}, it can't be bigger than {}x{}", self.map.width, self.map.length, MAX_MAP_SIZE, MAX_MAP_SIZE));
+        }
+        for generator in &self.monster_generators {
+            if generator.monsters.iter().all(|(_, chance)| *chance == 0) {
+                return Err(format!("monster generator at ({}, {}) has no monster with a chance above 0", generator.position.x, generator.position.y));
+            }
+        }
+        for position in self.landing_sites() {
+            if !self.contains(&position) {
+                return Err(format!("({}, {}) is outside the map", position.x, position.y));
+            }
+        }
+        Ok(())
+    }
+
+    // Checks the biome, unit templates and techs the scenario names against the data files,
+    // once their plugins have loaded them.
+    pub fn validate_references(&self, biomes: &BiomeRegistry, unit_templates: &UnitRegistry, tech_tree: &TechnologyTree) -> Result<(), String> {
+        if biomes.get(&self.biome).is_none() {
+            return Err(format!("unknown biome '{}'", self.biome));
+        }
+        let template_ids = self.units.iter().map(|unit| &unit.template)
+            .chain(self.monster_generators.iter().flat_map(|generator| generator.monsters.iter().map(|(id, _)| id)));
+        for id in template_ids {
+            if !unit_templates.contains(id) {
+                return Err(format!("unknown unit template '{}'", id));
+            }
+        }
+        for tech_id in &self.starting_tech {
+            if tech_tree.get(tech_id).is_none() {
+                return Err(format!("unknown starting tech '{}'", tech_id));
+            }
+        }
+        Ok(())
+    }
+
+    // Everything the scenario places on the map; world generation keeps open ground around these.
+    pub fn landing_sites(&self) -> Vec<Position> {
+        self.units.iter().map(|unit| unit.position)
+            .chain(self.items.iter().map(|item| item.position))
+            .chain(self.buildings.iter().map(|building| building.position))
+            .chain(self.monster_generators.iter().map(|generator| generator.position))
+            .collect()
+    }
+
+    // True for positions inside the border walls.
+    pub fn contains(&self, position: &Position) -> bool {
+        position.x > 0 && position.x < self.map.width - 1 && position.y > 0 && position.y < self.map.length - 1
+    }
+}
+
+impl UnitOverrides {
+    pub fn apply(&self, template: &mut UnitTemplate) {
+        if let Some(name) = &self.name {
+            template.name = Some(name.clone());
+            template.markers.retain(|marker| *marker != UnitMarker::GiveMeAName);
+        }
+        if let Some(personality) = &self.personality {
+            template.personality = personality.clone();
+        }
+        if let Some(attributes) = &self.attributes {
+            template.attributes = *attributes;
+        }
+        if let Some(afflictions) = &self.afflictions {
+            template.afflictions = afflictions.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BIOME_DIRECTORY, ITEM_DIRECTORY, TECH_DIRECTORY, UNIT_DIRECTORY};
+
+    fn scenario(units: &str, monster_generators: &str) -> Scenario {
+        ron::from_str(&format!(
+            "(name: \"Test\", map: (width: 20, length: 20), biome: \"Forest\", units: [{}], monster_generators: [{}])",
+            units, monster_generators,
+        )).unwrap()
+    }
+
+    #[test]
+    fn default_scenario_loads() {
+        let scenario = Scenario::load(&asset_path(DEFAULT_SCENARIO), None, None).unwrap();
+        assert!(!scenario.units.is_empty());
+    }
+
+    #[test]
+    fn maps_and_positions_are_checked() {
+        assert!(scenario("(template: \"human\", position: (x: 3, y: 3))", "").validate().is_ok());
+
+        let mut tiny = scenario("", "");
+        tiny.map = ScenarioMap { width: 2, length: 20 };
+        assert!(tiny.validate().unwrap_err().contains("at least 3x3"));
+        tiny.map = ScenarioMap { width: MAX_MAP_SIZE + 1, length: 20 };
+        assert!(tiny.validate().unwrap_err().contains("can't be bigger"));
+
+        // The border is wall, so nothing can start on it.
+        let on_border = scenario("(template: \"human\", position: (x: 0, y: 3))", "");
+        assert_eq!(on_border.validate().unwrap_err(), "(0, 3) is outside the map");
+        let off_map = scenario("(template: \"human\", position: (x: 3, y: 25))", "");
+        assert!(off_map.validate().is_err());
+    }
+
+    #[test]
+    fn monster_generators_need_a_chance() {
+        let never = scenario("", "(position: (x: 5, y: 5), monsters: [(\"rat\", 0), (\"spider\", 0)])");
+        assert!(never.validate().unwrap_err().contains("no monster with a chance above 0"));
+        let crowded = scenario("", "(position: (x: 5, y: 5), monsters: [(\"rat\", 200), (\"spider\", 200)])");
+        assert!(crowded.validate().is_ok());
+
+        // Chances adding up past u8 still pick every monster.
+        let generator = MonsterGenerator { monsters: crowded.monster_generators[0].monsters.clone() };
+        let mut rng = GameRng::new(3);
+        let picks = (0..200).map(|_| generator.pick(rng.stream(RngStream::Monsters)).to_string()).collect::<Vec<_>>();
+        assert!(picks.iter().any(|monster| monster == "rat") && picks.iter().any(|monster| monster == "spider"));
+    }
+
+    #[test]
+    fn references_are_checked_against_the_data_files() {
+        let biomes = BiomeRegistry::load(&asset_path(BIOME_DIRECTORY)).unwrap();
+        let items = crate::ItemRegistry::load(&asset_path(ITEM_DIRECTORY)).unwrap();
+        let units = UnitRegistry::load(&asset_path(UNIT_DIRECTORY), &items).unwrap();
+        let tech_tree = TechnologyTree::load(&asset_path(TECH_DIRECTORY)).unwrap();
+        let check = |scenario: &Scenario| scenario.validate_references(&biomes, &units, &tech_tree);
+
+        let mut valid = scenario("(template: \"human\", position: (x: 3, y: 3))", "(position: (x: 5, y: 5), monsters: [(\"rat\", 1)])");
+        assert_eq!(check(&valid), Ok(()));
+
+        valid.starting_tech = vec!["time_travel".to_string()];
+        assert_eq!(check(&valid), Err("unknown starting tech 'time_travel'".to_string()));
+        let mut wrong_biome = scenario("", "");
+        wrong_biome.biome = "Moon".to_string();
+        assert_eq!(check(&wrong_biome), Err("unknown biome 'Moon'".to_string()));
+        let unknown_unit = scenario("(template: \"wizard\", position: (x: 3, y: 3))", "");
+        assert_eq!(check(&unknown_unit), Err("unknown unit template 'wizard'".to_string()));
+        let unknown_monster = scenario("", "(position: (x: 5, y: 5), monsters: [(\"dragon\", 1)])");
+        assert_eq!(check(&unknown_monster), Err("unknown unit template 'dragon'".to_string()));
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 use crate::components::{Position, SizeXYZ};
 use crate::prelude::*;
+use crate::generate_map;
 use crate::spawn_unit_from_template;
-use crate::UnitTemplate;
+use crate::{BiomeMap, Scenario};
+use crate::UnitRegistry;
 
 // Make Startup Plugin
 pub struct StartupPlugin;
@@ -10,77 +12,83 @@ pub struct StartupPlugin;
 impl Plugin for StartupPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Startup, startup)
+        .add_systems(Startup, (generate_map, apply_deferred, startup).chain())
         .insert_resource(MyFont(Handle::<Font>::default()))
         ;
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn startup(
     mut commands: Commands,
-    sprite_sheet: Res<SpriteSheet>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
     biome: Res<Biome>,
+    biome_map: Res<BiomeMap>,
+    tile_map: Res<TileMap>,
+    scenario: Res<Scenario>,
+    units: Res<UnitRegistry>,
+    mut tech_tree: ResMut<TechnologyTree>,
+    mut weather: ResMut<Weather>,
+    mut time_of_day: ResMut<TimeOfDay>,
+    mut game_rng: ResMut<GameRng>,
 ) {
+    let sprite_sheet = sprite_sheet.as_deref();
+    let rng = game_rng.stream(RngStream::Startup);
+    let (map_width, map_length) = (scenario.map.width, scenario.map.length);
     // GENERATE UNITS
-    for i in 1..=5 {
-        let position = Position { x: 3, y: 3*i, z: 0 };
-        match i {
-            1 => {
-                spawn_unit_from_template(&mut commands, position, &sprite_sheet, &UnitTemplate::elf());
-            },
-            2 => {
-                spawn_unit_from_template(&mut commands, position, &sprite_sheet, &UnitTemplate::dwarf());
-            },
-            _ => {
-                spawn_unit_from_template(&mut commands, position, &sprite_sheet, &UnitTemplate::human());
-            }
-        }
-    }
-    // SPAWN MANDATORY RUSTACEANS
-    for i in 1..=3 {
-        let position = Position { x: 20, y: 3*i, z: 0 };
-        spawn_unit_from_template(&mut commands, position, &sprite_sheet, &UnitTemplate::crab());
+    for unit in &scenario.units {
+        let Some(mut template) = units.roll(&unit.template, rng) else { continue; };
+        unit.overrides.apply(&mut template);
+        spawn_unit_from_template(&mut commands, unit.position, sprite_sheet, &template, rng);
     }
-
-    let position = Position { x: 30, y: 6, z: 0 };
-    let sprite =  TextureAtlasSprite::new(TileType::Cave.sprite_index());
-    commands
-            .spawn(SpriteSheetBundle {
-                sprite,
-                texture_atlas: sprite_sheet.0.clone(),
-                ..default()
-            })
-            .insert(position)
+    for generator in &scenario.monster_generators {
+        let monsters = generator.monsters.clone();
+        crate::spawn_sprite(&mut commands, sprite_sheet, TileType::Cave.sprite_index())
+            .insert(generator.position)
             .insert(SizeXYZ::cube(1.0))
-            .insert(MonsterGenerator { monsters: vec![(UnitTemplate::rat(),1),(UnitTemplate::spider(),5),(UnitTemplate::cyclops(),1)] })
-            .insert(position.to_transform_layer(1.0))
+            .insert(MonsterGenerator { monsters })
+            .insert(generator.position.to_transform_layer(1.0))
+            ;
+    }
+    for item in &scenario.items {
+        let object = crate::spawn_sprite(&mut commands, sprite_sheet, item.item.sprite_index())
+            .insert(item.position)
+            .insert(item.position.to_transform_layer(0.5))
+            .insert( Object { itemtype: item.item, ..default() } )
+            .id()
             ;
+        item.item.add_components(&mut commands, object);
+    }
+    for building in &scenario.buildings {
+        spawn_building(&mut commands, building.building.clone(), building.position, sprite_sheet);
+    }
+    for tech_id in &scenario.starting_tech {
+        tech_tree.complete_research(tech_id.clone());
+    }
+    let (min_temp, max_temp) = scenario.weather.temperature_range();
+    weather.current_weather = scenario.weather.clone();
+    weather.temperature = (min_temp + max_temp) / 2.0 + biome.climate.temperature_offset;
+    weather.precipitation = scenario.weather.precipitation_amount();
+    weather.visibility = scenario.weather.visibility_modifier();
+    time_of_day.season = scenario.season.clone();
 
     // GENERATE PLANTS
-    // List taken positions as a Hashmap
+    // Plants and objects only go on a biome's own ground, not on water, sand or rock,
+    // and each biome sets how densely they're scattered.
     let mut taken_positions: HashMap<Position, u8> = HashMap::new();
-    for _ in 0..(MAP_WIDTH*MAP_LENGTH / 10) {
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(1..MAP_WIDTH-1);
-        let y = rng.gen_range(1..MAP_LENGTH-1);
-        let growth = rng.gen_range(0.1..1.0);
+    let open_ground = |position: &Position| -> Option<&Biome> {
+        let biome = biome_map.biome_at(position);
+        let tile_type = tile_map.get(position)?;
+        biome.tiles.iter().any(|(ground, _)| ground == tile_type).then_some(biome)
+    };
+    for (x, y) in (1..map_length-1).flat_map(|y| (1..map_width-1).map(move |x| (x, y))) {
         let position = Position { x, y, z: 0 };
-        if taken_positions.contains_key(&position) { continue; }
+        let Some(tile_biome) = open_ground(&position) else { continue; };
+        if !rng.gen_ratio(1, tile_biome.plant_overall_scarcity as u32) { continue; }
+        let Some(plant_type) = tile_biome.pick_plant(rng) else { continue; };
+        let growth = rng.gen_range(0.1..1.0);
         taken_positions.insert(position, 1);
-        let plant_type = biome.plants[rng.gen_range(0..biome.plants.len())];
-        let sprite =  TextureAtlasSprite::new(plant_type.sprite_index());
-        
-        let plant = commands
-            .spawn(SpriteSheetBundle {
-                sprite,
-                texture_atlas: sprite_sheet.0.clone(),
-                transform: Transform::from_xyz(
-                    position.x as f32 * TILE_SIZE,
-                    position.y as f32 * TILE_SIZE,
-                    position.z as f32 * TILE_SIZE,
-                ),
-                ..Default::default()
-            })
+        let plant = crate::spawn_sprite(&mut commands, sprite_sheet, plant_type.sprite_index())
             .insert(position)
             .insert(position.to_transform_layer(0.5))
             .insert(Plant { growth, plant_type })
@@ -95,27 +103,14 @@ pub fn startup(
         }
     }
     // Spawn Objects (Items)
-    for _ in 0..(MAP_WIDTH*MAP_LENGTH / biome.objects_overall_scarcity) {
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(1..MAP_WIDTH-1);
-        let y = rng.gen_range(1..MAP_LENGTH-1);
+    for (x, y) in (1..map_length-1).flat_map(|y| (1..map_width-1).map(move |x| (x, y))) {
         let position = Position { x, y, z: 0 };
         if taken_positions.contains_key(&position) { continue; }
+        let Some(tile_biome) = open_ground(&position) else { continue; };
+        if !rng.gen_ratio(1, tile_biome.objects_overall_scarcity as u32) { continue; }
+        let Some(object_type) = tile_biome.pick_object(rng) else { continue; };
         taken_positions.insert(position, 1);
-        let object_type = biome.objects[rng.gen_range(0..biome.objects.len())];
-        let sprite =  TextureAtlasSprite::new(object_type.sprite_index());
-        
-        let object = commands
-            .spawn(SpriteSheetBundle {
-                sprite,
-                texture_atlas: sprite_sheet.0.clone(),
-                transform: Transform::from_xyz(
-                    position.x as f32 * TILE_SIZE,
-                    position.y as f32 * TILE_SIZE,
-                    position.z as f32 * TILE_SIZE,
-                ),
-                ..Default::default()
-            })
+        let object = crate::spawn_sprite(&mut commands, sprite_sheet, object_type.sprite_index())
             .insert(position)
             .insert(position.to_transform_layer(0.5))
             .insert( Object { itemtype: object_type, ..default() } )
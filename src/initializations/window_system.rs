@@ -21,7 +21,7 @@ pub fn set_window_icon(
     // here we use the `image` crate to load our icon data from a png file
     // this is not a very bevy-native solution, but it will do
     let (icon_rgba, icon_width, icon_height) = {
-        let image = image::open("assets/fort2.png")
+        let image = image::open(crate::asset_path("assets/fort2.png"))
             .expect("Failed to open icon path")
             .into_rgba8();
         let (width, height) = image.dimensions();
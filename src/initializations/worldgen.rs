@@ -0,0 +1,270 @@
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use crate::prelude::*;
+use crate::BiomeRegistry;
+
+// ============================================================================
+// WORLD GENERATION - Layered noise for elevation, moisture and temperature
+// ============================================================================
+
+// Noise is sampled at tile coordinates times these, so features keep their size on any map.
+const ELEVATION_SCALE: f64 = 0.04;
+const CLIMATE_SCALE: f64 = 0.02;
+const RIVER_SCALE: f64 = 0.012;
+const DETAIL_SCALE: f64 = 0.2;
+const CAVERN_SCALE: f64 = 0.08;
+
+// How far a tile's climate can drift from the map biome's, in climate units (-1.0 to 1.0).
+const CLIMATE_SPREAD: f32 = 0.6;
+
+const LAKE_LEVEL: f32 = -0.35;
+const LAKE_MOISTURE_SHIFT: f32 = 0.15; // Wetter climates have more and bigger lakes.
+const SHORE_WIDTH: f32 = 0.06;
+const RIVER_WIDTH: f32 = 0.02;
+const RIVER_BANK_WIDTH: f32 = 0.04;
+const MOUNTAIN_LEVEL: f32 = 0.35;
+const CAVE_LEVEL: f32 = 0.5;
+
+// Scenario units, buildings and items get this much dry, open ground around them.
+const LANDING_RADIUS: i32 = 2;
+
+// The level below the surface is rock, with caverns where this noise is high.
+const CAVERN_LEVEL: f32 = 0.3;
+// Monster caves lead down into a den this big.
+const DEN_RADIUS: i32 = 4;
+
+pub struct WorldGenerator {
+    elevation: Fbm<Perlin>,
+    moisture: Fbm<Perlin>,
+    temperature: Fbm<Perlin>,
+    rivers: Fbm<Perlin>,
+    detail: Perlin,
+    caverns: Fbm<Perlin>,
+}
+
+pub struct TerrainSample {
+    pub elevation: f32,
+    pub moisture: f32,
+    pub temperature: f32,
+    pub river: f32,
+    pub detail: f32,
+}
+
+impl WorldGenerator {
+    // Every layer is seeded from the map stream, so the same seed always gives the same world.
+    pub fn new(rng: &mut impl Rng) -> Self {
+        WorldGenerator {
+            elevation: Fbm::<Perlin>::new(rng.gen()).set_octaves(5).set_frequency(ELEVATION_SCALE),
+            moisture: Fbm::<Perlin>::new(rng.gen()).set_octaves(3).set_frequency(CLIMATE_SCALE),
+            temperature: Fbm::<Perlin>::new(rng.gen()).set_octaves(3).set_frequency(CLIMATE_SCALE),
+            rivers: Fbm::<Perlin>::new(rng.gen()).set_octaves(2).set_frequency(RIVER_SCALE),
+            detail: Perlin::new(rng.gen()),
+            caverns: Fbm::<Perlin>::new(rng.gen()).set_octaves(3).set_frequency(CAVERN_SCALE),
+        }
+    }
+
+    pub fn sample(&self, x: i32, y: i32) -> TerrainSample {
+        let point = [x as f64, y as f64];
+        TerrainSample {
+            elevation: self.elevation.get(point) as f32,
+            moisture: self.moisture.get(point) as f32,
+            temperature: self.temperature.get(point) as f32,
+            river: self.rivers.get(point) as f32,
+            detail: self.detail.get([point[0] * DETAIL_SCALE, point[1] * DETAIL_SCALE]) as f32,
+        }
+    }
+
+    pub fn cavern(&self, x: i32, y: i32) -> f32 {
+        self.caverns.get([x as f64, y as f64]) as f32
+    }
+}
+
+// Which biome each tile was generated from, so plants and objects match the ground they grow on.
+#[derive(Resource)]
+pub struct BiomeMap {
+    width: i32,
+    biomes: Vec<Biome>,
+    tiles: Vec<usize>,
+}
+
+impl BiomeMap {
+    pub fn biome_at(&self, position: &Position) -> &Biome {
+        let index = (position.y * self.width + position.x) as usize;
+        &self.biomes[self.tiles.get(index).copied().unwrap_or(0)]
+    }
+}
+
+// Builds the ground for a `width` x `length` map, surface level first. The map biome covers most
+// of the surface; biomes with a `patch` climate close to a tile's climate take over in contiguous
+// regions. Every monster cave in `dens` gets stairs down to a den on the level below.
+pub fn generate_world(
+    width: i32,
+    length: i32,
+    home: &Biome,
+    biomes: &BiomeRegistry,
+    landing_sites: &[Position],
+    dens: &[Position],
+    rng: &mut impl Rng,
+) -> (Vec<Vec<TileType>>, BiomeMap) {
+    let generator = WorldGenerator::new(rng);
+    let mut candidates = vec![home.clone()];
+    if home.patch.is_some() {
+        candidates.extend(biomes.patch_biomes().filter(|biome| biome.id != home.id).cloned());
+    }
+    let mut tiles = Vec::with_capacity((width * length) as usize);
+    let mut biome_tiles = Vec::with_capacity((width * length) as usize);
+    for y in 0..length {
+        for x in 0..width {
+            let sample = generator.sample(x, y);
+            let (biome_index, moisture) = match &home.patch {
+                Some(patch) => {
+                    let temperature = patch.temperature + sample.temperature * CLIMATE_SPREAD;
+                    let moisture = patch.moisture + sample.moisture * CLIMATE_SPREAD;
+                    (nearest_biome(&candidates, temperature, moisture), moisture)
+                }
+                None => (0, sample.moisture * CLIMATE_SPREAD),
+            };
+            let biome = &candidates[biome_index];
+            let tile_type = if x == 0 || x == width - 1 || y == 0 || y == length - 1 {
+                TileType::WallGame
+            } else if near_landing_site(x, y, landing_sites) {
+                biome.pick_tile(rng)
+            } else {
+                terrain_tile(&sample, moisture, biome, rng)
+            };
+            tiles.push(tile_type);
+            biome_tiles.push(biome_index);
+        }
+    }
+    // Caves open into the level below, with one flight of stairs each.
+    let mut underground = generate_underground(width, length, &tiles, &generator);
+    for region in cave_regions(width, length, &tiles) {
+        let &(x, y) = region.choose(rng).unwrap();
+        tiles[(y * width + x) as usize] = TileType::StairsDown;
+        underground[(y * width + x) as usize] = TileType::StairsUp;
+    }
+    for den in dens {
+        dig_den(width, length, &mut tiles, &mut underground, den);
+    }
+    (vec![tiles, underground], BiomeMap { width, biomes: candidates, tiles: biome_tiles })
+}
+
+// Solid rock, except for caverns from noise and under the caves of the surface.
+fn generate_underground(width: i32, length: i32, surface: &[TileType], generator: &WorldGenerator) -> Vec<TileType> {
+    let mut tiles = Vec::with_capacity((width * length) as usize);
+    for y in 0..length {
+        for x in 0..width {
+            let tile_type = if x == 0 || x == width - 1 || y == 0 || y == length - 1 {
+                TileType::WallGame
+            } else if surface[(y * width + x) as usize] == TileType::Cave || generator.cavern(x, y) > CAVERN_LEVEL {
+                UNDERGROUND_FLOOR
+            } else {
+                UNDERGROUND_ROCK
+            };
+            tiles.push(tile_type);
+        }
+    }
+    tiles
+}
+
+// Each group of touching Cave tiles, as (x, y) lists.
+fn cave_regions(width: i32, length: i32, tiles: &[TileType]) -> Vec<Vec<(i32, i32)>> {
+    let mut seen = vec![false; tiles.len()];
+    let mut regions = Vec::new();
+    for start in 0..tiles.len() {
+        if seen[start] || tiles[start] != TileType::Cave { continue; }
+        seen[start] = true;
+        let mut region = Vec::new();
+        let mut open = vec![(start as i32 % width, start as i32 / width)];
+        while let Some((x, y)) = open.pop() {
+            region.push((x, y));
+            for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if nx < 0 || nx >= width || ny < 0 || ny >= length { continue; }
+                let index = (ny * width + nx) as usize;
+                if seen[index] || tiles[index] != TileType::Cave { continue; }
+                seen[index] = true;
+                open.push((nx, ny));
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+fn dig_den(width: i32, length: i32, surface: &mut [TileType], underground: &mut [TileType], den: &Position) {
+    let index = |x: i32, y: i32| (y * width + x) as usize;
+    for y in (den.y - DEN_RADIUS).max(1)..=(den.y + DEN_RADIUS).min(length - 2) {
+        for x in (den.x - DEN_RADIUS).max(1)..=(den.x + DEN_RADIUS).min(width - 2) {
+            if (x - den.x).pow(2) + (y - den.y).pow(2) <= DEN_RADIUS.pow(2) {
+                underground[index(x, y)] = UNDERGROUND_FLOOR;
+            }
+        }
+    }
+    surface[index(den.x, den.y)] = TileType::StairsDown;
+    underground[index(den.x, den.y)] = TileType::StairsUp;
+}
+
+fn terrain_tile(sample: &TerrainSample, moisture: f32, biome: &Biome, rng: &mut impl Rng) -> TileType {
+    let lake_level = LAKE_LEVEL + moisture * LAKE_MOISTURE_SHIFT;
+    let river = sample.river.abs();
+    if sample.elevation < lake_level {
+        TileType::Water
+    } else if sample.elevation < lake_level + SHORE_WIDTH {
+        TileType::Sand
+    } else if sample.elevation > CAVE_LEVEL && sample.detail > 0.0 {
+        TileType::Cave
+    } else if sample.elevation > MOUNTAIN_LEVEL {
+        TileType::Stone
+    } else if river < RIVER_WIDTH {
+        TileType::Water
+    } else if river < RIVER_BANK_WIDTH {
+        TileType::Sand
+    } else {
+        biome.pick_tile(rng)
+    }
+}
+
+fn nearest_biome(candidates: &[Biome], temperature: f32, moisture: f32) -> usize {
+    let mut nearest = 0;
+    let mut nearest_distance = f32::MAX;
+    for (index, biome) in candidates.iter().enumerate() {
+        let Some(patch) = &biome.patch else { continue; };
+        let distance = (patch.temperature - temperature).powi(2) + (patch.moisture - moisture).powi(2);
+        if distance < nearest_distance {
+            nearest = index;
+            nearest_distance = distance;
+        }
+    }
+    nearest
+}
+
+fn near_landing_site(x: i32, y: i32, landing_sites: &[Position]) -> bool {
+    landing_sites.iter().any(|site| (site.x - x).abs() <= LANDING_RADIUS && (site.y - y).abs() <= LANDING_RADIUS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BIOME_DIRECTORY;
+
+    fn world(seed: u64, biomes: &BiomeRegistry) -> Vec<Vec<TileType>> {
+        let landing_sites = [Position { x: 20, y: 20, z: 0 }];
+        let mut rng = GameRng::new(seed);
+        let (levels, _) = generate_world(64, 64, biomes.get("forest").unwrap(), biomes, &landing_sites, &[], rng.stream(RngStream::Map));
+        levels
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_world() {
+        let biomes = BiomeRegistry::load(&asset_path(BIOME_DIRECTORY)).unwrap();
+        let first = world(11, &biomes);
+        assert_eq!(first, world(11, &biomes));
+        assert_ne!(first, world(12, &biomes));
+
+        let surface = &first[0];
+        assert_eq!(surface.len(), 64 * 64);
+        assert!((0..64).all(|i| surface[i] == TileType::WallGame && surface[63 * 64 + i] == TileType::WallGame));
+        // Landing sites get plain biome ground, never water or rock.
+        let landing = &surface[20 * 64 + 20];
+        assert!(!landing.is_wall() && ![TileType::Water, TileType::Stone, TileType::Cave].contains(landing), "{:?}", landing);
+    }
+}
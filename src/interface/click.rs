@@ -56,6 +56,7 @@ pub fn mouse_click_input(
     mut event: EventWriter<ObjectFinderEvent>,
     mut dragging: ResMut<Dragging>,
     mut selection_event: EventWriter<SelectionEvent>,
+    view_level: Res<ViewLevel>,
 ) {
     if mouse_button_input.just_pressed(MouseButton::Left) {
         let (camera, camera_transform) = q_camera.single();
@@ -92,7 +93,7 @@ pub fn mouse_click_input(
             if y < 164.0 { return; }
         }
         if let Some(screen_pos) = window.cursor_position() {
-            position = Some(mouse_to_position(camera, camera_transform, window, screen_pos));
+            position = Some(mouse_to_position(camera, camera_transform, window, screen_pos, view_level.z));
         }
         if let Some(position) = position {
             event.send(ObjectFinderEvent { position });
@@ -112,9 +113,10 @@ pub fn mouse_drag_system(
     windows: Query<&mut Window>,
     q_camera: Query<(&Camera, &GlobalTransform)>,
     dragging: Res<Dragging>,
-    positions: Query<(Entity, &Position, Option<&Highlighted>)>,
+    positions: Query<(Entity, &Position, Option<&Highlighted>, &Visibility)>,
     // highlights: Query<(Entity, &Position), With<Highlighted>>,
-    highlightboxes: Query<(Entity, &Parent), With<HighlightBox>>
+    highlightboxes: Query<(Entity, &Parent), With<HighlightBox>>,
+    view_level: Res<ViewLevel>,
 ) {
     // Yes, this runs all the time.
     // But it's not a problem, because it's a no-op if we're not dragging.
@@ -125,14 +127,15 @@ pub fn mouse_drag_system(
     let window = windows.single();
     let mut end_position = None;
     if let Some(screen_pos) = window.cursor_position() {
-        end_position = Some(mouse_to_position(camera, camera_transform, window, screen_pos));
+        end_position = Some(mouse_to_position(camera, camera_transform, window, screen_pos, view_level.z));
     }
     if end_position.is_none() { return; }
     let end_position = end_position.unwrap();
     // Now just take all objects with a position that matches and mark them as "Highlighted".
     // Somehow only allow the types I want to be highlighted. Foragable. Unit. Choppable. Food. Storable.
-    for (entity, pos, highlighted) in positions.iter() {
-        if (start_position.x.min(end_position.x) <= pos.x) && (pos.x <= start_position.x.max(end_position.x) && (start_position.y.min(end_position.y) <= pos.y) && (pos.y <= start_position.y.max(end_position.y))) {
+    for (entity, pos, highlighted, visibility) in positions.iter() {
+        // Nothing the colony can't see can be picked.
+        if *visibility != Visibility::Hidden && pos.z == start_position.z && (start_position.x.min(end_position.x) <= pos.x) && (pos.x <= start_position.x.max(end_position.x) && (start_position.y.min(end_position.y) <= pos.y) && (pos.y <= start_position.y.max(end_position.y))) {
             if highlighted.is_some() { continue; }
             let highlight_box = commands.spawn(SpriteBundle {
                 sprite: Sprite {
@@ -140,7 +143,7 @@ pub fn mouse_drag_system(
                     custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
                     ..default()
                 },
-                transform: Transform::from_xyz(0.0, 0.0, pos.z as f32 + 0.1),//pos.x as f32, pos.y as f32, pos.z as f32 + 0.1),
+                transform: Transform::from_xyz(0.0, 0.0, 0.1),//pos.x as f32, pos.y as f32, pos.z as f32 + 0.1),
                 ..default()
             }).insert(HighlightBox).id();
             commands.entity(entity).insert(Highlighted );
@@ -161,21 +164,33 @@ pub fn mouse_move_system(
     windows: Query<&mut Window>,
     q_camera: Query<(&Camera, &GlobalTransform)>,
     // dragging: Res<Dragging>, Use to only highlight a specific type in the future??
-    positions: Query<(Entity, &Position, Option<&Brain>, Option<&Food>, Option<&Plant>)>,
+    positions: Query<(Entity, &Position, Option<&Brain>, Option<&Food>, Option<&Plant>, &Visibility)>,
     mut object_info: ResMut<SelectedObjectInformation>,
+    view_level: Res<ViewLevel>,
+    rooms: Res<crate::Rooms>,
+    vision: Res<crate::ColonyVision>,
+    tile_temperatures: Res<crate::TileTemperatures>,
 ) {
     let (camera, camera_transform) = q_camera.single();
     let window = windows.single();
     let mut pos = None;
     if let Some(screen_pos) = window.cursor_position() {
-        pos = Some(mouse_to_position(camera, camera_transform, window, screen_pos));
+        pos = Some(mouse_to_position(camera, camera_transform, window, screen_pos, view_level.z));
     }
     if pos.is_none() { return; }
     let pos = pos.unwrap();
     // Append info for each object to the SelectedObjectInfo.
     object_info.info = vec![];
-    for (_e, p, b, f, plant) in positions.iter() {
-        if (p.x == pos.x) && (p.y == pos.y) {
+    if vision.is_revealed(&pos) {
+        if let Some(room) = rooms.room_at(&pos) {
+            object_info.info.push(room.describe());
+        }
+        if let Some(temperature) = tile_temperatures.at(&pos) {
+            object_info.info.push(format!("Temperature: {:.1}C", temperature));
+        }
+    }
+    for (_e, p, b, f, plant, visibility) in positions.iter() {
+        if *p == pos && *visibility != Visibility::Hidden {
             if let Some(f) = f {
                 object_info.info.push(f.hover_note());
             }
@@ -197,18 +212,16 @@ pub fn mouse_move_system(
 pub fn object_finder_system(
     mut commands: Commands,
     mut event: EventReader<ObjectFinderEvent>,
-    mut people: Query<(Entity, &Position, &mut Brain, Option<&PhysicalBody>, Option<&ClickedOn>)>,
+    mut people: Query<(Entity, &Position, &mut Brain, Option<&PhysicalBody>, Option<&ClickedOn>, &Visibility)>,
 ) {
     for event in event.iter() {
-        for (entity, position, _brain, physical_body, clickedon) in people.iter_mut() {
+        for (entity, position, _brain, physical_body, clickedon, visibility) in people.iter_mut() {
             if clickedon.is_some() {
                 commands.entity(entity).remove::<ClickedOn>();
                 continue;
             }
-            if position == &event.position {
-                if physical_body.is_some() {
-                    commands.entity(entity).insert(ClickedOn);
-                }
+            if position == &event.position && *visibility != Visibility::Hidden && physical_body.is_some() {
+                commands.entity(entity).insert(ClickedOn);
             }
         }
     }
@@ -224,6 +237,7 @@ fn mouse_to_position(
     camera_transform: &GlobalTransform,
     window: &Window,
     screen_pos: Vec2,
+    z: i32,
 ) -> Position {
     // get the size of the window
     let window_size = Vec2::new(window.width(), window.height());
@@ -254,5 +268,5 @@ fn mouse_to_position(
     // event.send(ObjectFinderEvent { position });
     // dragging.dragging = true;
     // dragging.start_position = Some(position);
-    Position { x: (world_pos.x / TILE_SIZE) as i32, y: (world_pos.y / TILE_SIZE) as i32, z: 0 }
+    Position { x: (world_pos.x / TILE_SIZE) as i32, y: (world_pos.y / TILE_SIZE) as i32, z }
 }
\ No newline at end of file
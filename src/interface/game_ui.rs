@@ -8,7 +8,7 @@ impl Plugin for GameUiPlugin {
         app
         .add_systems(
             Startup,
-            initialize_game_ui
+            (initialize_game_ui, initialize_speed_buttons)
         )
         .add_systems(
             OnEnter(GameState::InGame),
@@ -18,6 +18,10 @@ impl Plugin for GameUiPlugin {
             Update,
             game_ui_click.run_if(in_state(GameState::InGame))
         )
+        .add_systems(
+            Update,
+            speed_button_system
+        )
         // .add_startup_system(initialize_game_ui)
         // .add_system_set(
         //     SystemSet::on_enter(GameState::InGame)
@@ -70,6 +74,74 @@ pub fn initialize_game_ui(
     });
 }
 
+pub fn initialize_speed_buttons(
+    mut commands: Commands,
+    font: Res<MyFont>,
+) {
+    let text_style = TextStyle {
+        font: font.0.clone(),
+        font_size: 18.0,
+        color: Color::WHITE,
+    };
+    for (i, (speed, label)) in [(0, "||"), (1, "1x"), (2, "2x"), (4, "4x")].iter().enumerate() {
+        commands.spawn((ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0 + 40.0 * (3 - i) as f32),
+                top: Val::Px(10.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Px(36.0),
+                height: Val::Px(28.0),
+                ..default()
+            },
+            background_color: Color::rgba(0.65, 0.65, 0.85, 0.65).into(),
+            ..default()
+        }, SpeedButton { speed: *speed })).with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(label.to_string(), text_style.clone())
+                .with_alignment(TextAlignment::Center),
+                ..default()
+            });
+        });
+    }
+}
+
+// Pause/1x/2x/4x buttons. The active one is highlighted.
+pub fn speed_button_system(
+    mut buttons: Query<(&Interaction, &SpeedButton, &mut BackgroundColor)>,
+    mut clock: ResMut<SimClock>,
+    gamestate: Res<State<GameState>>,
+    mut nextstate: ResMut<bevy::ecs::schedule::NextState<GameState>>,
+) {
+    let mut paused = *gamestate.get() == GameState::Paused;
+    for (interaction, button, _) in buttons.iter() {
+        if *interaction != Interaction::Pressed { continue; }
+        match (button.speed, gamestate.get()) {
+            (0, GameState::InGame) => {
+                nextstate.set(GameState::Paused);
+                paused = true;
+            }
+            (0, _) => {}
+            (speed, state) => {
+                clock.set_speed(speed);
+                if *state == GameState::Paused {
+                    nextstate.set(GameState::InGame);
+                    paused = false;
+                }
+            }
+        }
+    }
+    for (_, button, mut color) in buttons.iter_mut() {
+        let active = if paused { button.speed == 0 } else { button.speed == clock.speed };
+        *color = if active {
+            Color::rgba(0.35, 0.75, 0.35, 0.85).into()
+        } else {
+            Color::rgba(0.65, 0.65, 0.85, 0.65).into()
+        };
+    }
+}
+
 pub fn start_game_ui(
     mut commands: Commands,
     font: Res<MyFont>,
@@ -115,6 +187,8 @@ pub fn start_game_ui(
         "BED",
         "TABLE",
         "CHAIR",
+        "CAMPFIRE",
+        "COOLER",
     ],
     ]
     ;
@@ -245,6 +319,16 @@ pub fn game_ui_click(
                                 dragging.zone_type = ZoneType::Construction;
                                 dragging.item_type = ItemType::WallWood;
                             },
+                            6 => {
+                                dragging.looking_for = SelectableType::Zoning;
+                                dragging.zone_type = ZoneType::Construction;
+                                dragging.item_type = ItemType::Campfire;
+                            },
+                            7 => {
+                                dragging.looking_for = SelectableType::Zoning;
+                                dragging.zone_type = ZoneType::Construction;
+                                dragging.item_type = ItemType::Cooler;
+                            },
                             _ => {
                                 dragging.looking_for = SelectableType::Nothing;
                                 menu_state.state = MenuStates::Home;
@@ -63,6 +63,7 @@ pub fn info_system(
     mut commands: Commands,
     mut people: Query<(Entity, &Position, &Brain, &PhysicalBody, Option<&HasName>), With<ClickedOn>>,
     mut info_panel: ResMut<InfoPanelInformation>,
+    rooms: Res<crate::Rooms>,
 ) {
     if let Some((_, position, brain, physical_body, has_name)) = people.iter_mut().last() {
         if let Some(has_name) = has_name {
@@ -72,6 +73,9 @@ pub fn info_system(
         }
         info_panel.info = vec![];
         info_panel.info.push(format!("Position: {}, {}", position.x, position.y));
+        if let Some(room) = rooms.room_at(position) {
+            info_panel.info.push(room.describe());
+        }
         info_panel.info.extend_from_slice(&physical_body.info_panel_needs());
         info_panel.info.extend_from_slice(&brain.info_panel());
         info_panel.needs.extend_from_slice(&physical_body.info_panel_needs());
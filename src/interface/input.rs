@@ -1,5 +1,6 @@
 //mod super::prelude;
 use crate::prelude::*;
+use crate::save_system::{LoadGameEvent, SaveGameEvent, SaveSettings};
 
 pub fn keyboard_input(
     _commands: Commands,
@@ -7,6 +8,12 @@ pub fn keyboard_input(
     mut camera: Query<&mut Transform, With<Camera>>,
     gamestate: ResMut<State<GameState>>,
     mut nextstate: ResMut<bevy::ecs::schedule::NextState<GameState>>,
+    save_settings: Res<SaveSettings>,
+    mut save_events: EventWriter<SaveGameEvent>,
+    mut load_events: EventWriter<LoadGameEvent>,
+    mut clock: ResMut<SimClock>,
+    tile_map: Option<Res<TileMap>>,
+    mut view_level: ResMut<ViewLevel>,
 ) {
     if input.just_pressed(KeyCode::Space) {
         // Pause or Unpause.
@@ -20,6 +27,30 @@ pub fn keyboard_input(
             _ => {}
         }
     }
+    // 1, 2 and 4 pick the speed with that multiplier and unpause.
+    for (key, speed) in [KeyCode::Key1, KeyCode::Key2, KeyCode::Key4].iter().zip(SIM_SPEEDS) {
+        if input.just_pressed(*key) {
+            clock.set_speed(speed);
+            if *gamestate.get() == GameState::Paused {
+                nextstate.set(GameState::InGame);
+            }
+        }
+    }
+    if input.just_pressed(KeyCode::F5) {
+        save_events.send(SaveGameEvent { path: save_settings.quicksave_path() });
+    }
+    if input.just_pressed(KeyCode::F9) {
+        load_events.send(LoadGameEvent { path: save_settings.quicksave_path() });
+    }
+    // Page Down looks one level deeper underground, Page Up one level higher.
+    if let Some(tile_map) = &tile_map {
+        if input.just_pressed(KeyCode::PageDown) && view_level.z > tile_map.lowest_level() {
+            view_level.z -= 1;
+        }
+        if input.just_pressed(KeyCode::PageUp) && view_level.z < 0 {
+            view_level.z += 1;
+        }
+    }
     for mut transform in camera.iter_mut() {
         let move_speed = 16.0;
         //transform.translation.x += 5.0;
@@ -33,11 +64,12 @@ pub fn keyboard_input(
         } else if input.any_pressed([KeyCode::Right, KeyCode::D]) {
             next_position.x += move_speed;
         }
-        transform.translation = next_position;
-        if (next_position.x >= -15.0) && (next_position.x < VIEWAREA_WIDTH as f32 * MAP_WIDTH as f32) && (next_position.y >= -15.0) && (next_position.y < VIEWAREA_HEIGHT as f32 * MAP_LENGTH as f32) {
-            //transform.translation = next_position;
+        // Keep the middle of the screen over the map.
+        if let Some(tile_map) = &tile_map {
+            next_position.x = next_position.x.clamp(0.0, (tile_map.width() - 1) as f32 * TILE_SIZE);
+            next_position.y = next_position.y.clamp(0.0, (tile_map.length() - 1) as f32 * TILE_SIZE);
         }
-        //transform.translation = next_position;
+        transform.translation = next_position;
     }
 }
 
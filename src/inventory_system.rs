@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use bevy::prelude::*;
 use crate::prelude::*;
 
@@ -5,7 +6,7 @@ use crate::prelude::*;
 // INVENTORY SYSTEM - Grid-based inventory like Raft/The Long Dark
 // ============================================================================
 
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Inventory {
     pub slots: Vec<Option<InventoryItem>>,
     pub max_slots: usize,
@@ -104,7 +105,7 @@ impl Inventory {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InventoryItem {
     pub item_def: ItemDefinition,
     pub quantity: u32,
@@ -112,7 +113,7 @@ pub struct InventoryItem {
     pub data: ItemData, // Additional data per item type
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ItemDefinition {
     pub id: String,
     pub name: String,
@@ -125,7 +126,7 @@ pub struct ItemDefinition {
     pub sprite_index: usize,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ItemClass {
     Weapon(WeaponStats),
     Armor(ArmorStats),
@@ -137,7 +138,7 @@ pub enum ItemClass {
     Ammo(AmmoStats),
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct WeaponStats {
     pub damage: i32,
     pub attack_speed: f32,
@@ -148,7 +149,7 @@ pub struct WeaponStats {
     pub ammo_type: Option<String>,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum WeaponType {
     Melee,
     Railgun,
@@ -160,7 +161,7 @@ pub enum WeaponType {
     Hammer,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArmorStats {
     pub defense: i32,
     pub armor_type: ArmorType,
@@ -168,7 +169,7 @@ pub struct ArmorStats {
     pub durability: f32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ArmorType {
     Head,
     Chest,
@@ -178,14 +179,14 @@ pub enum ArmorType {
     Shield,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolStats {
     pub tool_type: ToolType,
     pub efficiency: f32,
     pub durability: f32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ToolType {
     Axe,
     Pickaxe,
@@ -196,7 +197,7 @@ pub enum ToolType {
     Knife,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClothingStats {
     pub warmth: f32,
     pub waterproof: f32,
@@ -204,7 +205,7 @@ pub struct ClothingStats {
     pub slot: ClothingSlot,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ClothingSlot {
     Head,
     Face,
@@ -215,7 +216,7 @@ pub enum ClothingSlot {
     Accessory,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct FoodStats {
     pub nutrition: f32,
     pub calories: i32, // The Long Dark style
@@ -224,13 +225,13 @@ pub struct FoodStats {
     pub cooking_required: bool,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConsumableStats {
     pub effect: ConsumableEffect,
     pub duration: f32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConsumableEffect {
     Healing(i32),
     Warmth(f32),
@@ -238,13 +239,13 @@ pub enum ConsumableEffect {
     Buff(String),
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct AmmoStats {
     pub ammo_type: String,
     pub damage_modifier: f32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ItemData {
     None,
     Food { spoilage: f32 },
@@ -257,7 +258,7 @@ pub enum ItemData {
 // EQUIPMENT SYSTEM
 // ============================================================================
 
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Equipment {
     pub weapon_main: Option<InventoryItem>,
     pub weapon_offhand: Option<InventoryItem>,
@@ -297,6 +298,39 @@ impl Default for Equipment {
 }
 
 impl Equipment {
+    // Puts the item in the first free slot that suits it, or hands it back if there is none.
+    #[allow(clippy::result_large_err)]
+    pub fn equip(&mut self, item: InventoryItem) -> Result<(), InventoryItem> {
+        let slots: Vec<&mut Option<InventoryItem>> = match &item.item_def.item_class {
+            ItemClass::Weapon(_) => vec![&mut self.weapon_main],
+            ItemClass::Tool(_) => vec![&mut self.weapon_offhand, &mut self.weapon_main],
+            ItemClass::Armor(stats) => vec![match stats.armor_type {
+                ArmorType::Head => &mut self.armor_head,
+                ArmorType::Chest => &mut self.armor_chest,
+                ArmorType::Legs => &mut self.armor_legs,
+                ArmorType::Feet => &mut self.armor_feet,
+                ArmorType::Hands => &mut self.armor_hands,
+                ArmorType::Shield => &mut self.weapon_offhand,
+            }],
+            ItemClass::Clothing(stats) => match stats.slot {
+                ClothingSlot::Head => vec![&mut self.clothing_head],
+                ClothingSlot::Chest => vec![&mut self.clothing_chest],
+                ClothingSlot::Legs => vec![&mut self.clothing_legs],
+                ClothingSlot::Feet => vec![&mut self.clothing_feet],
+                ClothingSlot::Hands => vec![&mut self.clothing_hands],
+                ClothingSlot::Face | ClothingSlot::Accessory => vec![&mut self.accessory1, &mut self.accessory2],
+            },
+            _ => vec![],
+        };
+        match slots.into_iter().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(item);
+                Ok(())
+            }
+            None => Err(item),
+        }
+    }
+
     pub fn total_defense(&self) -> i32 {
         let mut total = 0;
         if let Some(item) = &self.armor_head {
@@ -348,6 +382,17 @@ impl Equipment {
         total
     }
 
+    // Efficiency of the best tool of this type in hand, if any.
+    pub fn tool_efficiency(&self, tool_type: ToolType) -> Option<f32> {
+        [&self.weapon_main, &self.weapon_offhand].into_iter()
+            .flatten()
+            .filter_map(|item| match &item.item_def.item_class {
+                ItemClass::Tool(stats) if stats.tool_type == tool_type => Some(stats.efficiency),
+                _ => None,
+            })
+            .reduce(f32::max)
+    }
+
     pub fn weapon_damage(&self) -> i32 {
         if let Some(item) = &self.weapon_main {
             if let ItemClass::Weapon(stats) = &item.item_def.item_class {
@@ -359,14 +404,66 @@ impl Equipment {
 }
 
 // ============================================================================
-// ITEM DATABASE - Pre-defined items
+// ITEM REGISTRY - Item definitions loaded from assets/items
 // ============================================================================
 
-pub struct ItemDatabase;
+pub const ITEM_DIRECTORY: &str = "assets/items";
+
+#[derive(Resource, Default)]
+pub struct ItemRegistry {
+    items: HashMap<String, ItemDefinition>,
+    ids: Vec<String>,
+}
+
+impl ItemRegistry {
+    // Every .ron file in the directory holds a list of item definitions.
+    pub fn load(directory: &Path) -> Result<Self, Vec<String>> {
+        let files = read_ron_files::<Vec<ItemDefinition>>(directory)?;
+        let mut registry = ItemRegistry::default();
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut errors = Vec::new();
+        for (path, definitions) in files {
+            for definition in definitions {
+                if let Err(e) = definition.validate() {
+                    errors.push(format!("{}: item '{}': {}", path.display(), definition.id, e));
+                    continue;
+                }
+                if let Some(first) = sources.get(&definition.id) {
+                    errors.push(format!("{}: item '{}': field `id` is already defined in {}", path.display(), definition.id, first.display()));
+                    continue;
+                }
+                sources.insert(definition.id.clone(), path.clone());
+                registry.ids.push(definition.id.clone());
+                registry.items.insert(definition.id.clone(), definition);
+            }
+        }
+        for id in &registry.ids {
+            if let ItemClass::Weapon(WeaponStats { ammo_type: Some(ammo_type), .. }) = &registry.items[id].item_class {
+                let ammo_exists = registry.items.values()
+                    .any(|item| matches!(&item.item_class, ItemClass::Ammo(ammo) if &ammo.ammo_type == ammo_type));
+                if !ammo_exists {
+                    errors.push(format!("{}: item '{}': field `ammo_type` names unknown ammo '{}'", sources[id].display(), id, ammo_type));
+                }
+            }
+        }
+        if errors.is_empty() { Ok(registry) } else { Err(errors) }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ItemDefinition> {
+        self.items.get(id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.items.contains_key(id)
+    }
 
-impl ItemDatabase {
-    pub fn create_item(id: &str, quantity: u32) -> Option<InventoryItem> {
-        let def = Self::get_definition(id)?;
+    // In load order.
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    pub fn create_item(&self, id: &str, quantity: u32) -> Option<InventoryItem> {
+        let def = self.get(id)?;
         Some(InventoryItem {
             item_def: def.clone(),
             quantity,
@@ -380,518 +477,48 @@ impl ItemDatabase {
             },
         })
     }
+}
 
-    pub fn get_definition(id: &str) -> Option<ItemDefinition> {
-        match id {
-            // RESOURCES
-            "wood" => Some(ItemDefinition {
-                id: "wood".to_string(),
-                name: "Wood".to_string(),
-                description: "Basic building material".to_string(),
-                item_class: ItemClass::Resource,
-                weight: 2.0,
-                value: 1,
-                stackable: true,
-                max_stack: 100,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "stone" => Some(ItemDefinition {
-                id: "stone".to_string(),
-                name: "Stone".to_string(),
-                description: "Heavy building material".to_string(),
-                item_class: ItemClass::Resource,
-                weight: 5.0,
-                value: 2,
-                stackable: true,
-                max_stack: 50,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "iron_ore" => Some(ItemDefinition {
-                id: "iron_ore".to_string(),
-                name: "Iron Ore".to_string(),
-                description: "Raw iron ore for smelting".to_string(),
-                item_class: ItemClass::Resource,
-                weight: 3.0,
-                value: 5,
-                stackable: true,
-                max_stack: 50,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "iron_bar" => Some(ItemDefinition {
-                id: "iron_bar".to_string(),
-                name: "Iron Bar".to_string(),
-                description: "Refined iron for crafting".to_string(),
-                item_class: ItemClass::Resource,
-                weight: 2.5,
-                value: 10,
-                stackable: true,
-                max_stack: 50,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "cloth" => Some(ItemDefinition {
-                id: "cloth".to_string(),
-                name: "Cloth".to_string(),
-                description: "Fabric for clothing".to_string(),
-                item_class: ItemClass::Resource,
-                weight: 0.5,
-                value: 3,
-                stackable: true,
-                max_stack: 100,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "leather" => Some(ItemDefinition {
-                id: "leather".to_string(),
-                name: "Leather".to_string(),
-                description: "Tanned animal hide".to_string(),
-                item_class: ItemClass::Resource,
-                weight: 1.0,
-                value: 8,
-                stackable: true,
-                max_stack: 50,
-                sprite_index: 51 * 64 + 8,
-            }),
-
-            // WEAPONS - Melee
-            "wooden_club" => Some(ItemDefinition {
-                id: "wooden_club".to_string(),
-                name: "Wooden Club".to_string(),
-                description: "Basic melee weapon".to_string(),
-                item_class: ItemClass::Weapon(WeaponStats {
-                    damage: 5,
-                    attack_speed: 1.0,
-                    range: 1.0,
-                    weapon_type: WeaponType::Hammer,
-                    durability: 50.0,
-                    required_skill: 0,
-                    ammo_type: None,
-                }),
-                weight: 3.0,
-                value: 10,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "combat_blade" => Some(ItemDefinition {
-                id: "combat_blade".to_string(),
-                name: "Combat Blade".to_string(),
-                description: "Sturdy tactical blade".to_string(),
-                item_class: ItemClass::Weapon(WeaponStats {
-                    damage: 15,
-                    attack_speed: 1.2,
-                    range: 1.5,
-                    weapon_type: WeaponType::Blade,
-                    durability: 100.0,
-                    required_skill: 3,
-                    ammo_type: None,
-                }),
-                weight: 4.0,
-                value: 100,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 19 * 64 + 45,
-            }),
-            "power_axe" => Some(ItemDefinition {
-                id: "power_axe".to_string(),
-                name: "Power Axe".to_string(),
-                description: "Powered combat axe".to_string(),
-                item_class: ItemClass::Weapon(WeaponStats {
-                    damage: 20,
-                    attack_speed: 0.8,
-                    range: 1.5,
-                    weapon_type: WeaponType::PowerAxe,
-                    durability: 120.0,
-                    required_skill: 5,
-                    ammo_type: None,
-                }),
-                weight: 6.0,
-                value: 150,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "plasma_lance" => Some(ItemDefinition {
-                id: "plasma_lance".to_string(),
-                name: "Plasma Lance".to_string(),
-                description: "Long reach energy weapon".to_string(),
-                item_class: ItemClass::Weapon(WeaponStats {
-                    damage: 12,
-                    attack_speed: 1.0,
-                    range: 2.5,
-                    weapon_type: WeaponType::PlasmaLance,
-                    durability: 80.0,
-                    required_skill: 2,
-                    ammo_type: None,
-                }),
-                weight: 3.5,
-                value: 80,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-
-            // RANGED WEAPONS
-            "railgun" => Some(ItemDefinition {
-                id: "railgun".to_string(),
-                name: "Railgun".to_string(),
-                description: "Basic ballistic ranged weapon".to_string(),
-                item_class: ItemClass::Weapon(WeaponStats {
-                    damage: 10,
-                    attack_speed: 0.7,
-                    range: 8.0,
-                    weapon_type: WeaponType::Railgun,
-                    durability: 100.0,
-                    required_skill: 2,
-                    ammo_type: Some("railgun_slug".to_string()),
-                }),
-                weight: 2.0,
-                value: 120,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "gauss_rifle" => Some(ItemDefinition {
-                id: "gauss_rifle".to_string(),
-                name: "Gauss Rifle".to_string(),
-                description: "Powerful gauss rifle".to_string(),
-                item_class: ItemClass::Weapon(WeaponStats {
-                    damage: 18,
-                    attack_speed: 0.5,
-                    range: 10.0,
-                    weapon_type: WeaponType::GaussRifle,
-                    durability: 150.0,
-                    required_skill: 4,
-                    ammo_type: Some("gauss_charge".to_string()),
-                }),
-                weight: 4.5,
-                value: 200,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-
-            // AMMO
-            "railgun_slug" => Some(ItemDefinition {
-                id: "railgun_slug".to_string(),
-                name: "Railgun Slug".to_string(),
-                description: "Ammunition for railguns".to_string(),
-                item_class: ItemClass::Ammo(AmmoStats {
-                    ammo_type: "railgun_slug".to_string(),
-                    damage_modifier: 1.0,
-                }),
-                weight: 0.1,
-                value: 1,
-                stackable: true,
-                max_stack: 100,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "gauss_charge" => Some(ItemDefinition {
-                id: "gauss_charge".to_string(),
-                name: "Gauss Charge".to_string(),
-                description: "Charge for gauss rifles".to_string(),
-                item_class: ItemClass::Ammo(AmmoStats {
-                    ammo_type: "gauss_charge".to_string(),
-                    damage_modifier: 1.2,
-                }),
-                weight: 0.2,
-                value: 2,
-                stackable: true,
-                max_stack: 100,
-                sprite_index: 94 * 64 + 30,
-            }),
-
-            // TOOLS
-            "iron_axe" => Some(ItemDefinition {
-                id: "iron_axe".to_string(),
-                name: "Iron Axe".to_string(),
-                description: "Efficient tree chopping tool".to_string(),
-                item_class: ItemClass::Tool(ToolStats {
-                    tool_type: ToolType::Axe,
-                    efficiency: 1.5,
-                    durability: 200.0,
-                }),
-                weight: 3.0,
-                value: 50,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "iron_pickaxe" => Some(ItemDefinition {
-                id: "iron_pickaxe".to_string(),
-                name: "Iron Pickaxe".to_string(),
-                description: "For mining stone and ore".to_string(),
-                item_class: ItemClass::Tool(ToolStats {
-                    tool_type: ToolType::Pickaxe,
-                    efficiency: 1.5,
-                    durability: 200.0,
-                }),
-                weight: 4.0,
-                value: 60,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-            "fishing_rod" => Some(ItemDefinition {
-                id: "fishing_rod".to_string(),
-                name: "Fishing Rod".to_string(),
-                description: "For catching fish".to_string(),
-                item_class: ItemClass::Tool(ToolStats {
-                    tool_type: ToolType::FishingRod,
-                    efficiency: 1.0,
-                    durability: 100.0,
-                }),
-                weight: 1.5,
-                value: 40,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 94 * 64 + 30,
-            }),
-
-            // ARMOR
-            "leather_helmet" => Some(ItemDefinition {
-                id: "leather_helmet".to_string(),
-                name: "Leather Helmet".to_string(),
-                description: "Basic head protection".to_string(),
-                item_class: ItemClass::Armor(ArmorStats {
-                    defense: 3,
-                    armor_type: ArmorType::Head,
-                    warmth: 1.0,
-                    durability: 100.0,
-                }),
-                weight: 1.5,
-                value: 30,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "iron_helmet" => Some(ItemDefinition {
-                id: "iron_helmet".to_string(),
-                name: "Iron Helmet".to_string(),
-                description: "Strong head protection".to_string(),
-                item_class: ItemClass::Armor(ArmorStats {
-                    defense: 8,
-                    armor_type: ArmorType::Head,
-                    warmth: 0.5,
-                    durability: 200.0,
-                }),
-                weight: 3.0,
-                value: 100,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "leather_armor" => Some(ItemDefinition {
-                id: "leather_armor".to_string(),
-                name: "Leather Armor".to_string(),
-                description: "Light body protection".to_string(),
-                item_class: ItemClass::Armor(ArmorStats {
-                    defense: 5,
-                    armor_type: ArmorType::Chest,
-                    warmth: 2.0,
-                    durability: 150.0,
-                }),
-                weight: 4.0,
-                value: 80,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "iron_armor" => Some(ItemDefinition {
-                id: "iron_armor".to_string(),
-                name: "Iron Armor".to_string(),
-                description: "Heavy body protection".to_string(),
-                item_class: ItemClass::Armor(ArmorStats {
-                    defense: 15,
-                    armor_type: ArmorType::Chest,
-                    warmth: 1.0,
-                    durability: 250.0,
-                }),
-                weight: 10.0,
-                value: 250,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-
-            // CLOTHING - The Long Dark style
-            "wool_hat" => Some(ItemDefinition {
-                id: "wool_hat".to_string(),
-                name: "Wool Hat".to_string(),
-                description: "Keeps your head warm".to_string(),
-                item_class: ItemClass::Clothing(ClothingStats {
-                    warmth: 3.0,
-                    waterproof: 0.2,
-                    windproof: 0.5,
-                    slot: ClothingSlot::Head,
-                }),
-                weight: 0.3,
-                value: 20,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "winter_coat" => Some(ItemDefinition {
-                id: "winter_coat".to_string(),
-                name: "Winter Coat".to_string(),
-                description: "Heavy winter protection".to_string(),
-                item_class: ItemClass::Clothing(ClothingStats {
-                    warmth: 10.0,
-                    waterproof: 0.6,
-                    windproof: 0.8,
-                    slot: ClothingSlot::Chest,
-                }),
-                weight: 2.0,
-                value: 150,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "leather_gloves" => Some(ItemDefinition {
-                id: "leather_gloves".to_string(),
-                name: "Leather Gloves".to_string(),
-                description: "Protects hands from cold".to_string(),
-                item_class: ItemClass::Clothing(ClothingStats {
-                    warmth: 2.0,
-                    waterproof: 0.4,
-                    windproof: 0.6,
-                    slot: ClothingSlot::Hands,
-                }),
-                weight: 0.4,
-                value: 30,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "boots" => Some(ItemDefinition {
-                id: "boots".to_string(),
-                name: "Leather Boots".to_string(),
-                description: "Sturdy footwear".to_string(),
-                item_class: ItemClass::Clothing(ClothingStats {
-                    warmth: 3.0,
-                    waterproof: 0.7,
-                    windproof: 0.5,
-                    slot: ClothingSlot::Feet,
-                }),
-                weight: 1.2,
-                value: 50,
-                stackable: false,
-                max_stack: 1,
-                sprite_index: 51 * 64 + 8,
-            }),
-
-            // FOOD
-            "cooked_meat" => Some(ItemDefinition {
-                id: "cooked_meat".to_string(),
-                name: "Cooked Meat".to_string(),
-                description: "Nutritious cooked meat".to_string(),
-                item_class: ItemClass::Food(FoodStats {
-                    nutrition: 30.0,
-                    calories: 400,
-                    hydration: 0.0,
-                    spoilage_rate: 0.05,
-                    cooking_required: false,
-                }),
-                weight: 0.5,
-                value: 15,
-                stackable: true,
-                max_stack: 20,
-                sprite_index: 94 * 64 + 32,
-            }),
-            "raw_meat" => Some(ItemDefinition {
-                id: "raw_meat".to_string(),
-                name: "Raw Meat".to_string(),
-                description: "Needs cooking".to_string(),
-                item_class: ItemClass::Food(FoodStats {
-                    nutrition: 15.0,
-                    calories: 200,
-                    hydration: 0.0,
-                    spoilage_rate: 0.2,
-                    cooking_required: true,
-                }),
-                weight: 0.5,
-                value: 8,
-                stackable: true,
-                max_stack: 20,
-                sprite_index: 94 * 64 + 32,
-            }),
-            "fish" => Some(ItemDefinition {
-                id: "fish".to_string(),
-                name: "Fish".to_string(),
-                description: "Fresh caught fish".to_string(),
-                item_class: ItemClass::Food(FoodStats {
-                    nutrition: 20.0,
-                    calories: 250,
-                    hydration: 5.0,
-                    spoilage_rate: 0.3,
-                    cooking_required: true,
-                }),
-                weight: 0.4,
-                value: 10,
-                stackable: true,
-                max_stack: 20,
-                sprite_index: 94 * 64 + 32,
-            }),
-            "bread" => Some(ItemDefinition {
-                id: "bread".to_string(),
-                name: "Bread".to_string(),
-                description: "Basic food staple".to_string(),
-                item_class: ItemClass::Food(FoodStats {
-                    nutrition: 15.0,
-                    calories: 300,
-                    hydration: 0.0,
-                    spoilage_rate: 0.08,
-                    cooking_required: false,
-                }),
-                weight: 0.3,
-                value: 5,
-                stackable: true,
-                max_stack: 30,
-                sprite_index: 94 * 64 + 32,
-            }),
-
-            // CONSUMABLES
-            "bandage" => Some(ItemDefinition {
-                id: "bandage".to_string(),
-                name: "Bandage".to_string(),
-                description: "Stops bleeding".to_string(),
-                item_class: ItemClass::Consumable(ConsumableStats {
-                    effect: ConsumableEffect::Healing(20),
-                    duration: 0.0,
-                }),
-                weight: 0.1,
-                value: 10,
-                stackable: true,
-                max_stack: 50,
-                sprite_index: 51 * 64 + 8,
-            }),
-            "medkit" => Some(ItemDefinition {
-                id: "medkit".to_string(),
-                name: "Medkit".to_string(),
-                description: "Nanite injector that restores health".to_string(),
-                item_class: ItemClass::Consumable(ConsumableStats {
-                    effect: ConsumableEffect::Healing(50),
-                    duration: 0.0,
-                }),
-                weight: 0.5,
-                value: 50,
-                stackable: true,
-                max_stack: 10,
-                sprite_index: 51 * 64 + 8,
-            }),
+impl ItemDefinition {
+    fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("field `id` is empty".to_string());
+        }
+        if self.weight < 0.0 {
+            return Err(format!("field `weight` is {}, it can't be negative", self.weight));
+        }
+        if self.max_stack == 0 {
+            return Err("field `max_stack` must be at least 1".to_string());
+        }
+        if !self.stackable && self.max_stack != 1 {
+            return Err(format!("field `max_stack` is {}, but items that aren't stackable stack to 1", self.max_stack));
+        }
+        let durability = match &self.item_class {
+            ItemClass::Weapon(stats) => Some(stats.durability),
+            ItemClass::Armor(stats) => Some(stats.durability),
+            ItemClass::Tool(stats) => Some(stats.durability),
             _ => None,
+        };
+        if let Some(durability) = durability {
+            if durability <= 0.0 {
+                return Err(format!("field `item_class.durability` is {}, it must be positive", durability));
+            }
+        }
+        match &self.item_class {
+            ItemClass::Tool(stats) if stats.efficiency <= 0.0 => {
+                Err(format!("field `item_class.efficiency` is {}, it must be positive", stats.efficiency))
+            }
+            ItemClass::Clothing(stats) if !(0.0..=1.0).contains(&stats.waterproof) => {
+                Err(format!("field `item_class.waterproof` is {}, it must be between 0.0 and 1.0", stats.waterproof))
+            }
+            ItemClass::Clothing(stats) if !(0.0..=1.0).contains(&stats.windproof) => {
+                Err(format!("field `item_class.windproof` is {}, it must be between 0.0 and 1.0", stats.windproof))
+            }
+            ItemClass::Food(stats) if stats.spoilage_rate < 0.0 => {
+                Err(format!("field `item_class.spoilage_rate` is {}, it can't be negative", stats.spoilage_rate))
+            }
+            _ => Ok(()),
         }
-    }
-
-    pub fn all_item_ids() -> Vec<&'static str> {
-        vec![
-            "wood", "stone", "iron_ore", "iron_bar", "cloth", "leather",
-            "wooden_club", "combat_blade", "power_axe", "plasma_lance", "railgun", "gauss_rifle",
-            "railgun_slug", "gauss_charge",
-            "iron_axe", "iron_pickaxe", "fishing_rod",
-            "leather_helmet", "iron_helmet", "leather_armor", "iron_armor",
-            "wool_hat", "winter_coat", "leather_gloves", "boots",
-            "cooked_meat", "raw_meat", "fish", "bread",
-            "bandage", "medkit",
-        ]
     }
 }
 
@@ -903,18 +530,22 @@ pub struct InventoryPlugin;
 
 impl Plugin for InventoryPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (
+        let registry = ItemRegistry::load(&asset_path(ITEM_DIRECTORY))
+            .unwrap_or_else(|errors| exit_with_data_errors("items", &errors));
+        app.insert_resource(registry);
+        app.add_systems(SimTick, (
             inventory_degradation_system,
             equipment_bonus_system,
-        ).run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs(5))));
+        ).run_if(every_seconds(5.0)));
     }
 }
 
 // System to degrade equipment over time (The Long Dark style)
 fn inventory_degradation_system(
-    mut query: Query<(&mut Inventory, &mut Equipment)>,
+    mut query: Query<(&mut Inventory, &mut Equipment, Option<&Position>)>,
+    tile_temperatures: Res<crate::TileTemperatures>,
 ) {
-    for (mut inventory, mut equipment) in query.iter_mut() {
+    for (mut inventory, mut equipment, position) in query.iter_mut() {
         // Degrade equipped items
         let mut degrade_item = |item: &mut Option<InventoryItem>| {
             if let Some(inv_item) = item {
@@ -934,12 +565,13 @@ fn inventory_degradation_system(
         degrade_item(&mut equipment.clothing_chest);
         degrade_item(&mut equipment.clothing_head);
 
-        // Degrade food items in inventory
+        // Degrade food items in inventory, as fast as the carrier's tile would spoil them
+        let spoilage_factor = crate::spoilage_factor_at(position, &tile_temperatures);
         for slot in inventory.slots.iter_mut() {
             if let Some(item) = slot {
                 if let ItemClass::Food(food_stats) = &item.item_def.item_class {
                     if let ItemData::Food { spoilage } = &mut item.data {
-                        *spoilage -= food_stats.spoilage_rate * 0.01;
+                        *spoilage -= food_stats.spoilage_rate * 0.01 * spoilage_factor;
                         if *spoilage <= 0.0 {
                             *slot = None; // Food spoiled
                         }
@@ -959,3 +591,61 @@ fn equipment_bonus_system(
         // This could add temporary stat bonuses from equipment
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, item_class: &str, stackable: bool, max_stack: u32) -> String {
+        format!(
+            "(id: \"{}\", name: \"{}\", description: \"\", item_class: {}, weight: 1.0, value: 1, \
+             stackable: {}, max_stack: {}, sprite_index: 0)",
+            id, id, item_class, stackable, max_stack,
+        )
+    }
+
+    fn rifle(ammo_type: &str) -> String {
+        let stats = format!(
+            "Weapon((damage: 10, attack_speed: 1.0, range: 8.0, weapon_type: Melee, durability: 100.0, \
+             required_skill: 0, ammo_type: Some(\"{}\")))",
+            ammo_type,
+        );
+        item("rifle", &stats, false, 1)
+    }
+
+    #[test]
+    fn shipped_items_load() {
+        let registry = ItemRegistry::load(&asset_path(ITEM_DIRECTORY)).unwrap();
+        assert!(!registry.ids().is_empty());
+    }
+
+    #[test]
+    fn items_load_in_file_order() {
+        let directory = scratch_directory("items-order", &[
+            ("a.ron", &format!("[{}, {}]", item("wood", "Resource", true, 50), rifle("bullet"))),
+            ("b.ron", &format!("[{}]", item("bullet", "Ammo((ammo_type: \"bullet\", damage_modifier: 1.0))", true, 100))),
+        ]);
+        let registry = ItemRegistry::load(&directory).unwrap();
+        assert_eq!(registry.ids(), ["wood", "rifle", "bullet"]);
+        assert_eq!(registry.create_item("wood", 3).unwrap().quantity, 3);
+        assert!(registry.create_item("stone", 1).is_none());
+    }
+
+    #[test]
+    fn bad_items_are_rejected() {
+        let directory = scratch_directory("items-bad", &[
+            ("a.ron", &format!("[{}, {}, {}]",
+                item("wood", "Resource", true, 50),
+                item("sword", "Resource", false, 5),
+                rifle("bullet"),
+            )),
+            ("b.ron", &format!("[{}, {}]", item("wood", "Resource", true, 50), item("stone", "Resource", true, 0))),
+        ]);
+        let errors = ItemRegistry::load(&directory).err().expect("items should not load");
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("a.ron: item 'sword': field `max_stack` is 5")));
+        assert!(errors.iter().any(|e| e.contains("b.ron: item 'wood': field `id` is already defined in")));
+        assert!(errors.iter().any(|e| e.contains("b.ron: item 'stone': field `max_stack` must be at least 1")));
+        assert!(errors.iter().any(|e| e.contains("item 'rifle': field `ammo_type` names unknown ammo 'bullet'")));
+    }
+}
@@ -1,17 +1,40 @@
+// Bevy systems take their queries as parameters, so long query types are the norm here.
+#![allow(clippy::type_complexity)]
+// Modules written before the tree was linted with `clippy -D warnings` allow the lints their
+// existing code trips. Drop a module's allow once that module has been tidied up.
+
+#[allow(ambiguous_glob_reexports)]
 mod prelude;
 pub use crate::prelude::*;
 
 mod button_system;
 use button_system::*;
+#[allow(dead_code, unused_imports, unused_mut, unused_variables, clippy::clone_on_copy, clippy::manual_clamp, clippy::unnecessary_unwrap)]
 mod combat_system;
 use combat_system::*;
+#[allow(clippy::len_zero, clippy::needless_return)]
 mod components;
 mod constants;
+mod data_files;
+mod fire_system;
+use fire_system::*;
+mod flow_field_system;
+use flow_field_system::*;
+mod fov_system;
+use fov_system::*;
+mod headless;
+use headless::*;
+mod heat_system;
+use heat_system::*;
 mod initializations;
 use initializations::*;
+#[allow(clippy::manual_clamp, clippy::too_many_arguments)]
 mod interface;
 use interface::*;
+#[allow(clippy::match_like_matches_macro)]
 mod objects;
+mod pathfinding;
+use pathfinding::*;
 mod monstergenerator_system;
 use monstergenerator_system::*;
 mod moverandom_system;
@@ -24,40 +47,80 @@ mod names_system;
 use names_system::*;
 mod needs;
 use needs::*;
+#[allow(clippy::clone_on_copy)]
 mod nest;
 use nest::*;
 mod resources;
+mod room_system;
+use room_system::*;
+mod save_system;
+use save_system::*;
 mod seasons;
 use seasons::*;
+#[allow(clippy::redundant_field_names, clippy::too_many_arguments, clippy::unnecessary_unwrap)]
 mod selection_systems;
 use selection_systems::*;
+mod sim_clock;
+mod tile_map;
+mod simulation;
+use simulation::*;
 mod spoilage_system;
 use spoilage_system::*;
 mod statusdisplay_system;
 use statusdisplay_system::*;
+#[allow(unused_mut, unused_variables, clippy::collapsible_if, clippy::needless_borrow, clippy::needless_return, clippy::ptr_arg, clippy::unnecessary_unwrap)]
 mod task_system;
 use task_system::*;
 mod text_system;
 use text_system::*;
 mod thinking_system;
 use thinking_system::*;
+#[allow(clippy::clone_on_copy)]
 mod unitgenerator_system;
 use unitgenerator_system::*;
 
 // NEW AAA SYSTEMS
+#[allow(unused_mut, unused_variables, clippy::collapsible_if, clippy::derivable_impls, clippy::manual_flatten)]
 mod inventory_system;
+#[allow(unused_imports)]
 mod crafting_system;
+#[allow(clippy::manual_range_contains)]
 mod weather_system;
+#[allow(clippy::derivable_impls)]
 mod tech_system;
+#[allow(unused_mut, unused_variables)]
 mod building_system;
+#[allow(unused_mut, unused_variables, clippy::approx_constant)]
 mod formation_system;
+#[allow(unused_mut, unused_variables, clippy::derivable_impls)]
 mod diplomacy_system;
 
 fn main() {
     //println!("Hello, world!");
+    let args: Vec<String> = std::env::args().collect();
+    if export_tech_dot_from_args(&args) {
+        return;
+    }
+    if bench_paths_from_args(&args) {
+        return;
+    }
+    let seed = GameRng::seed_from_args(&args);
+    println!("Seed: {}", seed);
+    let scenario = match Scenario::from_args(&args) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("Scenario: {}", scenario.name);
+    if let Some(options) = HeadlessOptions::from_args(&args) {
+        run_headless(options, seed, scenario);
+        return;
+    }
     App::new()
         .insert_resource(Msaa::Off)
-        .add_plugins((DefaultPlugins, BiomePlugin, StartupPlugin))
+        .add_plugins((DefaultPlugins, SimulationPlugin { seed, scenario }))
         .add_systems(
             PreStartup, (load_sprites, load_font, load_sfx)
         )
@@ -67,22 +130,10 @@ fn main() {
             state: MenuStates::Home,
         })
         .add_systems(
-            Startup, (generate_map, setup_camera, text_test, set_window_title, set_window_icon, set_window_maximized)
+            Startup, (setup_camera, text_test, set_window_title, set_window_icon, set_window_maximized)
         )
-        .add_state::<GameState>()
         .add_plugins((MainMenusPlugin, ButtonPlugin))
-        .add_systems(
-            Update,
-            movement_random
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.1)))
-            .run_if(in_state(GameState::InGame))
-        )
-        .add_plugins(
-            (SelectionPlugin, MonsterGeneratorPlugin, MovementPlugin, SeasonsPlugin, NeedsPlugin, GameUiPlugin,
-                InfoPanelPlugin, ThinkingPlugin, TaskPlugin, CombatPlugin, SpoilagePlugin, ClickPlugin,
-                // NEW AAA PLUGINS
-                InventoryPlugin, CraftingPlugin, WeatherPlugin, TechPlugin,
-                BuildingPlugin, FormationPlugin, DiplomacyPlugin))
+        .add_plugins((SelectionPlugin, GameUiPlugin, InfoPanelPlugin, ClickPlugin, TileChunkPlugin, FogOfWarPlugin))
         .add_systems(
             Update,
             status_display_system
@@ -91,16 +142,11 @@ fn main() {
         )
         .add_systems(Update, (
             text_system,
-            remove_bad_positions,
-            namegiving_system,
             names_system,
             text_update_system,
-            movement_toward_attackable,
             keyboard_input,
             scrollwheel_input,
-            nest_system,
         ))
-        .add_event::<FoodNotifEvent>()
         .add_systems(
             Update,
             bevy::window::close_on_esc
@@ -122,25 +168,18 @@ fn main() {
 //     *state == GameState::InGame
 // }
 
-fn setup_camera(mut commands: Commands) {
+// Starts over the middle of whatever the scenario placed, or the middle of the map.
+fn setup_camera(mut commands: Commands, scenario: Res<Scenario>) {
+    let sites = scenario.landing_sites();
+    let (min_x, max_x) = sites.iter().map(|site| site.x).fold((i32::MAX, i32::MIN), |(min, max), x| (min.min(x), max.max(x)));
+    let (min_y, max_y) = sites.iter().map(|site| site.y).fold((i32::MAX, i32::MIN), |(min, max), y| (min.min(y), max.max(y)));
+    let (center_x, center_y) = if sites.is_empty() {
+        (scenario.map.width as f32 / 2.0, scenario.map.length as f32 / 2.0)
+    } else {
+        ((min_x + max_x) as f32 / 2.0, (min_y + max_y) as f32 / 2.0)
+    };
     let mut camera = Camera2dBundle::default();
-    camera.transform.translation.x = TILE_SIZE * 19.0;
-    camera.transform.translation.y = TILE_SIZE * 11.0;
+    camera.transform.translation.x = TILE_SIZE * center_x;
+    camera.transform.translation.y = TILE_SIZE * center_y;
     commands.spawn(camera);
 }
-
-fn remove_bad_positions(
-    mut commands: Commands,
-    query: Query<(Entity, &Position), Without<MapTile>>,
-    tiletypes: Res<TileHash>,
-) {
-    for (entity, position) in query.iter() {
-        if tiletypes.hash.contains_key(position) {
-            if tiletypes.hash.get(position).unwrap().is_wall() {
-                commands.entity(entity).despawn();
-            }
-        } else {
-            commands.entity(entity).despawn();
-        }
-    }
-}
@@ -1,4 +1,4 @@
-use crate::{prelude::*, unitgenerator_system::spawn_unit_from_template};
+use crate::{prelude::*, unitgenerator_system::{spawn_unit_from_template, UnitRegistry}};
 
 // Make plugin
 pub struct MonsterGeneratorPlugin;
@@ -6,7 +6,7 @@ pub struct MonsterGeneratorPlugin;
 impl Plugin for MonsterGeneratorPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update, monster_generator.run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
+            SimTick, monster_generator.run_if(every_seconds(0.5))
         );
     }
 }
@@ -14,13 +14,17 @@ impl Plugin for MonsterGeneratorPlugin {
 pub fn monster_generator(
     mut commands: Commands,
     entities: Query<(Entity, &Position, &MonsterGenerator)>,
-    tile_types: Query<(&Position, &TileType)>,
+    tile_map: Res<TileMap>,
     generated_monsters: Query<(Entity, &GeneratedBy)>,
-    sprite_sheet: Res<SpriteSheet>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
+    units: Res<UnitRegistry>,
+    mut game_rng: ResMut<GameRng>,
 ) {
+    let rng = game_rng.stream(RngStream::Monsters);
     for (entity, position, monster_generator) in entities.iter() {
-        let mut new_position = *position;
-        let dir = random::<i32>() % 4;
+        // Monsters hatch in the den below the cave, if the map has one.
+        let mut new_position = tile_map.stairs_exit(position).unwrap_or(*position);
+        let dir = rng.gen_range(0..4);
         match dir {
             0 => new_position.y += 1,
             1 => new_position.y -= 1,
@@ -28,14 +32,7 @@ pub fn monster_generator(
             3 => new_position.x += 1,
             _ => {}
         }
-        let mut can_generate = false;
-        for (tile_position, tile_type) in tile_types.iter() {
-            let mut p2 = new_position;
-            p2.z = 0;
-            if *tile_position == p2 && !tile_type.is_wall() {
-                can_generate = true;
-            }
-        }
+        let mut can_generate = !tile_map.is_wall(&new_position);
         for (_ent, parent) in generated_monsters.iter() {
             if parent.entity == entity {
                 can_generate = false;
@@ -44,7 +41,8 @@ pub fn monster_generator(
         if !can_generate {
             return;
         }
-        let monster = spawn_unit_from_template(&mut commands, new_position, &sprite_sheet, monster_generator.pick());
+        let Some(template) = units.roll(monster_generator.pick(rng), rng) else { continue; };
+        let monster = spawn_unit_from_template(&mut commands, new_position, sprite_sheet.as_deref(), &template, rng);
         commands.entity(monster).insert(GeneratedBy { entity });
         
         //*position = new_position;
@@ -7,24 +7,26 @@ pub struct MovementPlugin;
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems( Update, (movement_path_generating, clear_unreachable_paths))
+        .init_resource::<crate::PassabilityGrid>()
+        .add_systems( SimTick, (crate::update_passability, reroute_around_changed_tiles, movement_path_generating, clear_unreachable_paths).chain())
         .add_systems(
-            Update,
+            SimTick,
             movement_along_path
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(0.5))
         )
         .add_systems(
-            PostUpdate,
-            update_paths_for_moving_targets
+            SimTick,
+            update_paths_for_moving_targets.after(movement_along_path)
         )
         ;
     }
 }
 
+// Monsters close in on whoever is nearest. They all share one flow field to the colony rather
+// than each planning a path.
 pub fn movement_toward_attackable(
     mut commands: Commands,
-    attackers: Query<(Entity, &Position), (With<MoveTowardsNearestAttackable>, Without<Pathing>)>,
+    attackers: Query<(Entity, &Position), (With<MoveTowardsNearestAttackable>, Without<Pathing>, Without<crate::FollowFlowField>)>,
     attackables: Query<(Entity, &Position), With<Attackable>>
 ) {
     for (attacker, attacker_position) in attackers.iter() {
@@ -39,199 +41,46 @@ pub fn movement_toward_attackable(
         }
         if let Some(closest_target) = closest_target {
             commands.entity(attacker).insert(Targeting { target: closest_target });
-            let target_position = attackables.get(closest_target).unwrap().1;
-            commands.entity(attacker).insert( Pathing { path: vec![], destination: *target_position, ..default() });
+            commands.entity(attacker).insert(crate::FollowFlowField::new(crate::FlowGoal::NearestHostile));
         }
     }
 }
-#[derive(Clone, Debug)]
-struct Node {
-    position: Position,
-    g: i32,
-    f: i32,
-    parent: Option<Position>,
-}
 pub fn movement_path_generating(
-    mut entities: Query<(&Position, &mut Pathing)>,
-    tilehash: Res<TileHash>,
-    objects: Query<(&Object, &Position)>,
-    zones: Query<(&Zone, &Position)>,
+    mut entities: Query<(&Position, &mut Pathing, Option<&MovementProfile>)>,
+    tile_map: Res<TileMap>,
+    grid: Res<crate::PassabilityGrid>,
 ) {
-    let tiletypes: &std::collections::HashMap<Position, TileType> = &tilehash.hash;
-    // Find all objects that you can not pass through, as well as any zones of places to avoid.
-    let mut obstacles: std::collections::HashSet<Position> = objects
-        .iter()
-        .filter_map(|(object, position)| {
-            if object.itemtype.passable() == false {
-                Some(*position)
-            } else {
-                None
-            }
-        })
-        .collect();
-    for (zone, position) in zones.iter() {
-        if zone.zone_type == ZoneType::Avoid { // TO DO: Only apply this to humans.
-            obstacles.insert(*position);
-        }
-    }
-    
-    for (start_position, mut pathing) in entities.iter_mut() {
-        // println!("Pathing: {:?} to destination: {:?} - Unreachable? {:?}", pathing.path, pathing.destination, pathing.unreachable);
+    for (start_position, mut pathing, movement) in entities.iter_mut() {
         let destination = pathing.destination;
         if !pathing.path.is_empty() { continue; }
-        pathing.path = generate_path(start_position, &destination, tiletypes, &obstacles);
-        
-        // F = G + H
-        // G = distance from start
-        // H = distance from end
-
-        // let mut openlist: std::collections::HashMap<Position, Node> = std::collections::HashMap::new();
-        // let mut closedlist: std::collections::HashMap<Position, Node> = std::collections::HashMap::new();
-        // openlist.insert(*start_position, Node { position: *start_position, g: 0, f: 0, parent: None });
-        // while !openlist.is_empty() {
-        //     let mut current_node = None;
-        //     let mut lowest_f = -1;
-        //     for (_position, node) in openlist.iter() {
-        //         if lowest_f == -1 || node.f < lowest_f {
-        //             current_node = Some(node);
-        //             lowest_f = node.f;
-        //         }
-        //     }
-        //     if current_node.is_none() {
-        //         break;
-        //     }
-        //     let current_node = current_node.unwrap().clone();
-        //     let mut current_position = current_node.position;
-        //     openlist.remove(&current_position);
-        //     // Add n to the CLOSED list
-        //     let g = current_node.g + 1;
-        //     let h = current_position.distance(&destination);
-        //     let f = g + h;
-        //     closedlist.insert(current_position, Node { position: current_position, g, f, parent: current_node.parent });
-        //     // IF n is the same as the goal, we have a solution. Backtrack to find the path.
-        //     if current_position == destination {
-        //         let mut nodelist: Vec<Position> = vec![];
-        //         loop {
-        //             nodelist.push(current_position);
-        //             if closedlist.get(&current_position).is_none() { break; }
-        //             match closedlist.get(&current_position).unwrap().parent {
-        //                 None => break,
-        //                 _ => current_position = closedlist.get(&current_position).unwrap().parent.unwrap(),
-        //             }
-        //         }
-        //         pathing.path = nodelist;
-        //         break;
-        //     }
-        //     let mut neighbors: Vec<Position> = vec![];
-        //     neighbors.push(Position { x: current_position.x + 1, y: current_position.y, z: 0 });
-        //     neighbors.push(Position { x: current_position.x - 1, y: current_position.y, z: 0 });
-        //     neighbors.push(Position { x: current_position.x, y: current_position.y + 1, z: 0 });
-        //     neighbors.push(Position { x: current_position.x, y: current_position.y - 1, z: 0 });
-
-        //     for neighbor in neighbors {
-        //         if tiletypes.get(&neighbor).is_none() { continue; }
-        //         if tiletypes.get(&neighbor).unwrap().is_wall() {
-        //             continue;
-        //         }
-        //         let h = neighbor.distance(&destination);
-        //         let g = current_node.g + 1;
-        //         let f = g + h;
-        //         if openlist.contains_key(&neighbor) && g > openlist.get(&neighbor).unwrap().g {
-        //             continue;
-        //         }
-        //         if closedlist.contains_key(&neighbor) && g > closedlist.get(&neighbor).unwrap().g {
-        //             continue;
-        //         }
-        //         openlist.remove(&neighbor);
-        //         closedlist.remove(&neighbor);
-        //         openlist.insert(neighbor, Node { position: neighbor, g, f, parent: Some(current_position) });
-        //     }
-        // }
+        let movement = movement.copied().unwrap_or_default();
+        // No point searching for a way into another region.
+        if !grid.is_reachable(&tile_map, start_position, &destination, &movement) {
+            pathing.unreachable = true;
+            continue;
+        }
+        pathing.path = grid.find_path(&tile_map, start_position, &destination, &movement);
         // Unreachable!
         if pathing.path.is_empty() {
             pathing.unreachable = true;
         }
     }
 }
-fn generate_path(
-    start_position: &Position,
-    destination: &Position,
-    tiletypes: &std::collections::HashMap<Position, TileType>,
-    obstacles: &std::collections::HashSet<Position>,
-) -> Vec<Position> {
-    // F = G + H
-    // G = distance from start
-    // H = distance from end
-    let mut openlist: std::collections::HashMap<Position, Node> = std::collections::HashMap::new();
-    let mut closedlist: std::collections::HashMap<Position, Node> = std::collections::HashMap::new();
-    let mut nodelist: Vec<Position> = vec![];
-    openlist.insert(*start_position, Node { position: *start_position, g: 0, f: 0, parent: None });
-    while !openlist.is_empty() {
-        let mut current_node = None;
-        let mut lowest_f = -1;
-        for (_position, node) in openlist.iter() {
-            if lowest_f == -1 || node.f < lowest_f {
-                current_node = Some(node);
-                lowest_f = node.f;
-            }
-        }
-        if current_node.is_none() {
-            break;
-        }
-        let current_node = current_node.unwrap().clone();
-        let mut current_position = current_node.position;
-        openlist.remove(&current_position);
-        // Add n to the CLOSED list
-        let g = current_node.g + 1;
-        let h = current_position.distance(&destination);
-        let f = g + h;
-        closedlist.insert(current_position, Node { position: current_position, g, f, parent: current_node.parent });
-        // IF n is the same as the goal, we have a solution. Backtrack to find the path.
-        if current_position == *destination {
-            loop {
-                if current_position != *start_position { nodelist.push(current_position); }
-                if closedlist.get(&current_position).is_none() { break; }
-                match closedlist.get(&current_position).unwrap().parent {
-                    None => break,
-                    _ => current_position = closedlist.get(&current_position).unwrap().parent.unwrap(),
-                }
-            }
-            return nodelist;
-        }
-        let mut neighbors: Vec<Position> = vec![];
-        neighbors.push(Position { x: current_position.x + 1, y: current_position.y, z: 0 });
-        neighbors.push(Position { x: current_position.x - 1, y: current_position.y, z: 0 });
-        neighbors.push(Position { x: current_position.x, y: current_position.y + 1, z: 0 });
-        neighbors.push(Position { x: current_position.x, y: current_position.y - 1, z: 0 });
-
-        for neighbor in neighbors {
-            if tiletypes.get(&neighbor).is_none() { continue; }
-            if tiletypes.get(&neighbor).unwrap().is_wall() {
-                continue;
-            }
-            if obstacles.contains(&neighbor) {
-                continue;
-            }
-            let h = neighbor.distance(&destination);
-            let g = current_node.g + 1;
-            let f = g + h;
-            if openlist.contains_key(&neighbor) && g > openlist.get(&neighbor).unwrap().g {
-                continue;
-            }
-            if closedlist.contains_key(&neighbor) && g > closedlist.get(&neighbor).unwrap().g {
-                continue;
-            }
-            openlist.remove(&neighbor);
-            closedlist.remove(&neighbor);
-            openlist.insert(neighbor, Node { position: neighbor, g, f, parent: Some(current_position) });
 
+// Paths were planned on the old ground, so any that cross a changed tile get planned again.
+pub fn reroute_around_changed_tiles(
+    mut changes: EventReader<TileChangedEvent>,
+    mut entities: Query<&mut Pathing>,
+) {
+    let changed: std::collections::HashSet<Position> = changes.iter().map(|change| change.position).collect();
+    if changed.is_empty() { return; }
+    for mut pathing in entities.iter_mut() {
+        if pathing.path.iter().any(|position| changed.contains(position)) {
+            pathing.path.clear();
         }
     }
-
-    nodelist
 }
 
-
 pub fn clear_unreachable_paths(
     mut commands: Commands,
     entities: Query<(Entity, &Pathing)>,
@@ -243,75 +92,87 @@ pub fn clear_unreachable_paths(
     }
 }
 pub fn update_paths_for_moving_targets(
-    mut entities: Query<(&Position, &Targeting, &mut Pathing)>,
+    mut entities: Query<(&Position, &Targeting, &mut Pathing, Option<&MovementProfile>)>,
     targets: Query<(Entity, &Position)>,
-    tilehash: Res<TileHash>,
-    objects: Query<(&Object, &Position)>,
-    zones: Query<(&Zone, &Position)>,
+    tile_map: Res<TileMap>,
+    grid: Res<crate::PassabilityGrid>,
 ) {
-    let tiletypes: &std::collections::HashMap<Position, TileType> = &tilehash.hash;
-    let obstacles = collect_obstacles(objects, zones, tiletypes);
-    for (start_position, target, mut pathing) in entities.iter_mut() {
+    for (start_position, target, mut pathing, movement) in entities.iter_mut() {
         if !pathing.moving_target { continue; }
         pathing.moving_target = false;
         for (target_entity, target_position) in targets.iter() {
             if target_entity == target.target {
-                pathing.path = generate_path(start_position, &target_position, tiletypes, &obstacles);
+                pathing.path = grid.find_path(&tile_map, start_position, target_position, &movement.copied().unwrap_or_default());
                 // println!("Path: {:?}", pathing.path)
             }
         }
     }
 }
 
+// Damage a door breaker does to a gate in its way each tick, per point of strength.
+const DOOR_BASH_DAMAGE: i32 = 50;
+
+// Units cover BASE_MOVEMENT_COST each tick and take the next step once they have covered its
+// cost, so rough ground, water, snow and diagonals slow them down.
 pub fn movement_along_path(
     mut commands: Commands,
-    mut entities: Query<(Entity, &mut Position, &mut Pathing, &mut Transform)>,
+    mut entities: Query<(Entity, &mut Position, &mut Pathing, &mut Transform, Option<&MovementProfile>, Option<&PhysicalBody>)>,
+    mut gates: Query<(Entity, &Position, &mut Building), Without<Pathing>>,
+    grid: Res<crate::PassabilityGrid>,
 ) {
-    for (entity, mut position, mut pathing, mut transform) in entities.iter_mut() {
-        if pathing.path.is_empty() { continue; }
-        let next_position = pathing.path.pop().unwrap();
-        *position = next_position;
-        let next_transform = next_position.to_transform();
-        transform.translation.x = next_transform.translation.x;
-        transform.translation.y = next_transform.translation.y;
+    for (entity, mut position, mut pathing, mut transform, movement, physical_body) in entities.iter_mut() {
+        let Some(next_position) = pathing.path.last().copied() else { continue; };
+        let movement = movement.copied().unwrap_or_default();
+        let strength = physical_body.map_or(1, |body| body.attributes.strength);
+        if !advance(&mut commands, &mut gates, &grid, &mut position, &mut transform, &mut pathing.progress, next_position, &movement, strength) {
+            continue;
+        }
+        pathing.path.pop();
         if pathing.path.is_empty() {
             commands.entity(entity).remove::<Pathing>();
         }
     }
 }
-pub fn collect_obstacles(
-    objects: Query<(&Object, &Position)>,
-    zones: Query<(&Zone, &Position)>,
-    tiletypes: &std::collections::HashMap<Position, TileType>,
-) -> std::collections::HashSet<Position> {
-    let mut obstacles: std::collections::HashSet<Position> = objects
-        .iter()
-        .filter_map(|(object, position)| {
-            if object.itemtype.passable() == false {
-                Some(*position)
-            } else {
-                None
+
+// Moves a unit a tick's worth towards the neighbouring `next`, returning whether it got there.
+// A unit that can't open a gate in its way batters it until it gives.
+#[allow(clippy::too_many_arguments)]
+pub fn advance<F: bevy::ecs::query::ReadOnlyWorldQuery>(
+    commands: &mut Commands,
+    gates: &mut Query<(Entity, &Position, &mut Building), F>,
+    grid: &crate::PassabilityGrid,
+    position: &mut Position,
+    transform: &mut Transform,
+    progress: &mut i32,
+    next: Position,
+    movement: &MovementProfile,
+    strength: i32,
+) -> bool {
+    if grid.has_door(&next) && !movement.opens_doors {
+        for (gate, _, mut building) in gates.iter_mut().filter(|(_, gate_position, _)| **gate_position == next) {
+            building.health -= DOOR_BASH_DAMAGE * strength.max(1);
+            if building.health <= 0 {
+                commands.entity(gate).despawn_recursive();
             }
-        })
-        .collect();
-    for (zone, position) in zones.iter() {
-        if zone.zone_type == ZoneType::Avoid { // TO DO: Only apply this to humans.
-            obstacles.insert(*position);
         }
+        return false;
     }
-    for (position, tiletype) in tiletypes.iter() {
-        if tiletype.is_wall() {
-            obstacles.insert(*position);
-        }
-    }
-    obstacles
+    *progress += BASE_MOVEMENT_COST;
+    let cost = grid.step_cost(position, &next, movement);
+    if *progress < cost { return false; }
+    *progress -= cost;
+    *position = next;
+    let next_transform = next.to_transform();
+    transform.translation.x = next_transform.translation.x;
+    transform.translation.y = next_transform.translation.y;
+    true
 }
 pub fn is_position_reachable(
     start_position: &Position,
     end_position: &Position,
-    obstacles: &std::collections::HashSet<Position>,
-    tiletypes: &std::collections::HashMap<Position, TileType>,
+    grid: &crate::PassabilityGrid,
+    tile_map: &TileMap,
+    movement: &MovementProfile,
 ) -> bool {
-    let path = generate_path(start_position, end_position, tiletypes, obstacles);
-    !path.is_empty()
-}
\ No newline at end of file
+    grid.is_reachable(tile_map, start_position, end_position, movement)
+}
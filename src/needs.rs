@@ -1,15 +1,19 @@
 use super::prelude::*;
 
+// Dehydration gets one step more severe for every this many ticks without water.
+const DEHYDRATION_TICKS_PER_SEVERITY: i32 = 30;
+// Recovering takes a third as long as getting dehydrated did.
+const DEHYDRATION_RECOVERY_RATE: i32 = 3;
+
 // Make plugin.
 pub struct NeedsPlugin;
 
 impl Plugin for NeedsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
+            SimTick,
             needs_status_system
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(2.0)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(2.0))
         )
         ;
     }
@@ -37,8 +41,95 @@ pub fn needs_status_system(
                 needs_sleep.current = 0.0;
             }
         }
+        if let Some(needs_water) = physical_body.needs_water.as_mut() {
+            needs_water.current -= needs_water.rate;
+            if needs_water.current < 0.0 {
+                needs_water.current = 0.0;
+            }
+        }
+        update_dehydration(&mut physical_body);
+    }
+}
+
+// Going without water dehydrates a unit, worse the longer it lasts. Once it has drunk again,
+// the dehydration eases off and goes away.
+fn update_dehydration(physical_body: &mut PhysicalBody) {
+    let Some(needs_water) = physical_body.needs_water else { return; };
+    let parched = needs_water.current <= 0.0;
+    let dehydration = physical_body.afflictions.iter_mut()
+        .position(|affliction| matches!(affliction.affliction_type, AfflictionType::Dehydration));
+    match (dehydration, parched) {
+        (Some(index), _) => {
+            let affliction = &mut physical_body.afflictions[index];
+            affliction.worsening = parched;
+            affliction.duration = if parched { affliction.duration + 1 } else { (affliction.duration - DEHYDRATION_RECOVERY_RATE).max(0) };
+            affliction.severity = 1 + affliction.duration / DEHYDRATION_TICKS_PER_SEVERITY;
+            if !parched && affliction.duration == 0 {
+                physical_body.afflictions.remove(index);
+            }
+        }
+        (None, true) => physical_body.afflictions.push(Affliction {
+            affliction_type: AfflictionType::Dehydration,
+            affliction_location: AfflictionLocation::Torso,
+            duration: 0,
+            severity: 1,
+            worsening: true,
+        }),
+        (None, false) => {}
     }
 }
 
 #[derive(Event)]
 pub struct FoodNotifEvent;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thirsty_body() -> PhysicalBody {
+        PhysicalBody {
+            needs_food: None,
+            needs_entertainment: None,
+            needs_sleep: None,
+            needs_water: Some(Need { current: 1.0, max: 100.0, rate: 1.0, low: 15.0, normal: 30.0, high: 80.0 }),
+            index: 0,
+            crisis: None,
+            danger: None,
+            injured: false,
+            afflictions: vec![],
+            skillset: Skillset::default(),
+            attributes: Attributeset::default(),
+        }
+    }
+
+    fn dehydration(physical_body: &PhysicalBody) -> Option<&Affliction> {
+        physical_body.afflictions.iter().find(|affliction| matches!(affliction.affliction_type, AfflictionType::Dehydration))
+    }
+
+    #[test]
+    fn thirst_dehydrates_until_the_unit_drinks() {
+        let mut world = World::new();
+        let unit = world.spawn(thirsty_body()).id();
+        let mut schedule = Schedule::new();
+        schedule.add_systems(needs_status_system);
+
+        schedule.run(&mut world);
+        let body = world.get::<PhysicalBody>(unit).unwrap();
+        assert_eq!(body.needs_water.unwrap().current, 0.0);
+        assert_eq!(dehydration(body).map(|affliction| (affliction.severity, affliction.worsening)), Some((1, true)));
+
+        for _ in 0..DEHYDRATION_TICKS_PER_SEVERITY {
+            schedule.run(&mut world);
+        }
+        assert_eq!(dehydration(world.get::<PhysicalBody>(unit).unwrap()).unwrap().severity, 2);
+
+        // Once it has drunk, the dehydration eases off and goes away.
+        world.get_mut::<PhysicalBody>(unit).unwrap().needs_water.as_mut().unwrap().current = 100.0;
+        schedule.run(&mut world);
+        assert!(!dehydration(world.get::<PhysicalBody>(unit).unwrap()).unwrap().worsening);
+        for _ in 0..DEHYDRATION_TICKS_PER_SEVERITY {
+            schedule.run(&mut world);
+        }
+        assert!(dehydration(world.get::<PhysicalBody>(unit).unwrap()).is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use crate::prelude::*;
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Object {
     pub itemtype: ItemType,
     pub remaining_resources: Vec<(ItemType, u8)>,
@@ -17,11 +17,6 @@ impl Default for Object {
     }
 }
 
-#[derive(Component)]
-pub struct ItemReplacements {
-    pub replacements: Vec<ItemType>,
-}
-
 #[derive(Clone, Copy)]
 enum ItemGroup {
     Logs,
@@ -34,7 +29,7 @@ impl ItemGroup {
     pub fn items(&self) -> Vec<ItemType> {
         match self {
             ItemGroup::Logs => vec![ItemType::CedarLog, ItemType::PineLog, ItemType::OakLog],
-            ItemGroup::Stones => vec![ItemType::Goo1],
+            ItemGroup::Stones => vec![ItemType::Goo1, ItemType::Stone, ItemType::IronOre],
             ItemGroup::Walls => vec![ItemType::WallWood],
             ItemGroup::Statues => vec![ItemType::StatuePillar1,
                 ItemType::StatuePillar2,
@@ -91,11 +86,13 @@ impl ItemGroup {
     }
 }
 
-#[derive(Component, PartialEq, Copy, Clone, Debug)]
+#[derive(Component, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum ItemType {
     CedarLog,
     PineLog,
     OakLog,
+    Stone,
+    IronOre,
     StatuePillar1,
     StatuePillar2,
     StatuePillar3,
@@ -154,6 +151,8 @@ pub enum ItemType {
     WallMetal,
     WallStone,
     WallWood,
+    Campfire,
+    Cooler,
     Aloe,
     Azalea,
     Bush,
@@ -176,6 +175,8 @@ impl ItemType {
             ItemType::CedarLog => (94, 30),
             ItemType::PineLog => (94, 30),
             ItemType::OakLog => (94, 30),
+            ItemType::Stone => (51, 8),
+            ItemType::IronOre => (51, 14),
             ItemType::StatuePillar1 => (19, 12),
             ItemType::StatuePillar2 => (19, 13),
             ItemType::StatuePillar3 => (19, 14),
@@ -234,6 +235,8 @@ impl ItemType {
             ItemType::WallMetal => (1, 61),
             ItemType::WallStone => (3, 10),
             ItemType::WallWood => (6, 32),
+            ItemType::Campfire => (52, 28),
+            ItemType::Cooler => (7, 40),
             ItemType::Aloe => (67, 57),
             ItemType::Azalea => (67, 57),
             ItemType::Bush => (67, 57),
@@ -304,9 +307,22 @@ impl ItemType {
             }
         }
     }
+    // Degrees added to the tile it stands on each heat update; coolers take heat away.
+    pub fn heat_output(&self) -> f32 {
+        match self {
+            ItemType::Campfire => 4.0,
+            ItemType::Cooler => -3.0,
+            _ => 0.0,
+        }
+    }
+    pub fn is_wall(&self) -> bool {
+        matches!(self.group(), ItemGroup::Walls)
+    }
     pub fn construction_needs(&self) -> Vec<(ItemType, u8)> {
         match self {
             ItemType::WallWood => vec![(ItemType::CedarLog, 10)],
+            ItemType::Campfire => vec![(ItemType::CedarLog, 3)],
+            ItemType::Cooler => vec![(ItemType::Stone, 5)],
             ItemType::Carrot => vec![],
             _ => {
                 vec![]
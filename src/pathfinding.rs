@@ -0,0 +1,907 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Mutex;
+use std::time::Instant;
+use crate::prelude::*;
+
+// ============================================================================
+// PATHFINDING - A cached passability grid and A* over it
+// ============================================================================
+
+// A diagonal step costs this many tenths of a straight one.
+const DIAGONAL_TENTHS: i32 = 14;
+// Cost of tunnelling into a tile of rock, and extra cost of smashing through a gate, so both
+// are only done when the way round is much longer.
+const BURROW_COST: i32 = 40;
+const DOOR_BREAK_COST: i32 = 100;
+const STRAIGHT: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+#[derive(Clone, Copy, Default)]
+struct Terrain {
+    wall: bool,
+    // Rock a burrower can tunnel through.
+    rock: bool,
+    water: bool,
+    stairs: bool,
+    muddy: bool,
+    // Under a roof, out of the snow and rain.
+    sheltered: bool,
+    cost: i32,
+}
+
+impl Terrain {
+    fn of(tile_type: &TileType) -> Self {
+        Terrain {
+            wall: tile_type.is_wall(),
+            rock: tile_type.is_wall() && tile_type.is_mineable(),
+            water: *tile_type == TileType::Water,
+            stairs: matches!(tile_type, TileType::StairsDown | TileType::StairsUp),
+            muddy: tile_type.is_muddy(),
+            sheltered: false,
+            cost: tile_type.movement_cost(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Blocker {
+    // Statues and the like, which flyers pass over.
+    Object,
+    // Wall objects and wall buildings.
+    Wall,
+    // Gates.
+    Door,
+    Avoid,
+}
+
+const BLOCKERS: [Blocker; 4] = [Blocker::Object, Blocker::Wall, Blocker::Door, Blocker::Avoid];
+
+// What can be walked where, one flat grid per map level laid out like the TileMap's levels.
+// Tiles are copied in from the map, and objects, walls, doors and Avoid zones are counted per
+// tile, since several can stand on one. Each unit's MovementProfile picks which of them stop it.
+// Everything is kept up to date as it changes rather than rebuilt for every search.
+#[derive(Resource, Default)]
+pub struct PassabilityGrid {
+    width: i32,
+    length: i32,
+    terrain: Vec<Vec<Terrain>>,
+    objects: Vec<Vec<u16>>,
+    walls: Vec<Vec<u16>>,
+    doors: Vec<Vec<u16>>,
+    avoid: Vec<Vec<u16>>,
+    // Whether units may step diagonally, between two open tiles.
+    diagonal: bool,
+    // Extra cost of open-air surface tiles in the current weather, and of muddy ones on top.
+    snow_cost: i32,
+    mud_cost: i32,
+    // Where each blocker was counted, so a move or removal can be taken back.
+    counted: HashMap<(Entity, u8), Position>,
+    // Reused between searches so a search doesn't allocate grids the size of the map.
+    scratch: Mutex<SearchScratch>,
+    // Connected regions per way of getting about, labelled when first asked for and patched up
+    // as tiles open up or close off.
+    regions: Mutex<HashMap<MovementProfile, Regions>>,
+    // Tiles that may have opened up or closed off since the regions were last patched up, and
+    // whether stairs were built or removed there.
+    changed: Vec<(Position, bool)>,
+}
+
+// Region label of a tile nobody with that movement can stand on.
+const NO_REGION: u32 = u32::MAX;
+
+// Each tile's label, and which labels have since been joined into one region. Labels only
+// ever merge here; when a region may have split, its tiles get new labels instead.
+struct Regions {
+    labels: Vec<u32>,
+    roots: Vec<u32>,
+}
+
+impl Regions {
+    fn new_label(&mut self) -> u32 {
+        self.roots.push(self.roots.len() as u32);
+        self.roots.len() as u32 - 1
+    }
+
+    fn root(&mut self, mut label: u32) -> u32 {
+        while self.roots[label as usize] != label {
+            let parent = self.roots[label as usize];
+            self.roots[label as usize] = self.roots[parent as usize];
+            label = parent;
+        }
+        label
+    }
+
+    fn region(&mut self, node: usize) -> u32 {
+        match self.labels[node] {
+            NO_REGION => NO_REGION,
+            label => self.root(label),
+        }
+    }
+
+    fn join(&mut self, a: u32, b: u32) {
+        let (a, b) = (self.root(a), self.root(b));
+        if a != b { self.roots[b as usize] = a; }
+    }
+}
+
+#[derive(Default)]
+struct SearchScratch {
+    search: u32,
+    // A node's `g` and `parent` are only meaningful if its `seen` matches the current search.
+    seen: Vec<u32>,
+    g: Vec<i32>,
+    parent: Vec<u32>,
+}
+
+impl PassabilityGrid {
+    pub fn from_tile_map(tile_map: &TileMap) -> Self {
+        let mut grid = PassabilityGrid::default();
+        grid.refresh_terrain(tile_map);
+        grid
+    }
+
+    fn refresh_terrain(&mut self, tile_map: &TileMap) {
+        self.width = tile_map.width();
+        self.length = tile_map.length();
+        let levels = (1 - tile_map.lowest_level()) as usize;
+        let size = (self.width * self.length) as usize;
+        self.terrain = (0..levels).map(|level| {
+            let z = -(level as i32);
+            (0..size as i32).map(|index| {
+                let position = Position { x: index % self.width, y: index / self.width, z };
+                tile_map.get(&position).map_or(Terrain { wall: true, ..default() }, Terrain::of)
+            }).collect()
+        }).collect();
+        self.objects = vec![vec![0; size]; levels];
+        self.walls = vec![vec![0; size]; levels];
+        self.doors = vec![vec![0; size]; levels];
+        self.avoid = vec![vec![0; size]; levels];
+        self.counted.clear();
+        self.forget_regions();
+    }
+
+    fn fits(&self, tile_map: &TileMap) -> bool {
+        self.width == tile_map.width() && self.length == tile_map.length()
+            && self.terrain.len() == (1 - tile_map.lowest_level()) as usize
+    }
+
+    fn set_tile(&mut self, position: &Position, tile_type: &TileType) {
+        let Some((level, index)) = self.index(position) else { return; };
+        let old = self.terrain[level][index];
+        self.terrain[level][index] = Terrain { sheltered: old.sheltered, ..Terrain::of(tile_type) };
+        // Mined out, walled up, flooded, or dug into stairs.
+        let stairs = old.stairs || self.terrain[level][index].stairs;
+        self.changed.push((*position, stairs));
+    }
+
+    // Marks which surface tiles are roofed over, and so kept clear of snow and mud.
+    fn set_shelter(&mut self, rooms: &crate::Rooms) {
+        let width = self.width;
+        let Some(surface) = self.terrain.first_mut() else { return; };
+        for (index, terrain) in surface.iter_mut().enumerate() {
+            let position = Position { x: index as i32 % width, y: index as i32 / width, z: 0 };
+            terrain.sheltered = rooms.room_at(&position).is_some_and(|room| room.roofed);
+        }
+    }
+
+    fn counts(&mut self, blocker: Blocker) -> &mut Vec<Vec<u16>> {
+        match blocker {
+            Blocker::Object => &mut self.objects,
+            Blocker::Wall => &mut self.walls,
+            Blocker::Door => &mut self.doors,
+            Blocker::Avoid => &mut self.avoid,
+        }
+    }
+
+    // Counts `entity` as blocking `position`, or nothing if None, undoing wherever it was before.
+    fn set_blocker(&mut self, entity: Entity, blocker: Blocker, position: Option<Position>) {
+        let key = (entity, blocker as u8);
+        if self.counted.get(&key) == position.as_ref() { return; }
+        if let Some(old) = self.counted.remove(&key) {
+            if let Some((level, index)) = self.index(&old) {
+                let counts = self.counts(blocker);
+                counts[level][index] = counts[level][index].saturating_sub(1);
+                if counts[level][index] == 0 { self.changed.push((old, false)); }
+            }
+        }
+        let Some(position) = position else { return; };
+        let Some((level, index)) = self.index(&position) else { return; };
+        let counts = self.counts(blocker);
+        counts[level][index] += 1;
+        if counts[level][index] == 1 { self.changed.push((position, false)); }
+        self.counted.insert(key, position);
+    }
+
+    // Counts `entity` as the one kind of blocker it is now, if any, and as none of the others.
+    fn set_blockers(&mut self, entity: Entity, blocker: Option<Blocker>, position: &Position) {
+        for kind in BLOCKERS {
+            self.set_blocker(entity, kind, (blocker == Some(kind)).then_some(*position));
+        }
+    }
+
+    fn forget(&mut self, entity: Entity) {
+        for blocker in BLOCKERS {
+            self.set_blocker(entity, blocker, None);
+        }
+    }
+
+    fn index(&self, position: &Position) -> Option<(usize, usize)> {
+        if position.x < 0 || position.x >= self.width || position.y < 0 || position.y >= self.length { return None; }
+        let level = (-position.z) as usize;
+        if position.z > 0 || level >= self.terrain.len() { return None; }
+        Some((level, (position.y * self.width + position.x) as usize))
+    }
+
+    fn node(&self, position: &Position) -> Option<usize> {
+        self.index(position).map(|(level, index)| level * (self.width * self.length) as usize + index)
+    }
+
+    fn position(&self, node: usize) -> Position {
+        let area = (self.width * self.length) as usize;
+        let index = (node % area) as i32;
+        Position { x: index % self.width, y: index / self.width, z: -((node / area) as i32) }
+    }
+
+    pub fn can_enter(&self, position: &Position, movement: &MovementProfile) -> bool {
+        let Some((level, index)) = self.index(position) else { return false; };
+        let terrain = &self.terrain[level][index];
+        if terrain.wall && !(terrain.rock && movement.burrows) { return false; }
+        if terrain.water && !(movement.swims || movement.flies) { return false; }
+        if self.walls[level][index] > 0 { return false; }
+        if self.objects[level][index] > 0 && !movement.flies { return false; }
+        if self.doors[level][index] > 0 && !(movement.opens_doors || movement.breaks_doors) { return false; }
+        !(self.avoid[level][index] > 0 && movement.avoids_zones)
+    }
+
+    // Whether there is a gate on the tile.
+    pub fn has_door(&self, position: &Position) -> bool {
+        self.index(position).is_some_and(|(level, index)| self.doors[level][index] > 0)
+    }
+
+    fn tile_cost(&self, level: usize, index: usize, movement: &MovementProfile) -> i32 {
+        let terrain = &self.terrain[level][index];
+        let door = if self.doors[level][index] > 0 && !movement.opens_doors { DOOR_BREAK_COST } else { 0 };
+        // Flyers don't care what the ground is like.
+        if movement.flies { return BASE_MOVEMENT_COST + door; }
+        if terrain.wall { return BURROW_COST + door; }
+        if level > 0 || terrain.sheltered { return terrain.cost + door; }
+        terrain.cost + door + self.snow_cost + if terrain.muddy { self.mud_cost } else { 0 }
+    }
+
+    // What stepping from `from` onto the neighbouring `to` costs, weather included.
+    pub fn step_cost(&self, from: &Position, to: &Position, movement: &MovementProfile) -> i32 {
+        let Some((level, index)) = self.index(to) else { return BASE_MOVEMENT_COST; };
+        let cost = self.tile_cost(level, index, movement);
+        let diagonal = from.z == to.z && from.x != to.x && from.y != to.y;
+        if diagonal { cost * DIAGONAL_TENTHS / 10 } else { cost }
+    }
+
+    // Calls `visit` with each tile a unit could step to from `position`. A diagonal step needs
+    // both tiles beside it open, so nobody squeezes between the corners of two walls.
+    fn for_each_neighbor(&self, tile_map: &TileMap, position: &Position, movement: &MovementProfile, mut visit: impl FnMut(Position)) {
+        let step = |(dx, dy): (i32, i32)| Position { x: position.x + dx, y: position.y + dy, z: position.z };
+        let mut open = [false; 4];
+        for (i, offset) in STRAIGHT.into_iter().enumerate() {
+            let neighbor = step(offset);
+            open[i] = self.can_enter(&neighbor, movement);
+            if open[i] { visit(neighbor); }
+        }
+        if self.diagonal {
+            // Each diagonal lies between STRAIGHT[x side] and STRAIGHT[y side].
+            for (offset, x_side, y_side) in [((1, 1), 0, 2), ((1, -1), 0, 3), ((-1, 1), 1, 2), ((-1, -1), 1, 3)] {
+                let neighbor = step(offset);
+                if open[x_side] && open[y_side] && self.can_enter(&neighbor, movement) { visit(neighbor); }
+            }
+        }
+        // Stairs lead to the same spot on the next level.
+        let Some((level, index)) = self.index(position) else { return; };
+        if !self.terrain[level][index].stairs { return; }
+        if let Some(exit) = tile_map.stairs_exit(position).filter(|exit| self.can_enter(exit, movement)) {
+            visit(exit);
+        }
+    }
+
+    fn forget_regions(&mut self) {
+        self.regions.get_mut().unwrap().clear();
+        self.changed.clear();
+    }
+
+    // Labels every tile with the region it belongs to: tiles a unit can walk between share a
+    // region, and tiles it can't stand on get NO_REGION.
+    fn label_regions(&self, tile_map: &TileMap, movement: &MovementProfile) -> Regions {
+        let nodes = (self.width * self.length) as usize * self.terrain.len();
+        let mut regions = Regions { labels: vec![NO_REGION; nodes], roots: vec![] };
+        for node in 0..nodes {
+            if regions.labels[node] != NO_REGION || !self.can_enter(&self.position(node), movement) { continue; }
+            let label = regions.new_label();
+            self.flood(tile_map, movement, &mut regions.labels, node, label);
+        }
+        regions
+    }
+
+    // Gives `label` to `start` and everything that can be walked to from it.
+    fn flood(&self, tile_map: &TileMap, movement: &MovementProfile, labels: &mut [u32], start: usize, label: u32) {
+        labels[start] = label;
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            self.for_each_neighbor(tile_map, &self.position(current), movement, |neighbor| {
+                let neighbor_node = self.node(&neighbor).unwrap();
+                if labels[neighbor_node] == label { return; }
+                labels[neighbor_node] = label;
+                stack.push(neighbor_node);
+            });
+        }
+    }
+
+    // Patches up the labelled regions for the tiles that changed. Only ways of getting about
+    // that a tile's change makes a difference to are touched.
+    fn update_regions(&mut self, tile_map: &TileMap) {
+        let changed = std::mem::take(&mut self.changed);
+        let mut all_regions = std::mem::take(self.regions.get_mut().unwrap());
+        for (movement, regions) in all_regions.iter_mut() {
+            for (position, stairs) in changed.iter() {
+                self.update_region(tile_map, movement, regions, position, *stairs);
+            }
+        }
+        *self.regions.get_mut().unwrap() = all_regions;
+    }
+
+    fn update_region(&self, tile_map: &TileMap, movement: &MovementProfile, regions: &mut Regions, position: &Position, stairs: bool) {
+        let Some(node) = self.node(position) else { return; };
+        let was_open = regions.labels[node] != NO_REGION;
+        let open = self.can_enter(position, movement);
+        if was_open && (!open || stairs) {
+            // The region may have split. Whatever beside the tile was in it is labelled afresh,
+            // which only walks that one region, and the parts still joined up share a label.
+            let region = regions.region(node);
+            regions.labels[node] = NO_REGION;
+            let fresh = regions.roots.len() as u32;
+            let beside = (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| Position { x: position.x + dx, y: position.y + dy, z: position.z }))
+                .chain([Position { z: position.z - 1, ..*position }, Position { z: position.z + 1, ..*position }]);
+            for neighbor in beside {
+                let Some(neighbor_node) = self.node(&neighbor) else { continue; };
+                if regions.labels[neighbor_node] == NO_REGION || regions.labels[neighbor_node] >= fresh { continue; }
+                if regions.region(neighbor_node) != region || !self.can_enter(&neighbor, movement) { continue; }
+                let label = regions.new_label();
+                self.flood(tile_map, movement, &mut regions.labels, neighbor_node, label);
+            }
+        }
+        if open && regions.labels[node] == NO_REGION {
+            // Opening up a tile can only join the regions around it.
+            let label = regions.new_label();
+            regions.labels[node] = label;
+            self.for_each_neighbor(tile_map, position, movement, |neighbor| {
+                let neighbor_label = regions.labels[self.node(&neighbor).unwrap()];
+                if neighbor_label != NO_REGION { regions.join(label, neighbor_label); }
+            });
+        }
+    }
+
+    // Whether a unit at `start` could walk to `goal`, by comparing regions rather than searching.
+    // A unit may be standing somewhere it couldn't walk onto, such as an Avoid zone, and then
+    // any tile it can step off to will do. Being there already doesn't count.
+    pub fn is_reachable(&self, tile_map: &TileMap, start: &Position, goal: &Position, movement: &MovementProfile) -> bool {
+        let (Some(start_node), Some(goal_node)) = (self.node(start), self.node(goal)) else { return false; };
+        if start_node == goal_node { return false; }
+        let mut regions = self.regions.lock().unwrap();
+        let regions = regions.entry(*movement).or_insert_with(|| self.label_regions(tile_map, movement));
+        let goal_region = regions.region(goal_node);
+        if goal_region == NO_REGION { return false; }
+        if regions.labels[start_node] != NO_REGION { return regions.region(start_node) == goal_region; }
+        let mut neighbors = vec![];
+        self.for_each_neighbor(tile_map, start, movement, |neighbor| neighbors.push(self.node(&neighbor).unwrap()));
+        neighbors.into_iter().any(|neighbor| regions.region(neighbor) == goal_region)
+    }
+
+    // The steps from `start` to `goal`, last step first, without `start` itself. Empty if there
+    // is no way there.
+    pub fn find_path(&self, tile_map: &TileMap, start: &Position, goal: &Position, movement: &MovementProfile) -> Vec<Position> {
+        let (Some(start_node), Some(goal_node)) = (self.node(start), self.node(goal)) else { return vec![]; };
+        if start_node == goal_node || !self.can_enter(goal, movement) { return vec![]; }
+        let mut scratch = self.scratch.lock().unwrap();
+        let SearchScratch { search, seen, g, parent } = &mut *scratch;
+        let nodes = (self.width * self.length) as usize * self.terrain.len();
+        if seen.len() != nodes {
+            *seen = vec![0; nodes];
+            *g = vec![0; nodes];
+            *parent = vec![0; nodes];
+            *search = 0;
+        }
+        *search = search.wrapping_add(1);
+        if *search == 0 {
+            seen.fill(0);
+            *search = 1;
+        }
+        let search = *search;
+        // No tile is cheaper than open ground, so the walking distance across open ground,
+        // ignoring stairs, never overestimates.
+        let diagonal = self.diagonal;
+        let heuristic = |position: &Position| {
+            let (dx, dy) = ((position.x - goal.x).abs(), (position.y - goal.y).abs());
+            if diagonal {
+                BASE_MOVEMENT_COST * dx.max(dy) + (BASE_MOVEMENT_COST * DIAGONAL_TENTHS / 10 - BASE_MOVEMENT_COST) * dx.min(dy)
+            } else {
+                BASE_MOVEMENT_COST * (dx + dy)
+            }
+        };
+        let mut open = BinaryHeap::new();
+        seen[start_node] = search;
+        g[start_node] = 0;
+        // On equal estimates the node furthest along is taken first.
+        open.push(Reverse((heuristic(start), Reverse(0), start_node)));
+        while let Some(Reverse((_, Reverse(cost), node))) = open.pop() {
+            if cost > g[node] { continue; } // A cheaper way here was already expanded.
+            if node == goal_node {
+                let mut path = vec![];
+                let mut step = goal_node;
+                while step != start_node {
+                    path.push(self.position(step));
+                    step = parent[step] as usize;
+                }
+                return path;
+            }
+            let current = self.position(node);
+            self.for_each_neighbor(tile_map, &current, movement, |neighbor| {
+                let neighbor_node = self.node(&neighbor).unwrap();
+                let neighbor_cost = cost + self.step_cost(&current, &neighbor, movement);
+                if seen[neighbor_node] == search && neighbor_cost >= g[neighbor_node] { return; }
+                seen[neighbor_node] = search;
+                g[neighbor_node] = neighbor_cost;
+                parent[neighbor_node] = node as u32;
+                open.push(Reverse((neighbor_cost + heuristic(&neighbor), Reverse(neighbor_cost), neighbor_node)));
+            });
+        }
+        vec![]
+    }
+}
+
+// What an object stops, if anything.
+fn object_blocker(object: &Object) -> Option<Blocker> {
+    if object.itemtype.passable() { return None; }
+    Some(if object.itemtype.is_wall() { Blocker::Wall } else { Blocker::Object })
+}
+
+fn building_blocker(building: &Building) -> Option<Blocker> {
+    match building.building_type {
+        BuildingType::Wall => Some(Blocker::Wall),
+        BuildingType::Gate => Some(Blocker::Door),
+        _ => None,
+    }
+}
+
+fn zone_blocker(zone: &Zone) -> Option<Blocker> {
+    (zone.zone_type == ZoneType::Avoid).then_some(Blocker::Avoid)
+}
+
+// Keeps the grid in step with the map, objects, buildings and zones. Only what changed since
+// the last tick is looked at, unless the map itself was replaced.
+#[allow(clippy::too_many_arguments)]
+pub fn update_passability(
+    mut grid: ResMut<PassabilityGrid>,
+    tile_map: Res<TileMap>,
+    scenario: Res<crate::Scenario>,
+    weather: Res<Weather>,
+    rooms: Res<crate::Rooms>,
+    mut tile_changes: EventReader<TileChangedEvent>,
+    changed_objects: Query<(Entity, &Object, &Position), Or<(Changed<Object>, Changed<Position>)>>,
+    changed_buildings: Query<(Entity, &Building, &Position), Or<(Changed<Building>, Changed<Position>)>>,
+    changed_zones: Query<(Entity, &Zone, &Position), Or<(Changed<Zone>, Changed<Position>)>>,
+    mut removed: (RemovedComponents<Object>, RemovedComponents<Building>, RemovedComponents<Zone>),
+    objects: Query<(Entity, &Object, &Position)>,
+    buildings: Query<(Entity, &Building, &Position)>,
+    zones: Query<(Entity, &Zone, &Position)>,
+) {
+    if grid.diagonal != scenario.diagonal_movement {
+        grid.diagonal = scenario.diagonal_movement;
+        // Corners that could be cut may have been all that joined two regions.
+        grid.forget_regions();
+    }
+    let (snow_cost, mud_cost) = (weather.current_weather.snow_cost(), weather.current_weather.mud_cost());
+    if grid.snow_cost != snow_cost || grid.mud_cost != mud_cost {
+        grid.snow_cost = snow_cost;
+        grid.mud_cost = mud_cost;
+    }
+    let mut tile_events = 0;
+    for change in tile_changes.iter() {
+        tile_events += 1;
+        grid.set_tile(&change.position, &change.new);
+    }
+    let (removed_objects, removed_buildings, removed_zones) = &mut removed;
+    // A changed map that sent no tile events was swapped out wholesale, e.g. by loading a save.
+    if !grid.fits(&tile_map) || (tile_map.is_changed() && tile_events == 0) {
+        grid.refresh_terrain(&tile_map);
+        grid.set_shelter(&rooms);
+        for (entity, object, position) in objects.iter() {
+            grid.set_blockers(entity, object_blocker(object), position);
+        }
+        for (entity, building, position) in buildings.iter() {
+            grid.set_blockers(entity, building_blocker(building), position);
+        }
+        for (entity, zone, position) in zones.iter() {
+            grid.set_blockers(entity, zone_blocker(zone), position);
+        }
+        removed_objects.clear();
+        removed_buildings.clear();
+        removed_zones.clear();
+        grid.update_regions(&tile_map);
+        return;
+    }
+    if rooms.is_changed() {
+        grid.set_shelter(&rooms);
+    }
+    for entity in removed_objects.iter().chain(removed_buildings.iter()).chain(removed_zones.iter()) {
+        grid.forget(entity);
+    }
+    for (entity, object, position) in changed_objects.iter() {
+        grid.set_blockers(entity, object_blocker(object), position);
+    }
+    for (entity, building, position) in changed_buildings.iter() {
+        grid.set_blockers(entity, building_blocker(building), position);
+    }
+    for (entity, zone, position) in changed_zones.iter() {
+        grid.set_blockers(entity, zone_blocker(zone), position);
+    }
+    // Only touched when something changed, so the grid isn't marked changed every tick.
+    if !grid.changed.is_empty() {
+        grid.update_regions(&tile_map);
+    }
+}
+
+// ============================================================================
+// FLOW FIELDS - Walking cost to the nearest of a set of goals, from every tile
+// ============================================================================
+
+// One search serves any number of units heading for the same goals: each just steps to its
+// cheapest neighbour. Laid out like the grid's nodes, and only good while the grid keeps its size.
+pub struct FlowField {
+    costs: Vec<i32>,
+}
+
+const UNREACHABLE: i32 = i32::MAX;
+
+impl PassabilityGrid {
+    // Searches outwards from all the goals at once. A goal counts even if the unit couldn't
+    // stand on it, so a field to someone's position leads right up to them.
+    pub fn flow_field(&self, tile_map: &TileMap, goals: &[Position], movement: &MovementProfile) -> FlowField {
+        let nodes = (self.width * self.length) as usize * self.terrain.len();
+        let mut costs = vec![UNREACHABLE; nodes];
+        let mut open = BinaryHeap::new();
+        for goal in goals {
+            // As with paths, a goal the unit can't stand on can't be reached.
+            if !self.can_enter(goal, movement) { continue; }
+            let Some(node) = self.node(goal) else { continue; };
+            costs[node] = 0;
+            open.push(Reverse((0, node)));
+        }
+        while let Some(Reverse((cost, node))) = open.pop() {
+            if cost > costs[node] { continue; }
+            let current = self.position(node);
+            // Neighbours are neighbours both ways, so whoever can step here from a neighbour
+            // pays for the step onto this tile.
+            self.for_each_neighbor(tile_map, &current, movement, |neighbor| {
+                let neighbor_node = self.node(&neighbor).unwrap();
+                let neighbor_cost = cost + self.step_cost(&neighbor, &current, movement);
+                if neighbor_cost >= costs[neighbor_node] { return; }
+                costs[neighbor_node] = neighbor_cost;
+                open.push(Reverse((neighbor_cost, neighbor_node)));
+            });
+        }
+        FlowField { costs }
+    }
+
+    // What it costs to get from `position` to the nearest goal, if it can be done.
+    pub fn flow_cost(&self, field: &FlowField, position: &Position) -> Option<i32> {
+        let cost = *field.costs.get(self.node(position)?)?;
+        (cost != UNREACHABLE).then_some(cost)
+    }
+
+    // The next step from `position` towards the nearest goal. None on a goal, where there is no
+    // way on, or if the field was built for a map of another size.
+    pub fn flow_step(&self, tile_map: &TileMap, field: &FlowField, position: &Position, movement: &MovementProfile) -> Option<Position> {
+        let here = self.flow_cost(field, position)?;
+        let mut best: Option<(i32, Position)> = None;
+        self.for_each_neighbor(tile_map, position, movement, |neighbor| {
+            let Some(cost) = self.flow_cost(field, &neighbor) else { return; };
+            let total = cost + self.step_cost(position, &neighbor, movement);
+            if cost < here && best.is_none_or(|(best_total, _)| total < best_total) {
+                best = Some((total, neighbor));
+            }
+        });
+        best.map(|(_, step)| step)
+    }
+}
+
+// ============================================================================
+// BENCHMARK - `--bench-paths` times path searches on a generated map
+// ============================================================================
+
+pub const BENCH_MAP_SIZE: i32 = 200;
+pub const BENCH_AGENTS: usize = 200;
+const BENCH_ROUNDS: usize = 5;
+const BENCH_WALL_CHANCE: f64 = 0.12;
+const BENCH_WATER_CHANCE: f64 = 0.05;
+const BENCH_OBJECT_CHANCE: f64 = 0.05;
+
+// Runs the pathfinding benchmark if `--bench-paths` was passed. `--agents N` and `--seed N`
+// change the run, and `--four-way` plans without diagonal steps.
+pub fn bench_paths_from_args(args: &[String]) -> bool {
+    if !args.iter().any(|arg| arg == "--bench-paths") { return false; }
+    let agents = args.iter()
+        .position(|arg| arg == "--agents")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|agents| agents.parse().ok())
+        .unwrap_or(BENCH_AGENTS);
+    let seed = GameRng::seed_from_args(args);
+    let diagonal = !args.iter().any(|arg| arg == "--four-way");
+    bench_paths(BENCH_MAP_SIZE, agents, seed, diagonal);
+    true
+}
+
+fn bench_paths(size: i32, agents: usize, seed: u64, diagonal: bool) {
+    let mut game_rng = GameRng::new(seed);
+    let rng = game_rng.stream(RngStream::Map);
+    let tiles = (0..size * size).map(|index| {
+        let (x, y) = (index % size, index / size);
+        if x == 0 || y == 0 || x == size - 1 || y == size - 1 { return TileType::WallGame; }
+        if rng.gen_bool(BENCH_WALL_CHANCE) { return TileType::WallStone; }
+        if rng.gen_bool(BENCH_WATER_CHANCE) { return TileType::Water; }
+        TileType::Grass
+    }).collect();
+    let tile_map = TileMap::from_levels(size, size, vec![tiles]);
+
+    let started = Instant::now();
+    let mut grid = PassabilityGrid::from_tile_map(&tile_map);
+    grid.diagonal = diagonal;
+    let mut object_id = 0;
+    for y in 0..size {
+        for x in 0..size {
+            if !rng.gen_bool(BENCH_OBJECT_CHANCE) { continue; }
+            grid.set_blocker(Entity::from_raw(object_id), Blocker::Object, Some(Position { x, y, z: 0 }));
+            object_id += 1;
+        }
+    }
+    let build_time = started.elapsed();
+
+    let mut random_open_tile = || loop {
+        let position = Position { x: rng.gen_range(1..size - 1), y: rng.gen_range(1..size - 1), z: 0 };
+        if grid.can_enter(&position, &MovementProfile::default()) { return position; }
+    };
+    let trips = (0..agents).map(|_| (random_open_tile(), random_open_tile())).collect::<Vec<_>>();
+
+    let moves = if diagonal { "8-way" } else { "4-way" };
+    println!("=== Pathfinding benchmark: {}x{} map, {} agents, seed {}, {} moves ===", size, size, agents, seed, moves);
+    println!("Grid built in {:.2} ms ({} blocking objects)", build_time.as_secs_f64() * 1000.0, object_id);
+    let mut round_times = vec![];
+    for round in 1..=BENCH_ROUNDS {
+        let started = Instant::now();
+        let mut found = 0;
+        let mut steps = 0;
+        for (start, goal) in trips.iter() {
+            let path = grid.find_path(&tile_map, start, goal, &MovementProfile::default());
+            if !path.is_empty() { found += 1; }
+            steps += path.len();
+        }
+        let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+        println!("Round {}: {:.2} ms, {} of {} paths found, {} steps", round, elapsed, found, agents, steps);
+        round_times.push(elapsed);
+    }
+    round_times.sort_by(|a, b| a.total_cmp(b));
+    let median = round_times[round_times.len() / 2];
+    println!("Median: {:.2} ms per round, {:.1} us per path", median, median * 1000.0 / agents.max(1) as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_map(width: i32, length: i32) -> TileMap {
+        TileMap::from_levels(width, length, vec![vec![TileType::Grass; (width * length) as usize]])
+    }
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { x, y, z: 0 }
+    }
+
+    // Changes a tile in the map and the grid together, as apply_tile_changes and update_passability do.
+    fn set_tile(tile_map: &mut TileMap, grid: &mut PassabilityGrid, position: Position, tile_type: TileType) {
+        grid.set_tile(&position, &tile_type);
+        tile_map.set(&position, tile_type);
+    }
+
+    // The patched-up regions must split the map the same way labelling it afresh does.
+    fn assert_regions_match(grid: &PassabilityGrid, tile_map: &TileMap, movement: &MovementProfile) {
+        let mut cached = grid.regions.lock().unwrap();
+        let regions = cached.get_mut(movement).expect("regions were never labelled");
+        let fresh = grid.label_regions(tile_map, movement);
+        let mut fresh_to_cached = HashMap::new();
+        let mut cached_to_fresh = HashMap::new();
+        for node in 0..fresh.labels.len() {
+            let (expected, actual) = (fresh.labels[node], regions.region(node));
+            assert_eq!(expected == NO_REGION, actual == NO_REGION, "open tiles differ at {:?}", grid.position(node));
+            if expected == NO_REGION { continue; }
+            assert_eq!(*fresh_to_cached.entry(expected).or_insert(actual), actual, "region split differs at {:?}", grid.position(node));
+            assert_eq!(*cached_to_fresh.entry(actual).or_insert(expected), expected, "regions joined at {:?}", grid.position(node));
+        }
+    }
+
+    #[test]
+    fn find_path_takes_the_stairs() {
+        let mut surface = vec![TileType::Grass; 16];
+        surface[5] = TileType::StairsDown;
+        let mut below = vec![TileType::Cave; 16];
+        below[5] = TileType::StairsUp;
+        let tile_map = TileMap::from_levels(4, 4, vec![surface, below]);
+        let grid = PassabilityGrid::from_tile_map(&tile_map);
+        let goal = Position { x: 3, y: 3, z: -1 };
+        let path = grid.find_path(&tile_map, &at(0, 0), &goal, &MovementProfile::default());
+        assert_eq!(path.first(), Some(&goal));
+        assert!(path.contains(&Position { x: 1, y: 1, z: -1 }));
+        assert!(grid.is_reachable(&tile_map, &goal, &at(0, 0), &MovementProfile::default()));
+    }
+
+    #[test]
+    fn land_only_units_keep_out_of_water() {
+        let mut tile_map = open_map(5, 5);
+        for y in 0..4 {
+            tile_map.set(&at(2, y), TileType::Water);
+        }
+        let grid = PassabilityGrid::from_tile_map(&tile_map);
+        let walker = MovementProfile { swims: false, ..default() };
+        let path = grid.find_path(&tile_map, &at(0, 0), &at(4, 0), &walker);
+        assert_eq!(path.first(), Some(&at(4, 0)));
+        assert!(path.iter().all(|step| tile_map.get(step) != Some(&TileType::Water)));
+        // Swimmers may cross, but only where the way round is too long.
+        assert!(grid.find_path(&tile_map, &at(0, 1), &at(4, 1), &MovementProfile::default()).contains(&at(2, 1)));
+    }
+
+    #[test]
+    fn find_path_goes_round_walls() {
+        let mut tile_map = open_map(5, 5);
+        for y in 0..4 {
+            tile_map.set(&at(2, y), TileType::WallStone);
+        }
+        let grid = PassabilityGrid::from_tile_map(&tile_map);
+        let path = grid.find_path(&tile_map, &at(0, 0), &at(4, 0), &MovementProfile::default());
+        // Up to the gap at the top, across and back down.
+        assert_eq!(path.len(), 12);
+        assert_eq!(path[0], at(4, 0));
+        assert!(path.contains(&at(2, 4)));
+        assert!(path.iter().all(|step| !tile_map.is_wall(step)));
+    }
+
+    #[test]
+    fn find_path_is_empty_without_a_way_through() {
+        let mut tile_map = open_map(5, 5);
+        for y in 0..5 {
+            tile_map.set(&at(2, y), TileType::WallStone);
+        }
+        let grid = PassabilityGrid::from_tile_map(&tile_map);
+        let movement = MovementProfile::default();
+        assert!(grid.find_path(&tile_map, &at(0, 0), &at(4, 0), &movement).is_empty());
+        assert!(!grid.is_reachable(&tile_map, &at(0, 0), &at(4, 0), &movement));
+        // Nor is there a path onto a wall, or to where the unit already stands.
+        assert!(grid.find_path(&tile_map, &at(0, 0), &at(2, 0), &movement).is_empty());
+        assert!(grid.find_path(&tile_map, &at(0, 0), &at(0, 0), &movement).is_empty());
+    }
+
+    #[test]
+    fn diagonals_cost_more_and_paths_take_the_cheaper_ground() {
+        let mut tile_map = open_map(5, 3);
+        tile_map.set(&at(2, 1), TileType::Water);
+        let mut grid = PassabilityGrid::from_tile_map(&tile_map);
+        grid.diagonal = true;
+        let movement = MovementProfile::default();
+        assert_eq!(grid.step_cost(&at(0, 0), &at(1, 0), &movement), BASE_MOVEMENT_COST);
+        assert_eq!(grid.step_cost(&at(0, 0), &at(1, 1), &movement), BASE_MOVEMENT_COST * DIAGONAL_TENTHS / 10);
+        // Two diagonal steps round the water are cheaper than a swim.
+        let path = grid.find_path(&tile_map, &at(0, 1), &at(4, 1), &movement);
+        assert_eq!(path.len(), 4);
+        assert!(!path.contains(&at(2, 1)));
+    }
+
+    #[test]
+    fn diagonal_steps_do_not_cut_corners() {
+        let mut tile_map = open_map(3, 3);
+        tile_map.set(&at(1, 0), TileType::WallStone);
+        let mut grid = PassabilityGrid::from_tile_map(&tile_map);
+        grid.diagonal = true;
+        let movement = MovementProfile::default();
+        assert_eq!(grid.find_path(&tile_map, &at(0, 0), &at(1, 1), &movement), vec![at(1, 1), at(0, 1)]);
+        // Round the wall takes four steps, not two diagonals past its corners.
+        assert_eq!(grid.find_path(&tile_map, &at(0, 0), &at(2, 0), &movement).len(), 4);
+        assert_eq!(grid.find_path(&tile_map, &at(0, 1), &at(2, 2), &movement).len(), 2);
+    }
+
+    #[test]
+    fn only_swimmers_and_flyers_cross_water() {
+        let mut tile_map = open_map(5, 3);
+        for y in 0..3 {
+            tile_map.set(&at(2, y), TileType::Water);
+        }
+        let grid = PassabilityGrid::from_tile_map(&tile_map);
+        let walker = MovementProfile { swims: false, ..default() };
+        let flyer = MovementProfile { swims: false, flies: true, ..default() };
+        assert!(grid.find_path(&tile_map, &at(0, 1), &at(4, 1), &walker).is_empty());
+        assert!(!grid.is_reachable(&tile_map, &at(0, 1), &at(4, 1), &walker));
+        assert!(grid.is_reachable(&tile_map, &at(0, 1), &at(4, 1), &MovementProfile::default()));
+        assert_eq!(grid.find_path(&tile_map, &at(0, 1), &at(4, 1), &flyer).len(), 4);
+    }
+
+    #[test]
+    fn flow_field_leads_to_the_nearest_goal() {
+        let mut tile_map = open_map(6, 6);
+        for y in 1..6 {
+            tile_map.set(&at(3, y), TileType::WallStone);
+        }
+        let grid = PassabilityGrid::from_tile_map(&tile_map);
+        let movement = MovementProfile::default();
+        let goals = [at(5, 5), at(0, 5)];
+        let field = grid.flow_field(&tile_map, &goals, &movement);
+        assert_eq!(grid.flow_cost(&field, &at(5, 5)), Some(0));
+        assert_eq!(grid.flow_cost(&field, &at(3, 3)), None);
+        assert_eq!(grid.flow_step(&tile_map, &field, &at(5, 5), &movement), None);
+
+        // From the far side of the wall, the goal on this side is nearer.
+        let mut position = at(5, 1);
+        let mut steps = 0;
+        while let Some(next) = grid.flow_step(&tile_map, &field, &position, &movement) {
+            assert!(grid.flow_cost(&field, &next) < grid.flow_cost(&field, &position));
+            position = next;
+            steps += 1;
+            assert!(steps < 36, "flow field went round in circles");
+        }
+        assert_eq!(position, at(5, 5));
+        assert_eq!(steps, 4);
+    }
+
+    #[test]
+    fn regions_follow_walls_going_up_and_coming_down() {
+        let mut tile_map = open_map(7, 7);
+        let mut grid = PassabilityGrid::from_tile_map(&tile_map);
+        let movement = MovementProfile::default();
+        assert!(grid.is_reachable(&tile_map, &at(0, 0), &at(6, 6), &movement));
+
+        for y in 0..7 {
+            set_tile(&mut tile_map, &mut grid, at(3, y), TileType::WallStone);
+        }
+        grid.update_regions(&tile_map);
+        assert!(!grid.is_reachable(&tile_map, &at(0, 0), &at(6, 6), &movement));
+        assert_regions_match(&grid, &tile_map, &movement);
+
+        set_tile(&mut tile_map, &mut grid, at(3, 6), TileType::Dirt);
+        grid.update_regions(&tile_map);
+        assert!(grid.is_reachable(&tile_map, &at(0, 0), &at(6, 6), &movement));
+        assert_regions_match(&grid, &tile_map, &movement);
+    }
+
+    #[test]
+    fn regions_follow_blockers_and_stairs() {
+        let mut surface = vec![TileType::Grass; 25];
+        surface[12] = TileType::StairsDown;
+        let mut below = vec![TileType::WallStone; 25];
+        below[12] = TileType::StairsUp;
+        below[13] = TileType::Cave;
+        let mut tile_map = TileMap::from_levels(5, 5, vec![surface, below]);
+        let mut grid = PassabilityGrid::from_tile_map(&tile_map);
+        let walker = MovementProfile::default();
+        let cave = Position { x: 3, y: 2, z: -1 };
+        assert!(grid.is_reachable(&tile_map, &at(0, 0), &cave, &walker));
+
+        // A gate shuts out anyone who can't open it, and opening it lets them back in.
+        let gate = Entity::from_raw(1);
+        grid.set_blocker(gate, Blocker::Door, Some(Position { x: 2, y: 2, z: -1 }));
+        grid.update_regions(&tile_map);
+        assert!(!grid.is_reachable(&tile_map, &at(0, 0), &cave, &walker));
+        assert_regions_match(&grid, &tile_map, &walker);
+        grid.forget(gate);
+        grid.update_regions(&tile_map);
+        assert!(grid.is_reachable(&tile_map, &at(0, 0), &cave, &walker));
+
+        // Filling in the stairs cuts the levels apart.
+        set_tile(&mut tile_map, &mut grid, at(2, 2), TileType::Grass);
+        set_tile(&mut tile_map, &mut grid, Position { x: 2, y: 2, z: -1 }, TileType::Cave);
+        grid.update_regions(&tile_map);
+        assert!(!grid.is_reachable(&tile_map, &at(0, 0), &cave, &walker));
+        assert_regions_match(&grid, &tile_map, &walker);
+    }
+}
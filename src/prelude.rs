@@ -2,15 +2,18 @@ pub use super::components::{
     ActorType, Affliction, AfflictionType, AfflictionLocation, Attackable, Attacked, Attributeset,
     Bed, Brain, Carryable, Choppable, ClickedOn, Danger, DangerType, Dying, Food, Foragable, ForageType, GameState, GeneratedBy,
     GiveMeAName, HasName, HasNameShown, HighlightBox, Highlighted, HoverNote, Huntable, InfoPanel, InGameButton, IsName,
-    Logs, MainMenuOverlay, MapTile, MenuStates, Mineable, MonsterGenerator, Motivation, MoveRandom,
-    MoveTowardsNearestAttackable, MoveTowardsTarget, NearestEntity, Need, Nest,
+    Logs, MainMenuOverlay, MapTile, MenuStates, MiningProgress, MiningTarget, MonsterGenerator, Motivation, MoveRandom,
+    MoveTowardsNearestAttackable, MoveTowardsTarget, MovementProfile, NearestEntity, Need, Nest,
     Order, Pathing, PauseOverlay, PersonalityTrait, PhysicalBody, Plant, Position,
-    SelectableType, SetNest, Skillset, Skill, SizeXYZ, StrikeType,
-    Targeting, Task, TemporaryVisualElement, TextName, TileType, WorkMarker, WorkTarget, Zone, ZoneMarker, ZoneType,
+    SelectableType, SetNest, Skillset, Skill, SizeXYZ, SpeedButton, StrikeType,
+    Targeting, Task, TemporaryVisualElement, TextName, TileType, Viewshed, WorkMarker, WorkTarget, Zone, ZoneType,
 };
 pub use crate::objects::{ItemType, Object};
 pub use crate::constants::*;
+pub use crate::data_files::*;
 pub use crate::resources::*;
+pub use crate::sim_clock::*;
+pub use crate::tile_map::*;
 
 // New AAA systems
 pub use crate::inventory_system::*;
@@ -26,4 +29,5 @@ pub use bevy::prelude::*;
 pub use rand::prelude::random;
 pub use rand::seq::SliceRandom;
 pub use rand::Rng;
+pub use serde::{Deserialize, Serialize};
 pub use std::collections::HashMap;
@@ -0,0 +1,266 @@
+use std::collections::{HashSet, VecDeque};
+use crate::prelude::*;
+
+// ============================================================================
+// ROOMS - Flood-filled areas between walls, and the shelter they give
+// ============================================================================
+
+// Past this many tiles an area is too big to roof over, however well it is walled in.
+pub const MAX_ROOM_TILES: usize = 400;
+// Warmth of a snug room; the biggest rooms give half this.
+const ROOM_WARMTH: f32 = 10.0;
+
+#[derive(Clone, Debug)]
+pub struct Room {
+    pub id: usize,
+    pub size: usize,
+    // Walled and doored off from the edge of the map.
+    pub enclosed: bool,
+    // Underground everything has a roof; on the surface only enclosed rooms small enough to span.
+    pub roofed: bool,
+}
+
+impl Room {
+    pub fn is_indoors(&self) -> bool {
+        self.enclosed && self.roofed
+    }
+
+    pub fn shelter(&self) -> Option<Shelter> {
+        if !self.is_indoors() { return None; }
+        let snugness = 1.0 - self.size.min(MAX_ROOM_TILES) as f32 / (2 * MAX_ROOM_TILES) as f32;
+        Some(Shelter {
+            protection_from_wind: 1.0,
+            protection_from_rain: 1.0,
+            warmth_provided: ROOM_WARMTH * snugness,
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        if !self.enclosed && !self.roofed {
+            return String::from("Room: Outdoors");
+        }
+        let walls = if self.enclosed { "enclosed" } else { "open" };
+        let roof = if self.roofed { "roofed" } else { "open to the sky" };
+        format!("Room: #{} ({} tiles, {}, {})", self.id, self.size, walls, roof)
+    }
+}
+
+// Which room every tile belongs to, one grid per map level laid out like the TileMap's levels.
+// Walls and doors belong to no room.
+#[derive(Resource, Default)]
+pub struct Rooms {
+    width: i32,
+    length: i32,
+    tiles: Vec<Vec<Option<u32>>>,
+    rooms: Vec<Room>,
+    // Wall objects and buildings the rooms were last built around, so removing one is noticed.
+    boundaries: HashSet<Entity>,
+}
+
+impl Rooms {
+    pub fn room_at(&self, position: &Position) -> Option<&Room> {
+        let (level, index) = self.index(position)?;
+        self.tiles[level][index].map(|id| &self.rooms[id as usize])
+    }
+
+    fn index(&self, position: &Position) -> Option<(usize, usize)> {
+        if position.x < 0 || position.x >= self.width || position.y < 0 || position.y >= self.length { return None; }
+        let level = (-position.z) as usize;
+        if position.z > 0 || level >= self.tiles.len() { return None; }
+        Some((level, (position.y * self.width + position.x) as usize))
+    }
+}
+
+pub struct RoomPlugin;
+
+impl Plugin for RoomPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Rooms>()
+        .add_systems(SimTick, detect_rooms.after(apply_tile_changes))
+        .add_systems(
+            SimTick,
+            shelter_indoor_colonists
+            .after(detect_rooms)
+            .run_if(every_seconds(0.5))
+        )
+        ;
+    }
+}
+
+// Rebuilds every room whenever a wall or door goes up or comes down.
+#[allow(clippy::too_many_arguments)]
+pub fn detect_rooms(
+    mut rooms: ResMut<Rooms>,
+    tile_map: Res<TileMap>,
+    mut tile_changes: EventReader<TileChangedEvent>,
+    changed_objects: Query<&Object, Changed<Object>>,
+    added_buildings: Query<&Building, Added<Building>>,
+    mut removed_objects: RemovedComponents<Object>,
+    mut removed_buildings: RemovedComponents<Building>,
+    objects: Query<(Entity, &Object, &Position)>,
+    buildings: Query<(Entity, &Building, &Position)>,
+) {
+    let mut tile_events = 0;
+    let mut walls_moved = false;
+    for change in tile_changes.iter() {
+        tile_events += 1;
+        walls_moved |= change.old.is_wall() != change.new.is_wall();
+    }
+    // A changed map that sent no tile events was swapped out wholesale, e.g. by loading a save.
+    let map_replaced = tile_map.is_changed() && tile_events == 0;
+    let unbuilt = rooms.width != tile_map.width() || rooms.length != tile_map.length()
+        || rooms.tiles.len() != (1 - tile_map.lowest_level()) as usize;
+    let walls_built = changed_objects.iter().any(|object| object.itemtype.is_wall())
+        || added_buildings.iter().any(|building| is_boundary(&building.building_type));
+    let mut walls_removed = false;
+    for entity in removed_objects.iter().chain(removed_buildings.iter()) {
+        walls_removed |= rooms.boundaries.contains(&entity);
+    }
+    if !(unbuilt || map_replaced || walls_moved || walls_built || walls_removed) { return; }
+
+    let mut walls = HashSet::new();
+    let mut doors = HashSet::new();
+    let mut boundaries = HashSet::new();
+    for (entity, object, position) in objects.iter() {
+        if !object.itemtype.is_wall() || object.under_construction { continue; }
+        walls.insert(*position);
+        boundaries.insert(entity);
+    }
+    for (entity, building, position) in buildings.iter() {
+        match building.building_type {
+            BuildingType::Wall => { walls.insert(*position); }
+            BuildingType::Gate => { doors.insert(*position); }
+            _ => continue,
+        }
+        boundaries.insert(entity);
+    }
+    *rooms = flood_fill_rooms(&tile_map, &walls, &doors);
+    rooms.boundaries = boundaries;
+}
+
+fn is_boundary(building_type: &BuildingType) -> bool {
+    matches!(building_type, BuildingType::Wall | BuildingType::Gate)
+}
+
+// Splits each level into rooms along walls and doors. A room that reaches the border of the map
+// is outdoors; doors close a room off like walls do.
+fn flood_fill_rooms(tile_map: &TileMap, walls: &HashSet<Position>, doors: &HashSet<Position>) -> Rooms {
+    let (width, length) = (tile_map.width(), tile_map.length());
+    let levels = (1 - tile_map.lowest_level()) as usize;
+    let mut tiles = vec![vec![None; (width * length) as usize]; levels];
+    let mut rooms = vec![];
+    for (level, level_tiles) in tiles.iter_mut().enumerate() {
+        let z = -(level as i32);
+        for y in 0..length {
+            for x in 0..width {
+                let start = Position { x, y, z };
+                if level_tiles[(y * width + x) as usize].is_some() { continue; }
+                if tile_map.is_wall(&start) || walls.contains(&start) || doors.contains(&start) { continue; }
+                let id = rooms.len();
+                let mut size = 0;
+                let mut reaches_border = false;
+                let mut open = VecDeque::from([start]);
+                level_tiles[(y * width + x) as usize] = Some(id as u32);
+                while let Some(position) = open.pop_front() {
+                    size += 1;
+                    for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                        let neighbor = Position { x: position.x + dx, y: position.y + dy, z };
+                        if tile_map.get(&neighbor).is_none_or(|tile_type| *tile_type == TileType::WallGame) {
+                            reaches_border = true;
+                            continue;
+                        }
+                        if tile_map.is_wall(&neighbor) || walls.contains(&neighbor) || doors.contains(&neighbor) { continue; }
+                        let index = (neighbor.y * width + neighbor.x) as usize;
+                        if level_tiles[index].is_some() { continue; }
+                        level_tiles[index] = Some(id as u32);
+                        open.push_back(neighbor);
+                    }
+                }
+                let enclosed = !reaches_border;
+                let roofed = z < 0 || (enclosed && size <= MAX_ROOM_TILES);
+                rooms.push(Room { id, size, enclosed, roofed });
+            }
+        }
+    }
+    Rooms { width, length, tiles, rooms, boundaries: HashSet::new() }
+}
+
+// Colonists indoors are kept out of the wind and rain and warmed by the room around them.
+pub fn shelter_indoor_colonists(
+    mut commands: Commands,
+    colonists: Query<(Entity, &Position, &Brain, Option<&Shelter>)>,
+    rooms: Res<Rooms>,
+) {
+    for (entity, position, brain, shelter) in colonists.iter() {
+        if brain.personality.contains(&PersonalityTrait::Creature) { continue; }
+        match (rooms.room_at(position).and_then(Room::shelter), shelter) {
+            (Some(new), Some(old)) if new.warmth_provided == old.warmth_provided => {}
+            (Some(new), _) => { commands.entity(entity).insert(new); }
+            (None, Some(_)) => { commands.entity(entity).remove::<Shelter>(); }
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_map(width: i32, length: i32) -> TileMap {
+        TileMap::from_levels(width, length, vec![vec![TileType::Grass; (width * length) as usize]])
+    }
+
+    fn at(x: i32, y: i32) -> Position {
+        Position { x, y, z: 0 }
+    }
+
+    // The ring of tiles around the square from (1, 1) to (5, 5), leaving a 3x3 room inside.
+    fn ring() -> HashSet<Position> {
+        (1..=5).flat_map(|x| (1..=5).map(move |y| at(x, y)))
+            .filter(|position| position.x == 1 || position.x == 5 || position.y == 1 || position.y == 5)
+            .collect()
+    }
+
+    #[test]
+    fn walls_enclose_a_roofed_room() {
+        let tile_map = open_map(7, 7);
+        let rooms = flood_fill_rooms(&tile_map, &ring(), &HashSet::new());
+        let inside = rooms.room_at(&at(3, 3)).unwrap();
+        assert_eq!(inside.size, 9);
+        assert!(inside.enclosed && inside.roofed);
+        assert_eq!(rooms.room_at(&at(2, 4)).unwrap().id, inside.id);
+
+        let outside = rooms.room_at(&at(0, 0)).unwrap();
+        assert_eq!(outside.size, 49 - 25);
+        assert!(!outside.enclosed && !outside.roofed);
+        assert!(rooms.room_at(&at(1, 1)).is_none());
+    }
+
+    #[test]
+    fn doors_close_a_room_but_gaps_do_not() {
+        let tile_map = open_map(7, 7);
+        let mut walls = ring();
+        walls.remove(&at(3, 1));
+        let doors = HashSet::from([at(3, 1)]);
+        assert!(flood_fill_rooms(&tile_map, &walls, &doors).room_at(&at(3, 3)).unwrap().enclosed);
+
+        let rooms = flood_fill_rooms(&tile_map, &walls, &HashSet::new());
+        assert!(!rooms.room_at(&at(3, 3)).unwrap().enclosed);
+        assert_eq!(rooms.room_at(&at(3, 3)).unwrap().id, rooms.room_at(&at(0, 0)).unwrap().id);
+    }
+
+    #[test]
+    fn wall_tiles_bound_rooms_and_underground_is_roofed() {
+        let mut surface = vec![TileType::Grass; 25];
+        for x in 0..5 {
+            surface[10 + x] = TileType::WallStone;
+        }
+        let tile_map = TileMap::from_levels(5, 5, vec![surface, vec![TileType::Cave; 25]]);
+        let rooms = flood_fill_rooms(&tile_map, &HashSet::new(), &HashSet::new());
+        assert_ne!(rooms.room_at(&at(0, 0)).unwrap().id, rooms.room_at(&at(0, 4)).unwrap().id);
+        assert!(rooms.room_at(&at(2, 2)).is_none());
+        let cave = rooms.room_at(&Position { x: 2, y: 2, z: -1 }).unwrap();
+        assert!(cave.roofed && !cave.enclosed);
+    }
+}
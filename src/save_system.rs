@@ -0,0 +1,501 @@
+use std::path::{Path, PathBuf};
+use crate::prelude::*;
+use crate::ColonyVision;
+
+// ============================================================================
+// SAVE / LOAD SYSTEM
+// ============================================================================
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(SaveSettings::default())
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>()
+            // Runs before Update so the rebuilt world is in place before any system touches it.
+            .add_systems(PreUpdate, (save_game_system, load_game_system))
+            .add_systems(
+                SimTick,
+                autosave_system
+                    .run_if(every_seconds(1.0)),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct SaveSettings {
+    pub directory: PathBuf,
+    pub autosave_every_days: i32, // 0 disables autosave
+}
+
+impl Default for SaveSettings {
+    fn default() -> Self {
+        SaveSettings {
+            directory: PathBuf::from("saves"),
+            autosave_every_days: 1,
+        }
+    }
+}
+
+impl SaveSettings {
+    pub fn quicksave_path(&self) -> PathBuf {
+        self.directory.join("quicksave.ron")
+    }
+    pub fn autosave_path(&self) -> PathBuf {
+        self.directory.join("autosave.ron")
+    }
+}
+
+#[derive(Event)]
+pub struct SaveGameEvent {
+    pub path: PathBuf,
+}
+
+#[derive(Event)]
+pub struct LoadGameEvent {
+    pub path: PathBuf,
+}
+
+// ============================================================================
+// SAVE FILE FORMAT
+// ============================================================================
+
+// Entity ids stored in a save are only used to remap references on load.
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub seed: u64,
+    pub clock: SimClock,
+    pub tiles: TileMap,
+    pub vision: ColonyVision,
+    #[serde(default)]
+    pub mining: MiningDesignations,
+    #[serde(default)]
+    pub temperatures: crate::TileTemperatures,
+    pub entities: Vec<SavedEntity>,
+    pub squads: Vec<Squad>,
+    #[serde(default)]
+    pub research: SavedResearch,
+    pub weather: Weather,
+    pub time_of_day: TimeOfDay,
+    pub population: Population,
+    pub diplomacy: DiplomacyState,
+}
+
+// The techs themselves are read from assets/techs at startup; a save only keeps how far
+// research has got, so tech data can be changed without breaking old saves.
+#[derive(Default, Serialize, Deserialize)]
+pub struct SavedResearch {
+    pub unlocked_techs: Vec<String>,
+    pub researching: Option<ResearchProgress>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedEntity {
+    pub entity: Entity,
+    pub position: Position,
+    pub actor_type: Option<ActorType>,
+    pub name: Option<HasName>,
+    pub physical_body: Option<PhysicalBody>,
+    pub brain: Option<Brain>,
+    pub inventory: Option<Inventory>,
+    pub equipment: Option<Equipment>,
+    pub object: Option<Object>,
+    pub plant: Option<Plant>,
+    pub food: Option<Food>,
+    pub item_type: Option<ItemType>,
+    pub building: Option<Building>,
+    pub nest: Option<Nest>,
+    pub targeting: Option<Entity>,
+    pub generated_by: Option<Entity>,
+    pub monster_generator: Option<Vec<(String, u8)>>,
+    pub movement: Option<MovementProfile>,
+    #[serde(default)]
+    pub zone: Option<Zone>,
+    #[serde(default)]
+    pub fire: Option<crate::Fire>,
+    #[serde(default)]
+    pub charred: Option<crate::Charred>,
+    pub markers: Vec<SavedMarker>,
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SavedMarker {
+    Foragable,
+    Choppable,
+    Carryable,
+    Logs,
+    WorkTarget,
+    SetNest,
+    GiveMeAName,
+    Attackable,
+    Huntable,
+    MoveRandom,
+    MoveTowardsNearestAttackable,
+}
+
+impl SavedEntity {
+    fn sprite(&self) -> Option<(usize, f32)> {
+        if let Some(actor_type) = self.actor_type {
+            Some((actor_type.sprite_index(), 1.0))
+        } else if self.building.is_some() {
+            Some((3 * 64 + 10, 1.0))
+        } else if self.monster_generator.is_some() {
+            Some((TileType::Cave.sprite_index(), 1.0))
+        } else if let Some(plant) = &self.plant {
+            Some((plant.plant_type.sprite_index(), 0.5))
+        } else if let Some(object) = &self.object {
+            Some((object.itemtype.sprite_index(), 0.5))
+        } else {
+            self.item_type.map(|item_type| (item_type.sprite_index(), 2.0))
+        }
+    }
+}
+
+pub fn write_save(path: &Path, save: &SaveGame) -> std::io::Result<()> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    let contents = ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(path, contents)
+}
+
+pub fn read_save(path: &Path) -> std::io::Result<SaveGame> {
+    let contents = std::fs::read_to_string(path)?;
+    ron::from_str(&contents).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+type SavedQuery<'a> = (
+    Entity,
+    &'a Position,
+    (Option<&'a ActorType>, Option<&'a HasName>, Option<&'a PhysicalBody>, Option<&'a Brain>, Option<&'a Inventory>, Option<&'a Equipment>),
+    (Option<&'a Object>, Option<&'a Plant>, Option<&'a Food>, Option<&'a ItemType>, Option<&'a Building>, Option<&'a Nest>),
+    (Option<&'a Targeting>, Option<&'a GeneratedBy>, Option<&'a MonsterGenerator>, Option<&'a MovementProfile>, Option<&'a Zone>,
+        Option<&'a crate::Fire>, Option<&'a crate::Charred>),
+    (Option<&'a Foragable>, Option<&'a Choppable>, Option<&'a Carryable>, Option<&'a Logs>, Option<&'a WorkTarget>, Option<&'a SetNest>, Option<&'a GiveMeAName>,
+        Option<&'a Attackable>, Option<&'a Huntable>, Option<&'a MoveRandom>, Option<&'a MoveTowardsNearestAttackable>),
+);
+
+#[allow(clippy::too_many_arguments)]
+fn save_game_system(
+    mut events: EventReader<SaveGameEvent>,
+    tile_map: Res<TileMap>,
+    vision: Res<ColonyVision>,
+    mining: Res<MiningDesignations>,
+    temperatures: Res<crate::TileTemperatures>,
+    entities: Query<SavedQuery, (Without<MapTile>, Without<TemporaryVisualElement>)>,
+    squads: Query<&Squad>,
+    game_rng: Res<GameRng>,
+    clock: Res<SimClock>,
+    tech_tree: Res<TechnologyTree>,
+    weather: Res<Weather>,
+    time_of_day: Res<TimeOfDay>,
+    population: Res<Population>,
+    diplomacy: Res<DiplomacyState>,
+) {
+    for event in events.iter() {
+        let mut saved_entities = Vec::new();
+        for (entity, position, (actor_type, name, physical_body, brain, inventory, equipment),
+            (object, plant, food, item_type, building, nest),
+            (targeting, generated_by, monster_generator, movement, zone, fire, charred),
+            (foragable, choppable, carryable, logs, work_target, set_nest, give_me_a_name,
+                attackable, huntable, move_random, move_towards_nearest_attackable)) in entities.iter()
+        {
+            let markers = [
+                (foragable.is_some(), SavedMarker::Foragable),
+                (choppable.is_some(), SavedMarker::Choppable),
+                (carryable.is_some(), SavedMarker::Carryable),
+                (logs.is_some(), SavedMarker::Logs),
+                (work_target.is_some(), SavedMarker::WorkTarget),
+                (set_nest.is_some(), SavedMarker::SetNest),
+                (give_me_a_name.is_some(), SavedMarker::GiveMeAName),
+                (attackable.is_some(), SavedMarker::Attackable),
+                (huntable.is_some(), SavedMarker::Huntable),
+                (move_random.is_some(), SavedMarker::MoveRandom),
+                (move_towards_nearest_attackable.is_some(), SavedMarker::MoveTowardsNearestAttackable),
+            ].iter().filter(|(present, _)| *present).map(|(_, marker)| *marker).collect();
+            saved_entities.push(SavedEntity {
+                entity,
+                position: *position,
+                actor_type: actor_type.copied(),
+                name: name.cloned(),
+                physical_body: physical_body.cloned(),
+                brain: brain.cloned(),
+                inventory: inventory.cloned(),
+                equipment: equipment.cloned(),
+                object: object.cloned(),
+                plant: plant.cloned(),
+                food: food.cloned(),
+                item_type: item_type.copied(),
+                building: building.cloned(),
+                nest: nest.cloned(),
+                targeting: targeting.map(|targeting| targeting.target),
+                generated_by: generated_by.map(|generated_by| generated_by.entity),
+                monster_generator: monster_generator.map(|generator| generator.monsters.clone()),
+                movement: movement.copied(),
+                zone: zone.cloned(),
+                fire: fire.copied(),
+                charred: charred.copied(),
+                markers,
+            });
+        }
+        let save = SaveGame {
+            seed: game_rng.seed,
+            clock: clock.clone(),
+            tiles: tile_map.clone(),
+            vision: vision.clone(),
+            mining: mining.clone(),
+            temperatures: temperatures.clone(),
+            entities: saved_entities,
+            squads: squads.iter().cloned().collect(),
+            research: SavedResearch {
+                unlocked_techs: tech_tree.unlocked_techs.clone(),
+                researching: tech_tree.researching.clone(),
+            },
+            weather: weather.clone(),
+            time_of_day: time_of_day.clone(),
+            population: population.clone(),
+            diplomacy: diplomacy.clone(),
+        };
+        match write_save(&event.path, &save) {
+            Ok(()) => println!("Saved game to {}", event.path.display()),
+            Err(e) => eprintln!("Could not save game to {}: {}", event.path.display(), e),
+        }
+    }
+}
+
+fn load_game_system(
+    mut commands: Commands,
+    mut events: EventReader<LoadGameEvent>,
+    existing: Query<Entity, Or<(With<Position>, With<Squad>)>>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
+    font: Res<MyFont>,
+    mut tech_tree: ResMut<TechnologyTree>,
+) {
+    // Only the most recent request matters.
+    let Some(event) = events.iter().last() else { return; };
+    let save = match read_save(&event.path) {
+        Ok(save) => save,
+        Err(e) => {
+            eprintln!("Could not load game from {}: {}", event.path.display(), e);
+            return;
+        }
+    };
+    let sprite_sheet = sprite_sheet.as_deref();
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // Spawn every entity before inserting anything, so references can point forward.
+    // Tile sprites aren't saved; they are spawned as the camera comes near them.
+    let mut entity_map: HashMap<Entity, Entity> = HashMap::new();
+    for saved in save.entities.iter() {
+        if let Some(zone) = &saved.zone {
+            entity_map.insert(saved.entity, crate::spawn_zone(&mut commands, saved.position, zone.clone()));
+            continue;
+        }
+        let new_entity = match saved.sprite() {
+            Some((sprite_index, layer)) => crate::spawn_sprite(&mut commands, sprite_sheet, sprite_index)
+                .insert(saved.position.to_transform_layer(layer))
+                .id(),
+            None => commands.spawn(SpatialBundle::default())
+                .insert(saved.position.to_transform())
+                .id(),
+        };
+        entity_map.insert(saved.entity, new_entity);
+    }
+    let remap = |entity: Entity| entity_map.get(&entity).copied();
+
+    for saved in save.entities.iter() {
+        let entity = entity_map[&saved.entity];
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(saved.position);
+        if let Some(actor_type) = saved.actor_type { entity_commands.insert(actor_type); }
+        if let Some(name) = &saved.name { entity_commands.insert(name.clone()); }
+        if let Some(physical_body) = &saved.physical_body {
+            let mut physical_body = physical_body.clone();
+            if let Some(danger) = &mut physical_body.danger {
+                danger.danger_source = danger.danger_source.and_then(remap);
+            }
+            entity_commands.insert(physical_body);
+        }
+        if let Some(brain) = &saved.brain { entity_commands.insert(brain.clone()); }
+        if let Some(inventory) = &saved.inventory { entity_commands.insert(inventory.clone()); }
+        if let Some(equipment) = &saved.equipment { entity_commands.insert(equipment.clone()); }
+        if let Some(object) = &saved.object { entity_commands.insert(object.clone()); }
+        if let Some(plant) = &saved.plant { entity_commands.insert(plant.clone()); }
+        if let Some(food) = &saved.food { entity_commands.insert(food.clone()); }
+        if let Some(item_type) = saved.item_type { entity_commands.insert(item_type); }
+        if let Some(building) = &saved.building {
+            let mut building = building.clone();
+            building.garrison = building.garrison.iter().filter_map(|e| remap(*e)).collect();
+            entity_commands.insert(building);
+        }
+        if let Some(nest) = &saved.nest { entity_commands.insert(nest.clone()); }
+        if let Some(target) = saved.targeting.and_then(remap) {
+            entity_commands.insert(Targeting { target });
+        }
+        if let Some(generator) = saved.generated_by.and_then(remap) {
+            entity_commands.insert(GeneratedBy { entity: generator });
+        }
+        if let Some(monsters) = &saved.monster_generator {
+            entity_commands.insert(MonsterGenerator { monsters: monsters.clone() });
+        }
+        if let Some(movement) = saved.movement { entity_commands.insert(movement); }
+        if let Some(fire) = saved.fire { entity_commands.insert(fire); }
+        if let Some(charred) = saved.charred { entity_commands.insert(charred); }
+        for marker in saved.markers.iter() {
+            match marker {
+                SavedMarker::Foragable => { entity_commands.insert(Foragable); }
+                SavedMarker::Choppable => { entity_commands.insert(Choppable); }
+                SavedMarker::Carryable => { entity_commands.insert(Carryable); }
+                SavedMarker::Logs => { entity_commands.insert(Logs); }
+                SavedMarker::WorkTarget => { entity_commands.insert(WorkTarget); }
+                SavedMarker::SetNest => { entity_commands.insert(SetNest); }
+                SavedMarker::GiveMeAName => { entity_commands.insert(GiveMeAName); }
+                SavedMarker::Attackable => { entity_commands.insert(Attackable); }
+                SavedMarker::Huntable => { entity_commands.insert(Huntable); }
+                SavedMarker::MoveRandom => { entity_commands.insert(MoveRandom); }
+                SavedMarker::MoveTowardsNearestAttackable => { entity_commands.insert(MoveTowardsNearestAttackable); }
+            }
+        }
+        if saved.markers.contains(&SavedMarker::WorkTarget) {
+            crate::create_marker(&mut commands, &entity, font.0.clone());
+        }
+    }
+
+    for squad in save.squads.iter() {
+        let mut squad = squad.clone();
+        squad.members = squad.members.iter().filter_map(|e| remap(*e)).collect();
+        squad.leader = squad.leader.and_then(remap);
+        for (i, &member) in squad.members.iter().enumerate() {
+            commands.entity(member).insert(FormationPosition {
+                squad_id: squad.squad_id,
+                relative_position: Vec2::ZERO,
+                rank: i as i32 / 4,
+            });
+        }
+        commands.spawn(squad);
+    }
+
+    let mut diplomacy = save.diplomacy;
+    for faction in diplomacy.factions.values_mut() {
+        faction.leader = faction.leader.and_then(remap);
+    }
+    diplomacy.trade_routes.retain_mut(|route| {
+        match (remap(route.from_building), remap(route.to_building)) {
+            (Some(from_building), Some(to_building)) => {
+                route.from_building = from_building;
+                route.to_building = to_building;
+                route.caravans = route.caravans.iter().filter_map(|e| remap(*e)).collect();
+                true
+            }
+            _ => false,
+        }
+    });
+
+    commands.insert_resource(GameRng::new(save.seed));
+    commands.insert_resource(save.tiles);
+    commands.insert_resource(save.vision);
+    commands.insert_resource(save.mining);
+    commands.insert_resource(save.temperatures);
+    commands.insert_resource(TileChunks::default());
+    commands.insert_resource(save.clock);
+    // Techs that have since been removed from the data files are dropped.
+    tech_tree.unlocked_techs = save.research.unlocked_techs.into_iter()
+        .filter(|tech_id| tech_tree.get(tech_id).is_some())
+        .collect();
+    tech_tree.researching = save.research.researching
+        .filter(|research| tech_tree.get(&research.tech_id).is_some());
+    commands.insert_resource(save.weather);
+    commands.insert_resource(save.time_of_day);
+    commands.insert_resource(save.population);
+    commands.insert_resource(diplomacy);
+    println!("Loaded game from {}", event.path.display());
+}
+
+fn autosave_system(
+    time_of_day: Res<TimeOfDay>,
+    settings: Res<SaveSettings>,
+    mut last_day: Local<Option<i32>>,
+    mut save_events: EventWriter<SaveGameEvent>,
+) {
+    let day = time_of_day.day;
+    if *last_day == Some(day) { return; }
+    let first_check = last_day.is_none();
+    *last_day = Some(day);
+    if first_check || settings.autosave_every_days <= 0 { return; }
+    if day % settings.autosave_every_days == 0 {
+        save_events.send(SaveGameEvent { path: settings.autosave_path() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::{headless_app, run_ticks, small_scenario};
+
+    // Saves and loads run in PreUpdate, so running just that schedule handles the request
+    // without the simulation moving on.
+    fn save(app: &mut App, path: &Path) -> SaveGame {
+        app.world.send_event(SaveGameEvent { path: path.to_path_buf() });
+        app.world.run_schedule(PreUpdate);
+        read_save(path).unwrap()
+    }
+
+    fn ron<T: Serialize>(value: &T) -> String {
+        ron::to_string(value).unwrap()
+    }
+
+    #[test]
+    fn saves_load_back_the_same() {
+        let directory = std::env::temp_dir().join(format!("colony-save-round-trip-{}", std::process::id()));
+        let mut app = headless_app(11, small_scenario());
+        run_ticks(&mut app, 300);
+        let before = save(&mut app, &directory.join("before.ron"));
+
+        app.world.send_event(LoadGameEvent { path: directory.join("before.ron") });
+        app.world.run_schedule(PreUpdate);
+        let after = save(&mut app, &directory.join("after.ron"));
+
+        assert_eq!(after.seed, before.seed);
+        assert_eq!(after.clock.tick, 300);
+        assert_eq!(ron(&after.tiles), ron(&before.tiles));
+        assert_eq!(ron(&after.vision), ron(&before.vision));
+        assert_eq!(ron(&after.temperatures), ron(&before.temperatures));
+        assert_eq!(ron(&after.research), ron(&before.research));
+        assert_eq!(ron(&after.weather), ron(&before.weather));
+        assert_eq!(after.squads.len(), before.squads.len());
+
+        // Entities get new ids on load, so compare everything else about them.
+        let without_ids = |save: &SaveGame| {
+            let mut entities: Vec<String> = save.entities.iter().map(|saved| {
+                let mut saved: SavedEntity = ron::from_str(&ron(saved)).unwrap();
+                (saved.entity, saved.targeting, saved.generated_by) = (Entity::PLACEHOLDER, None, None);
+                if let Some(danger) = saved.physical_body.as_mut().and_then(|body| body.danger.as_mut()) {
+                    danger.danger_source = None;
+                }
+                ron(&saved)
+            }).collect();
+            entities.sort();
+            entities
+        };
+        assert_eq!(without_ids(&after), without_ids(&before));
+        // And the references were pointed at the new ids.
+        let ids: HashSet<Entity> = after.entities.iter().map(|saved| saved.entity).collect();
+        for saved in after.entities.iter() {
+            let danger_source = saved.physical_body.as_ref().and_then(|body| body.danger.as_ref()?.danger_source);
+            for reference in [saved.targeting, saved.generated_by, danger_source].into_iter().flatten() {
+                assert!(ids.contains(&reference), "{:?} refers to a missing entity", saved.position);
+            }
+        }
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
@@ -7,10 +7,9 @@ impl Plugin for SeasonsPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(
-            Update,
+            SimTick,
             seasons_system
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(2.0)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(2.0))
         )
         ;
     }
@@ -19,10 +18,12 @@ impl Plugin for SeasonsPlugin {
 pub fn seasons_system(
     mut commands: Commands,
     mut plants: Query<(Entity, &mut Plant, &mut Transform, Option<&Foragable>, Option<&Choppable>)>,
+    mut game_rng: ResMut<GameRng>,
 ) {
+    let rng = game_rng.stream(RngStream::Seasons);
     for (entity, mut plant, mut transform, foragable, choppable) in plants.iter_mut() {
         if plant.growth < 1.0 {
-            let rand = rand::thread_rng().gen_range(0..2);
+            let rand = rng.gen_range(0..2);
             let base_growth_speed = plant.plant_type.growth_speed();
             plant.growth += match rand { 0 => 3.0 * base_growth_speed, 1 => base_growth_speed, _ => 0.0 };
             transform.scale = Vec3::new(plant.growth, plant.growth, 1.0);
@@ -38,7 +39,6 @@ pub fn seasons_system(
         } else {
             plant.growth += 0.01;
             if plant.growth > 1.01 {
-                let mut rng = rand::thread_rng();
                 let death = rng.gen_range(0..100);
                 if death < 2 {
                     //commands.entity(entity).despawn();
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use super::prelude::*;
 
 // Make plugin.
@@ -110,21 +112,21 @@ pub fn select_huntables(
     unhighlight(commands, highlighteds, highlightboxes);
 }
 
+// Tiles are designated by position; their X is drawn by mark_mining_designations.
 pub fn select_mineables(
-    mut commands: Commands,
-    mut query: Query<(Entity, Option<&Mineable>), With<Highlighted>>,
+    commands: Commands,
+    query: Query<(&Position, &TileType), (With<Highlighted>, With<MapTile>)>,
     highlighteds: Query<Entity, With<Highlighted>>,
     highlightboxes: Query<Entity, With<HighlightBox>>,
     event: EventReader<SelectionEvent>,
     dragging: Res<Dragging>,
-    font: Res<MyFont>,
+    mut designations: ResMut<MiningDesignations>,
 ) {
     if event.is_empty() { return; }
     if dragging.looking_for != SelectableType::Mineable { return; }
-    for (entity, selection_reason) in query.iter_mut() {
-        if selection_reason.is_some() {
-            commands.entity(entity).insert(WorkTarget);
-            create_marker(&mut commands, &entity, font.0.clone());
+    for (position, tile_type) in query.iter() {
+        if tile_type.is_mineable() {
+            designations.designate(*position);
         }
     }
     unhighlight(commands, highlighteds, highlightboxes);
@@ -152,8 +154,8 @@ pub fn select_carryables(
 
 pub fn select_zoning(
     mut commands: Commands,
-    query: Query<(Entity, Option<&MapTile>), With<Highlighted>>,
-    zoned: Query<Entity, With<Zone>>,
+    query: Query<&Position, (With<Highlighted>, With<MapTile>)>,
+    zoned: Query<&Position, With<Zone>>,
     highlighteds: Query<Entity, With<Highlighted>>,
     highlightboxes: Query<Entity, With<HighlightBox>>,
     event: EventReader<SelectionEvent>,
@@ -161,27 +163,17 @@ pub fn select_zoning(
 ) {
     if event.is_empty() { return; }
     if dragging.looking_for != SelectableType::Zoning { return; }
-    'outer: for (entity, selection_reason) in query.iter() {
-        for zoned in zoned.iter() { if zoned == entity { continue 'outer; } } // Don't zone tiles that already have a zone.
-        if selection_reason.is_some() {
-            commands.entity(entity).insert(
-                Zone {
-                    zone_type: dragging.zone_type,
-                    item_type: dragging.item_type
-                }
-            );
-            let zonemarker = commands.spawn( (SpriteBundle {
-                sprite: Sprite {
-                        color: Color::rgba(0.8, 0.8, 1.0, 0.1),
-                        custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
-                        ..default()
-                    },
-                transform: Transform::from_xyz(0.0, 0.0, 300.0),
-                ..default()
-            }, ZoneMarker))
-            .id();
-            commands.entity(entity).push_children(&[zonemarker]);
-        }
+    let zoned: HashSet<Position> = zoned.iter().copied().collect(); // Don't zone tiles that already have a zone.
+    for position in query.iter() {
+        if zoned.contains(position) { continue; }
+        spawn_zone(
+            &mut commands,
+            *position,
+            Zone {
+                zone_type: dragging.zone_type,
+                item_type: dragging.item_type
+            }
+        );
     }
     unhighlight(commands, highlighteds, highlightboxes);
 }
@@ -202,13 +194,18 @@ fn unhighlight(
 fn select_unselecting(
     mut commands: Commands,
     highlighteds: Query<Entity, With<Highlighted>>,
+    highlighted_tiles: Query<&Position, (With<Highlighted>, With<MapTile>)>,
     workmarkers: Query<(Entity, &Parent), With<WorkMarker>>,
     highlightboxes: Query<Entity, With<HighlightBox>>,
     event: EventReader<SelectionEvent>,
     dragging: Res<Dragging>,
+    mut designations: ResMut<MiningDesignations>,
 ) {
     if event.is_empty() { return; }
     if dragging.looking_for != SelectableType::Unselecting { return; }
+    for position in highlighted_tiles.iter() {
+        designations.remove(position);
+    }
     for entity in highlighteds.iter() {
         commands.entity(entity).remove::<WorkTarget>();
         for (workmarker, parent) in workmarkers.iter() {
@@ -234,18 +231,18 @@ fn select_nothing(
 fn select_unzoning(
     mut commands: Commands,
     highlighteds: Query<Entity, With<Highlighted>>,
-    zonemarkers: Query<(Entity, &Parent), With<ZoneMarker>>,
+    highlighted_positions: Query<&Position, With<Highlighted>>,
+    zones: Query<(Entity, &Position), With<Zone>>,
     highlightboxes: Query<Entity, With<HighlightBox>>,
     event: EventReader<SelectionEvent>,
     dragging: Res<Dragging>,
 ) {
     if event.is_empty() { return; }
     if dragging.looking_for != SelectableType::Unzoning { return; }
-    for entity in highlighteds.iter() {
-        commands.entity(entity).remove::<Zone>();
-        for (zonemarker, parent) in zonemarkers.iter() {
-            if parent.get() != entity { continue; }
-            commands.entity(zonemarker).despawn();
+    let unzoned: HashSet<Position> = highlighted_positions.iter().copied().collect();
+    for (zone, position) in zones.iter() {
+        if unzoned.contains(position) {
+            commands.entity(zone).despawn_recursive();
         }
     }
     unhighlight(commands, highlighteds, highlightboxes);
@@ -265,4 +262,23 @@ pub fn create_marker(
     ))
     .insert(Transform::from_xyz(10.0, 20.0, 100.0)).id();
     commands.entity(*entity).push_children(&[child]);
-}
\ No newline at end of file
+}
+// A zone is an entity of its own on top of the tile, so it stays when the tile's sprite is streamed out.
+pub fn spawn_zone(
+    commands: &mut Commands,
+    position: Position,
+    zone: Zone,
+) -> Entity {
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+                color: Color::rgba(0.8, 0.8, 1.0, 0.1),
+                custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
+                ..default()
+            },
+        transform: position.to_transform_layer(300.0),
+        ..default()
+    })
+    .insert(position)
+    .insert(zone)
+    .id()
+}
@@ -0,0 +1,81 @@
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::utils::Duration;
+use crate::prelude::*;
+
+// ============================================================================
+// SIMULATION CLOCK
+// ============================================================================
+
+// Gameplay runs on whole ticks instead of frame time, so a run is the same on any machine
+// and at any speed. One tick is a tenth of a game second.
+pub const TICKS_PER_SECOND: u64 = 10;
+pub const SECONDS_PER_TICK: f32 = 1.0 / TICKS_PER_SECOND as f32;
+pub const SIM_SPEEDS: [u32; 3] = [1, 2, 4];
+
+// Every gameplay system lives in this schedule. It runs once per tick and not at all while paused.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SimTick;
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct SimClock {
+    pub tick: u64,
+    pub speed: u32,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        SimClock { tick: 0, speed: 1 }
+    }
+}
+
+impl SimClock {
+    pub fn seconds(&self) -> f32 {
+        self.tick as f32 * SECONDS_PER_TICK
+    }
+    pub fn set_speed(&mut self, speed: u32) {
+        if SIM_SPEEDS.contains(&speed) {
+            self.speed = speed;
+        }
+    }
+}
+
+pub fn seconds_to_ticks(seconds: f32) -> u64 {
+    ((seconds * TICKS_PER_SECOND as f32).round() as u64).max(1)
+}
+
+// Run condition: true on every tick that lands on the given interval of game seconds.
+pub fn every_seconds(seconds: f32) -> impl FnMut(Res<SimClock>) -> bool + Clone {
+    let ticks = seconds_to_ticks(seconds);
+    move |clock: Res<SimClock>| clock.tick.is_multiple_of(ticks)
+}
+
+pub struct SimClockPlugin;
+
+impl Plugin for SimClockPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_schedule(SimTick)
+            .insert_resource(SimClock::default())
+            .insert_resource(FixedTime::new_from_secs(SECONDS_PER_TICK))
+            .add_systems(FixedUpdate, run_sim_tick)
+            .add_systems(Update, sim_speed_system);
+    }
+}
+
+fn run_sim_tick(world: &mut World) {
+    if *world.resource::<State<GameState>>().get() != GameState::InGame {
+        return;
+    }
+    world.resource_mut::<SimClock>().tick += 1;
+    world.run_schedule(SimTick);
+}
+
+// Faster speeds just shorten the fixed timestep, so more ticks fit in each frame.
+fn sim_speed_system(
+    clock: Res<SimClock>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    if clock.is_changed() {
+        fixed_time.period = Duration::from_secs_f32(SECONDS_PER_TICK / clock.speed.max(1) as f32);
+    }
+}
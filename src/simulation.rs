@@ -0,0 +1,60 @@
+use crate::prelude::*;
+use crate::*;
+
+// Everything the colony needs to tick, with no rendering, input or UI attached.
+// Shared by the windowed game and headless runs.
+pub struct SimulationPlugin {
+    pub seed: u64,
+    pub scenario: Scenario,
+}
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(GameRng::new(self.seed))
+        .insert_resource(self.scenario.clone())
+        .add_plugins((BiomePlugin, StartupPlugin))
+        .add_state::<GameState>()
+        .add_plugins((SimClockPlugin, TileMapPlugin))
+        .add_systems(
+            SimTick,
+            movement_random
+            .run_if(every_seconds(0.1))
+        )
+        .add_plugins(
+            (MonsterGeneratorPlugin, MovementPlugin, SeasonsPlugin, NeedsPlugin, ThinkingPlugin, TaskPlugin,
+                CombatPlugin, SpoilagePlugin))
+        .add_plugins(
+            // NEW AAA PLUGINS
+            // Items and techs load before crafting, which checks recipes against them.
+            (InventoryPlugin, TechPlugin, CraftingPlugin, WeatherPlugin,
+                BuildingPlugin, FormationPlugin, DiplomacyPlugin))
+        // Unit templates check their starting gear against the item registry.
+        .add_plugins((UnitGeneratorPlugin, SavePlugin, FovPlugin, RoomPlugin, HeatPlugin, FirePlugin, FlowFieldPlugin))
+        .add_systems(SimTick, (
+            remove_bad_positions,
+            namegiving_system,
+            movement_toward_attackable,
+            nest_system,
+        ))
+        .add_event::<FoodNotifEvent>()
+        ;
+        // The data files the scenario refers to are loaded by the plugins above.
+        let world = &app.world;
+        if let Err(e) = self.scenario.validate_references(world.resource(), world.resource(), world.resource()) {
+            exit_with_data_errors("scenario", &[format!("{}: {}", self.scenario.name, e)]);
+        }
+    }
+}
+
+fn remove_bad_positions(
+    mut commands: Commands,
+    query: Query<(Entity, &Position), Without<MapTile>>,
+    tile_map: Res<TileMap>,
+) {
+    for (entity, position) in query.iter() {
+        if tile_map.is_wall(position) {
+            commands.entity(entity).insert(Dying);
+        }
+    }
+}
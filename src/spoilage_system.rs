@@ -7,24 +7,41 @@ impl Plugin for SpoilagePlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(
-            Update,
+            SimTick,
             spoilage_system
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(2.0)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(2.0))
         )
         ;
     }
 }
 
+// Food keeps longer the colder its tile, and not at all while it's frozen.
+const FREEZING: f32 = 0.0;
+// At this temperature food spoils at its usual rate.
+const ROOM_TEMPERATURE: f32 = 20.0;
+const MIN_SPOILAGE_FACTOR: f32 = 0.25;
+const MAX_SPOILAGE_FACTOR: f32 = 3.0;
+
 pub fn spoilage_system(
     mut commands: Commands,
-    mut food: Query<(Entity, &mut Food)>,
+    mut food: Query<(Entity, &mut Food, Option<&Position>)>,
+    tile_temperatures: Res<crate::TileTemperatures>,
 ) {
-    for (entity, mut food) in food.iter_mut() {
-        food.spoilage -= food.spoilage_rate;
+    for (entity, mut food, position) in food.iter_mut() {
+        food.spoilage -= food.spoilage_rate * spoilage_factor_at(position, &tile_temperatures);
         if food.spoilage < 0.0 {
             // TO DO: ALERT PLAYER.
             commands.entity(entity).despawn();
         }
     }
-}
\ No newline at end of file
+}
+// How much faster than usual food spoils at `position`: where it lies, or where its carrier stands.
+pub fn spoilage_factor_at(position: Option<&Position>, tile_temperatures: &crate::TileTemperatures) -> f32 {
+    let temperature = position.and_then(|position| tile_temperatures.at(position)).unwrap_or(ROOM_TEMPERATURE);
+    spoilage_factor(temperature)
+}
+
+fn spoilage_factor(temperature: f32) -> f32 {
+    if temperature <= FREEZING { return 0.0; }
+    (temperature / ROOM_TEMPERATURE).clamp(MIN_SPOILAGE_FACTOR, MAX_SPOILAGE_FACTOR)
+}
@@ -20,6 +20,11 @@ pub fn status_display_system (
                 vec_statuses.push("HUNGRY".to_string());
             }
         }
+        if let Some(n) = &physical_body.needs_water {
+            if n.current < 5.0 {
+                vec_statuses.push("THIRSTY".to_string());
+            }
+        }
         if let Some(n) = &physical_body.needs_entertainment {
             if n.current < 5.0 {
                 vec_statuses.push("BORED".to_string());
@@ -30,6 +35,9 @@ pub fn status_display_system (
                 vec_statuses.push("TIRED".to_string());
             }
         }
+        if physical_body.afflictions.iter().any(|affliction| matches!(affliction.affliction_type, AfflictionType::Burned)) {
+            vec_statuses.push("BURNED".to_string());
+        }
         if let Some(Task::Sleeping) = brain.task {
             vec_statuses.push("ZZZ...".to_string());
         }
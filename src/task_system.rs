@@ -1,8 +1,13 @@
 use super::prelude::*;
 mod chop;
+mod drink;
 mod eat;
+mod firefight;
+pub use firefight::FIREFIGHTING_RANGE;
+mod flee;
 mod forage;
 mod meander;
+mod mine;
 mod personality;
 use personality::PersonalityPlugin;
 mod plant;
@@ -18,43 +23,46 @@ impl Plugin for TaskPlugin {
         app
         .add_plugins(PersonalityPlugin)
         .add_systems(
-            Update,
+            SimTick,
             (
                 eat::task_system_eat
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
+            ,
+                drink::task_system_drink
+                .run_if(every_seconds(0.5))
+            ,
+                flee::task_system_flee
+                .run_if(every_seconds(0.5))
+            ,
+                firefight::task_system_firefight
+                .run_if(every_seconds(0.5))
             ,
                 sleep::task_system_sleep
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             ,
                 sleep::task_system_sleeping
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             ,
                 play::task_system_playing
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             ,
                 meander::task_system_meander
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             ,
                 work::task_system_work
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             ,
                 forage::task_system_forage
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             ,
                 chop::task_system_chop
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
+            ,
+                mine::task_system_mine
+                .run_if(every_seconds(0.5))
             ,
                 plant::task_system_zone
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
             )
         )
         ;
@@ -5,7 +5,7 @@ pub fn task_system_chop(
     mut entities_that_might_chop: Query<(Entity, &mut Brain, &Position, Option<&Pathing>, Option<&Targeting>)>,
     mut targets: Query<(Entity, &Position, &Choppable, &mut Plant), With<WorkTarget>>,
     workmarkers: Query<(Entity, &Parent), With<WorkMarker>>,
-    sprite_sheet: Res<SpriteSheet>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
 ) {
     let mut already_targeted = crate::set_already_targetted(&entities_that_might_chop);
     'outer: for (entity, mut brain, position, pathing,  targeting) in entities_that_might_chop.iter_mut() {
@@ -20,7 +20,7 @@ pub fn task_system_chop(
             if distance <= 1 && targeting.is_some() && targeting.unwrap().target == targetable_entity {
                 commands.entity(entity).remove::<Targeting>();
                 crate::remove_x_markers(&mut commands, & workmarkers, targetable_entity);
-                spawn_logs(&mut commands, targetable_entity, targetable_position, &sprite_sheet, &mut plant);
+                spawn_logs(&mut commands, targetable_entity, targetable_position, sprite_sheet.as_deref(), &mut plant);
                 continue 'outer;
             }
             // Unless it is already targetted by someone other than you.
@@ -49,7 +49,7 @@ fn spawn_logs(
     commands: &mut Commands,
     targetable_entity: Entity,
     targetable_position: &Position,
-    sprite_sheet: &Res<SpriteSheet>,
+    sprite_sheet: Option<&SpriteSheet>,
     plant: &mut Plant,
 ) {
     //plant.growth = 0.1;
@@ -59,12 +59,7 @@ fn spawn_logs(
         let mut p = *targetable_position;
         p.x += if (i%2) == 0 { i/2 } else { -i/2 };
         p.y += if (i%2) == 0 { i/2 } else { -i/2 };
-        let sprite =  TextureAtlasSprite::new( pt.sprite_index() );
-        commands.spawn(SpriteSheetBundle {
-            sprite,
-            texture_atlas: sprite_sheet.0.clone(),
-            ..Default::default()
-        })
+        crate::spawn_sprite(commands, sprite_sheet, pt.sprite_index())
         .insert(Logs )
         .insert(p)
         .insert(p.to_transform_layer(2.0))
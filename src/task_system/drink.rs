@@ -0,0 +1,69 @@
+use std::collections::{HashSet, VecDeque};
+use crate::prelude::*;
+
+// How many steps away a thirsty unit will look for water.
+const WATER_SEARCH_RANGE: i32 = 60;
+
+// Drinks from water next to the unit or from a hydrating item it carries; otherwise walks to
+// the nearest shore it can reach.
+pub fn task_system_drink(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Brain, &Position, &mut PhysicalBody, Option<&mut Inventory>, Option<&MovementProfile>), Without<Pathing>>,
+    tile_map: Res<TileMap>,
+    grid: Res<crate::PassabilityGrid>,
+) {
+    for (entity, mut brain, position, mut physical_body, inventory, movement) in query.iter_mut() {
+        if brain.task != Some(Task::Drink) { continue; }
+        let movement = movement.copied().unwrap_or_default();
+        let Some(needs_water) = physical_body.needs_water.as_mut() else {
+            brain.remotivate();
+            continue;
+        };
+        if next_to_water(&tile_map, position) {
+            needs_water.current = needs_water.max;
+        } else if let Some(hydration) = inventory.and_then(|mut inventory| drink_from_inventory(&mut inventory)) {
+            needs_water.current = (needs_water.current + hydration).min(needs_water.max);
+        } else if let Some(shore) = nearest_shore(&tile_map, &grid, position, &movement) {
+            commands.entity(entity).insert(Pathing { path: vec![], destination: shore, ..default() });
+            continue;
+        }
+        // Done drinking, or there's nothing to drink anywhere near.
+        brain.remotivate();
+    }
+}
+
+fn next_to_water(tile_map: &TileMap, position: &Position) -> bool {
+    (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+        .any(|(dx, dy)| tile_map.is_water(&Position { x: position.x + dx, y: position.y + dy, z: position.z }))
+}
+
+// Uses up one hydrating item and returns how much water it gave.
+fn drink_from_inventory(inventory: &mut Inventory) -> Option<f32> {
+    let (item_id, hydration) = inventory.slots.iter().flatten().find_map(|item| match &item.item_def.item_class {
+        ItemClass::Food(stats) if stats.hydration > 0.0 => Some((item.item_def.id.clone(), stats.hydration)),
+        _ => None,
+    })?;
+    inventory.remove_item(&item_id, 1).then_some(hydration)
+}
+
+// Walks outwards from the unit to the closest tile beside water. Stairs are followed like any
+// other step. The shore is checked against the grid's regions as well, so a unit is never
+// sent off on a path that fails and tries again every tick.
+fn nearest_shore(tile_map: &TileMap, grid: &crate::PassabilityGrid, start: &Position, movement: &MovementProfile) -> Option<Position> {
+    let mut seen = HashSet::from([*start]);
+    let mut open = VecDeque::from([(*start, 0)]);
+    while let Some((position, steps)) = open.pop_front() {
+        if !tile_map.is_water(&position) && next_to_water(tile_map, &position) {
+            return grid.is_reachable(tile_map, start, &position, movement).then_some(position);
+        }
+        if steps >= WATER_SEARCH_RANGE { continue; }
+        let neighbors = [(1, 0), (-1, 0), (0, 1), (0, -1)].iter()
+            .map(|(dx, dy)| Position { x: position.x + dx, y: position.y + dy, z: position.z })
+            .chain(tile_map.stairs_exit(&position));
+        for neighbor in neighbors {
+            if !grid.can_enter(&neighbor, movement) || !seen.insert(neighbor) { continue; }
+            open.push_back((neighbor, steps + 1));
+        }
+    }
+    None
+}
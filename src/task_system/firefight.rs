@@ -0,0 +1,40 @@
+use crate::prelude::*;
+
+// Intensity knocked off a fire each time a colonist beats at it.
+const FIREFIGHTING_POWER: f32 = 0.3;
+// Colonists drop their work for fires within this distance.
+pub const FIREFIGHTING_RANGE: i32 = 30;
+
+// Firefighters walk up beside the nearest fire and beat it down until it goes out, then move on
+// to the next one.
+pub fn task_system_firefight(
+    mut commands: Commands,
+    mut firefighters: Query<(Entity, &mut Brain, &Position), Without<Pathing>>,
+    mut fires: Query<(Entity, &Position, &mut crate::Fire)>,
+    tile_map: Res<TileMap>,
+) {
+    let burning = fires.iter().map(|(_, position, _)| *position).collect::<Vec<Position>>();
+    for (entity, mut brain, position) in firefighters.iter_mut() {
+        if brain.task != Some(Task::Firefight) { continue; }
+        let nearest_fire = fires.iter()
+            .filter(|(_, fire_position, fire)| fire_position.z == position.z && fire.intensity > 0.0 && fire_position.distance(position) <= FIREFIGHTING_RANGE)
+            .min_by_key(|(_, fire_position, _)| fire_position.distance(position))
+            .map(|(fire_entity, fire_position, _)| (fire_entity, *fire_position));
+        let Some((fire_entity, fire_position)) = nearest_fire else {
+            brain.remotivate();
+            continue;
+        };
+        if fire_position.distance(position) <= 1 {
+            let Ok((_, _, mut fire)) = fires.get_mut(fire_entity) else { continue; };
+            fire.intensity -= FIREFIGHTING_POWER;
+            if fire.intensity <= 0.0 {
+                crate::put_out(&mut commands, fire_entity, &fire);
+            }
+            continue;
+        }
+        match crate::firefighting_spot(&tile_map, &burning, position, &fire_position) {
+            Some(spot) => { commands.entity(entity).insert(Pathing { path: vec![], destination: spot, ..default() }); }
+            None => brain.remotivate(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::prelude::*;
+
+// How far from the nearest fire a unit tries to get before it feels safe.
+const FLEE_DISTANCE: i32 = 6;
+
+// Units in danger from a fire run away from it, and calm down once it's far enough behind them.
+// Hemmed in with nowhere to run, they make for the colony's meeting zone instead, if it has one
+// they can get to.
+pub fn task_system_flee(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Brain, &mut PhysicalBody, &Position, Option<&MovementProfile>, Option<&crate::FollowFlowField>), Without<Pathing>>,
+    fires: Query<&Position, With<crate::Fire>>,
+    zones: Query<(&Zone, &Position)>,
+    grid: Res<crate::PassabilityGrid>,
+    tile_map: Res<TileMap>,
+) {
+    for (entity, mut brain, mut physical_body, position, movement, follower) in query.iter_mut() {
+        if brain.task != Some(Task::Flee) { continue; }
+        if !physical_body.danger.as_ref().is_some_and(|danger| danger.danger_type == DangerType::Fire) { continue; }
+        let nearest_fire = fires.iter()
+            .filter(|fire| fire.z == position.z && fire.distance(position) < FLEE_DISTANCE)
+            .min_by_key(|fire| fire.distance(position));
+        let Some(fire) = nearest_fire else {
+            physical_body.danger = None;
+            brain.remotivate();
+            commands.entity(entity).remove::<crate::FollowFlowField>();
+            continue;
+        };
+        let burning = fires.iter().copied().collect::<Vec<Position>>();
+        let movement = movement.copied().unwrap_or_default();
+        if let Some(refuge) = refuge(&grid, &burning, position, fire, &movement) {
+            commands.entity(entity).insert(Pathing { path: vec![], destination: refuge, ..default() });
+        } else if follower.is_none() {
+            let meeting_reachable = zones.iter()
+                .any(|(zone, zone_position)| zone.zone_type == ZoneType::Meeting && grid.is_reachable(&tile_map, position, zone_position, &movement));
+            if meeting_reachable {
+                commands.entity(entity).insert(crate::FollowFlowField::new(crate::FlowGoal::Zone(ZoneType::Meeting)));
+            }
+        }
+    }
+}
+
+// A tile FLEE_DISTANCE away, as directly away from the fire as the map allows.
+fn refuge(grid: &crate::PassabilityGrid, burning: &[Position], position: &Position, fire: &Position, movement: &MovementProfile) -> Option<Position> {
+    let away = Vec2::new((position.x - fire.x) as f32, (position.y - fire.y) as f32);
+    let away = if away == Vec2::ZERO { Vec2::X } else { away.normalize() };
+    // Straight away first, then veering off to either side.
+    [0.0_f32, 45.0, -45.0, 90.0, -90.0].iter()
+        .map(|degrees| Vec2::from_angle(degrees.to_radians()).rotate(away) * FLEE_DISTANCE as f32)
+        .map(|step| Position { x: position.x + step.x.round() as i32, y: position.y + step.y.round() as i32, z: position.z })
+        .find(|spot| grid.can_enter(spot, movement) && !burning.contains(spot))
+}
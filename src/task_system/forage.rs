@@ -4,7 +4,7 @@ pub fn task_system_forage(
     mut commands: Commands,
     mut entities_that_might_forage: Query<(Entity, &mut Brain, &Position, Option<&Pathing>, Option<&Targeting>)>,
     mut foragables: Query<(Entity, &Position, &Foragable, &mut Plant, Option<&WorkTarget>)>,
-    sprite_sheet: Res<SpriteSheet>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
 ) {
     let mut already_targeted = crate::set_already_targetted(&entities_that_might_forage);
     for (entity, mut brain, position, pathing, targeting) in entities_that_might_forage.iter_mut() {
@@ -18,7 +18,7 @@ pub fn task_system_forage(
             let distance = position.distance(foragable_position);
             if distance <= 1 && targeting.is_some() && targeting.unwrap().target == foragable_entity {
                 commands.entity(entity).remove::<Targeting>();
-                spawn_food(&mut commands, foragable_entity, foragable_position, &sprite_sheet, &mut plant);
+                spawn_food(&mut commands, foragable_entity, foragable_position, sprite_sheet.as_deref(), &mut plant);
                 commands.entity(entity).remove::<WorkTarget>();
                 did_foraging = true;
                 nearest_entity = None;
@@ -54,7 +54,7 @@ fn spawn_food(
     commands: &mut Commands,
     foragable_entity: Entity,
     foragable_position: &Position,
-    sprite_sheet: &Res<SpriteSheet>,
+    sprite_sheet: Option<&SpriteSheet>,
     plant: &mut Plant,
 ) {
     plant.growth = 0.1;
@@ -70,12 +70,7 @@ fn spawn_food(
         let mut p = *foragable_position;
         p.x += if (i%2) == 0 { i/2 } else { -i/2 };
         p.y += if (i%2) == 0 { i/2 } else { -i/2 };
-        let sprite =  TextureAtlasSprite::new(pt.sprite_index());
-        commands.spawn(SpriteSheetBundle {
-            sprite,
-            texture_atlas: sprite_sheet.0.clone(),
-            ..Default::default()
-        })
+        crate::spawn_sprite(commands, sprite_sheet, pt.sprite_index())
         .insert(Food { ..default() } )
         .insert(p)
         .insert(p.to_transform_layer(2.0))
@@ -2,14 +2,15 @@ use crate::prelude::*;
 
 pub fn task_system_meander(
     _commands: Commands,
-    mut query: Query<(Entity, &mut Brain, &mut Position, &mut Transform), Without<TileType>>,
-    tile_types: Query<(&Position, &TileType)>,
+    mut query: Query<(Entity, &mut Brain, &mut Position, &mut Transform, Option<&MovementProfile>), Without<TileType>>,
+    grid: Res<crate::PassabilityGrid>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    for (_entity, mut brain, mut position, mut transform) in query.iter_mut() {
+    let rng = game_rng.stream(RngStream::Meander);
+    for (_entity, mut brain, mut position, mut transform, movement) in query.iter_mut() {
         if brain.task != Some(Task::Meander) { continue; }
         brain.task = Some(Task::Meander);
         let mut new_position = *position;
-        let mut rng = rand::thread_rng();
         let dir = rng.gen_range(0..4);
         match dir {
             0 => new_position.y += 1,
@@ -18,14 +19,10 @@ pub fn task_system_meander(
             3 => new_position.x += 1,
             _ => {}
         }
-        for (tile_position, tile_type) in tile_types.iter() {
-            let mut p2 = new_position;
-            p2.z = 0;
-            if *tile_position == p2 && !tile_type.is_wall() {
-                *position = new_position;
-                transform.translation.x = new_position.x as f32 * TILE_SIZE;
-                transform.translation.y = new_position.y as f32 * TILE_SIZE;
-            }
+        if grid.can_enter(&new_position, &movement.copied().unwrap_or_default()) {
+            *position = new_position;
+            transform.translation.x = new_position.x as f32 * TILE_SIZE;
+            transform.translation.y = new_position.y as f32 * TILE_SIZE;
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,171 @@
+use crate::prelude::*;
+
+// Each skill level adds this much to a miner's speed.
+const MINING_SPEED_PER_LEVEL: f32 = 0.1;
+// Mining without a pickaxe is slow.
+const BARE_HANDS_EFFICIENCY: f32 = 0.5;
+const MINING_XP: i32 = 25;
+// Chance that mined rock also gives up a lump of iron ore.
+const ORE_CHANCE: f64 = 0.2;
+
+// Miners walk up to a designated tile and work at it until it gives way. Walls turn into rubble
+// floor; rocky ground is dug into stairs down, opening up the level below.
+#[allow(clippy::too_many_arguments)]
+pub fn task_system_mine(
+    mut commands: Commands,
+    mut entities_that_might_mine: Query<(Entity, &mut Brain, &Position, Option<&Pathing>, Option<&MiningTarget>)>,
+    mut workers: Query<(&mut PhysicalBody, Option<&Equipment>)>,
+    mut designations: ResMut<MiningDesignations>,
+    tile_map: Res<TileMap>,
+    mut set_tiles: EventWriter<SetTileEvent>,
+    mut game_rng: ResMut<GameRng>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
+) {
+    let mut already_targeted = claimed_sites(&entities_that_might_mine);
+    for (entity, mut brain, position, pathing, mining_target) in entities_that_might_mine.iter_mut() {
+        if pathing.is_some() { continue; }
+        if brain.task != Some(Task::Mine) { continue; }
+        // If you are already next to the tile you claimed, mine it.
+        let dig_site = mining_target
+            .map(|target| target.position)
+            .filter(|site| designations.contains(site) && position.distance(site) <= 1);
+        if let Some(dig_position) = dig_site {
+            let Ok((mut body, equipment)) = workers.get_mut(entity) else { continue; };
+            let Some(tile_type) = tile_map.get(&dig_position).cloned() else { continue; };
+            let Some(progress) = designations.progress_mut(&dig_position) else { continue; };
+            let efficiency = equipment.and_then(|equipment| equipment.tool_efficiency(ToolType::Pickaxe)).unwrap_or(BARE_HANDS_EFFICIENCY);
+            progress.work_done += (1.0 + body.skillset.mining.level() as f32 * MINING_SPEED_PER_LEVEL) * efficiency;
+            if progress.work_done < tile_type.mining_work() { continue; }
+            commands.entity(entity).remove::<MiningTarget>();
+            designations.remove(&dig_position);
+            let changes = tile_map.mining_changes(&dig_position);
+            if changes.is_empty() { continue; }
+            for (position, new_type) in changes {
+                set_tiles.send(SetTileEvent { position, tile_type: new_type });
+            }
+            spawn_rubble(&mut commands, &tile_type, &dig_position, game_rng.stream(RngStream::Mining), sprite_sheet.as_deref());
+            body.skillset.mining.experience += MINING_XP;
+            continue;
+        }
+        let own_site = mining_target.map(|target| target.position);
+        let closest = designations.sites()
+            // Unless it is already claimed by someone other than you.
+            .filter(|site| Some(**site) == own_site || !already_targeted.contains(site))
+            // Walls are mined from beside them, so one needs an open side.
+            .filter_map(|site| tile_map.work_spot(position, site).map(|spot| (*site, spot)))
+            .min_by_key(|(site, _)| position.distance(site));
+        if let Some((site, spot)) = closest {
+            commands.entity(entity).insert(MiningTarget { position: site });
+            commands.entity(entity).insert(Pathing { path: vec![], destination: spot, ..default() });
+            already_targeted.push(site);
+        } else {
+            commands.entity(entity).remove::<MiningTarget>();
+            commands.entity(entity).remove::<Pathing>();
+            brain.remotivate();
+        }
+    }
+}
+
+// Sites miners have already claimed.
+fn claimed_sites(
+    miners: &Query<(Entity, &mut Brain, &Position, Option<&Pathing>, Option<&MiningTarget>)>,
+) -> Vec<Position> {
+    miners.iter()
+        .filter_map(|(_, _, _, _, mining_target)| mining_target.map(|target| target.position))
+        .collect()
+}
+
+// Mined rock leaves stone behind, sometimes with iron ore; metal walls give ore.
+fn spawn_rubble(
+    commands: &mut Commands,
+    mined_type: &TileType,
+    position: &Position,
+    rng: &mut SimRng,
+    sprite_sheet: Option<&SpriteSheet>,
+) {
+    let mut items = match mined_type {
+        TileType::WallMetal => vec![ItemType::IronOre, ItemType::IronOre],
+        TileType::WallStone | TileType::Stone => vec![ItemType::Stone, ItemType::Stone],
+        _ => vec![ItemType::Stone],
+    };
+    let natural_rock = matches!(mined_type, TileType::WallStone | TileType::Stone | TileType::Cave);
+    if natural_rock && rng.gen_bool(ORE_CHANCE) {
+        items.push(ItemType::IronOre);
+    }
+    for item in items {
+        crate::spawn_sprite(commands, sprite_sheet, item.sprite_index())
+        .insert(*position)
+        .insert(position.to_transform_layer(2.0))
+        .insert(item)
+        ;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Has a bare-handed miner dig out a `wall` tile until its designation is done.
+    fn mine(wall: TileType) -> (World, Entity, Position) {
+        let mut world = World::new();
+        let site = Position { x: 2, y: 2, z: 0 };
+        let mut tiles = vec![TileType::Grass; 25];
+        tiles[12] = wall;
+        world.insert_resource(TileMap::from_levels(5, 5, vec![tiles]));
+        let mut designations = MiningDesignations::default();
+        designations.designate(site);
+        world.insert_resource(designations);
+        world.insert_resource(GameRng::new(1));
+        world.init_resource::<Events<SetTileEvent>>();
+        let miner = world.spawn((
+            Brain { task: Some(Task::Mine), ..default() },
+            Position { x: 1, y: 2, z: 0 },
+            PhysicalBody {
+                needs_food: None,
+                needs_entertainment: None,
+                needs_sleep: None,
+                needs_water: None,
+                index: 0,
+                crisis: None,
+                danger: None,
+                injured: false,
+                afflictions: vec![],
+                skillset: Skillset::default(),
+                attributes: Attributeset::default(),
+            },
+            MiningTarget { position: site },
+        )).id();
+        let mut schedule = Schedule::new();
+        schedule.add_systems(task_system_mine);
+        for _ in 0..100 {
+            schedule.run(&mut world);
+            if !world.resource::<MiningDesignations>().contains(&site) { break; }
+        }
+        (world, miner, site)
+    }
+
+    fn items_at(world: &mut World, site: &Position) -> Vec<ItemType> {
+        world.query::<(&ItemType, &Position)>().iter(world)
+            .filter(|(_, position)| *position == site)
+            .map(|(item_type, _)| *item_type)
+            .collect()
+    }
+
+    #[test]
+    fn mined_walls_leave_rubble_and_train_the_miner() {
+        let (mut world, miner, site) = mine(TileType::WallStone);
+        assert!(!world.resource::<MiningDesignations>().contains(&site));
+        let events = world.resource::<Events<SetTileEvent>>();
+        let changes: Vec<_> = events.get_reader().iter(events).map(|event| (event.position, event.tile_type.clone())).collect();
+        assert_eq!(changes, vec![(site, MINED_FLOOR)]);
+        assert_eq!(world.get::<PhysicalBody>(miner).unwrap().skillset.mining.experience, MINING_XP);
+        assert!(world.get::<MiningTarget>(miner).is_none());
+        let rubble = items_at(&mut world, &site);
+        assert_eq!(rubble.iter().filter(|item| **item == ItemType::Stone).count(), 2);
+        assert!(rubble.iter().all(|item| matches!(item, ItemType::Stone | ItemType::IronOre)));
+
+        // Metal walls give ore, and only ore.
+        let (mut world, _, site) = mine(TileType::WallMetal);
+        assert_eq!(items_at(&mut world, &site), vec![ItemType::IronOre, ItemType::IronOre]);
+    }
+}
@@ -10,32 +10,33 @@ impl Plugin for PersonalityPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(
-            Update,
+            SimTick,
                 personalities
-                .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-                .run_if(in_state(GameState::InGame))
+                .run_if(every_seconds(0.5))
         );
     }
 }
 
 pub fn personalities(
-    mut entities: Query<(Entity, &mut Brain, &mut PhysicalBody, &Position, Option<&Nest>, Option<&Targeting>)>,
+    mut entities: Query<(Entity, &mut Brain, &mut PhysicalBody, &Position, Option<&Nest>, Option<&Targeting>, Option<&MovementProfile>)>,
     mut objects: Query<(Entity, Option<&Object>, Option<&Zone>, Option<&WorkTarget>, &Position)>,
-    tilehash: Res<TileHash>,
-    objects2: Query<(&Object, &Position)>,
-    zones: Query<(&Zone, &Position)>,
+    miners: Query<&MiningTarget>,
+    mining: Res<MiningDesignations>,
+    tile_map: Res<TileMap>,
+    grid: Res<crate::PassabilityGrid>,
+    fires: Query<&Position, With<crate::Fire>>,
 ) {
-    let tiletypes: &std::collections::HashMap<Position, TileType> = &tilehash.hash;
-    let obstacles = crate::collect_obstacles(objects2, zones, tiletypes);
+    let burning = fires.iter().copied().collect::<Vec<Position>>();
     let potential_targets = entities.iter()
-        .map(|(entity, _, _, position, _, _)| (entity, *position)) // Clone the Position data
+        .map(|(entity, _, _, position, _, _, _)| (entity, *position)) // Clone the Position data
         .collect::<Vec<(Entity, Position)>>();
     let already_targeted = entities
         .iter()
-        .filter(|(_, _, _, _, _, targeting)| targeting.is_some())
-        .map(|(_, _, _, _, _, targeting)| targeting.unwrap().target)
+        .filter(|(_, _, _, _, _, targeting, _)| targeting.is_some())
+        .map(|(_, _, _, _, _, targeting, _)| targeting.unwrap().target)
         .collect::<Vec<Entity>>();
-    for (entity, mut brain, mut physical_body, position, nest, targeting) in entities.iter_mut() {
+    let claimed_sites = miners.iter().map(|target| target.position).collect::<Vec<Position>>();
+    for (entity, mut brain, mut physical_body, position, nest, targeting, movement) in entities.iter_mut() {
         if brain.task != Some(Task::Personality) { continue; }
         let next_trait = brain.get_next_personality_trait();
         match next_trait {
@@ -46,7 +47,7 @@ pub fn personalities(
                 territorial::territorial(entity, brain, physical_body, position, nest, &potential_targets);
             },
             Some(PersonalityTrait::Human) => {
-                human::human(entity, brain, physical_body, position, &objects, &already_targeted, &obstacles, &tiletypes);
+                human::human(entity, brain, physical_body, position, &objects, &already_targeted, &mining, &claimed_sites, &grid, &tile_map, &movement.copied().unwrap_or_default(), &burning);
             },
             _ => {
                 nopersonality::nopersonality(entity, brain, physical_body, position, nest);
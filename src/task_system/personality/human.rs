@@ -1,5 +1,6 @@
 use crate::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn human(
     entity: Entity,
     mut brain: Mut<Brain>,
@@ -7,11 +8,24 @@ pub fn human(
     position: &Position,
     potential_targets: &Query<(Entity, Option<&Object>, Option<&Zone>, Option<&WorkTarget>, &Position)>,
     already_targeted: &Vec<Entity>,
-    obstacles: &std::collections::HashSet<Position>,
-    tiletypes: &std::collections::HashMap<Position, TileType>,
+    mining: &MiningDesignations,
+    claimed_sites: &[Position],
+    grid: &crate::PassabilityGrid,
+    tile_map: &TileMap,
+    movement: &MovementProfile,
+    burning: &[Position],
 ) {
     if brain.task != Some(Task::Personality) { return; }
     if !brain.personality.contains(&PersonalityTrait::Human) { return; }
+    // Putting out fires comes before any other work.
+    let fire_to_fight = burning.iter()
+        .filter(|fire| fire.z == position.z && position.distance(fire) <= crate::FIREFIGHTING_RANGE)
+        .filter_map(|fire| crate::firefighting_spot(tile_map, burning, position, fire))
+        .any(|spot| spot == *position || crate::is_position_reachable(position, &spot, grid, tile_map, movement));
+    if fire_to_fight {
+        brain.task = Some(Task::Firefight);
+        return;
+    }
     let targets_to_choose_from: Vec<(Entity, u128)> = Vec::new();
     let object_positions: Vec<Position> = potential_targets.iter()
         .filter(|(_, object, _, _, _)| object.is_some())
@@ -27,7 +41,7 @@ pub fn human(
         if target_entity == entity { continue; }
         let distance = position.distance(&target_position);
         if distance > 50 { continue; }
-        let is_reachable = || crate::is_position_reachable(position, target_position, obstacles, tiletypes);
+        let is_reachable = || crate::is_position_reachable(position, target_position, grid, tile_map, movement);
         
         // TARGET OBJECTS
         if target_object.is_some() {
@@ -61,6 +75,15 @@ pub fn human(
             }
         }
     }
+    // TARGET TILES
+    for site in mining.sites() {
+        if claimed_sites.contains(site) { continue; }
+        if position.distance(site) > 50 { continue; }
+        // Walls can't be stood on, so check the spot a miner would work from.
+        let is_spot_reachable = || tile_map.work_spot(position, site)
+            .is_some_and(|spot| spot == *position || crate::is_position_reachable(position, &spot, grid, tile_map, movement));
+        if set_task(&mut brain, Task::Mine, is_spot_reachable) { return; }
+    }
     brain.motivation = Some(Motivation::Meander);
     brain.task = Some(Task::Meander);
 }
@@ -5,8 +5,8 @@ pub fn task_system_zone(
     mut entities_that_might_plant: Query<(Entity, &mut Brain, &Position, Option<&Pathing>, Option<&Targeting>)>,
     targetables: Query<(Entity, &Position, &Zone)>,
     // obstacles: Query<&Position, (Without<Brain>, Without<MapTile>)>, // This seems to be an "AND"
-    obstacles: Query<(Entity, &Position), Without<MapTile>>,
-    sprite_sheet: Res<SpriteSheet>,
+    obstacles: Query<(Entity, &Position), (Without<MapTile>, Without<Zone>)>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
 ) {
     let mut already_targeted = crate::set_already_targetted(&entities_that_might_plant);
     'brains: for (entity, mut brain, position, pathing, targeting) in entities_that_might_plant.iter_mut() {
@@ -27,10 +27,10 @@ pub fn task_system_zone(
                 commands.entity(entity).remove::<Targeting>();
                 match zone.zone_type {
                     ZoneType::Farm => {
-                        spawn_plant(&mut commands, targetable_position, &sprite_sheet, zone);
+                        spawn_plant(&mut commands, targetable_position, sprite_sheet.as_deref(), zone);
                     }
                     ZoneType::Construction => {
-                        spawn_building(&mut commands, targetable_position, &sprite_sheet, zone);
+                        spawn_building(&mut commands, targetable_position, sprite_sheet.as_deref(), zone);
                         commands.entity(targetable_entity).despawn_recursive();
                     }
                     _ => {}
@@ -58,21 +58,11 @@ pub fn task_system_zone(
 fn spawn_plant(
     commands: &mut Commands,
     position: &Position,
-    sprite_sheet: &Res<SpriteSheet>,
+    sprite_sheet: Option<&SpriteSheet>,
     zone: &Zone,
 ) {
     // commands.entity(foragable_entity).remove::<Foragable>();
-    let sprite =  TextureAtlasSprite::new(zone.item_type.sprite_index());
-    commands.spawn(SpriteSheetBundle {
-        sprite,
-        texture_atlas: sprite_sheet.0.clone(),
-        transform: Transform::from_xyz(
-            position.x as f32 * TILE_SIZE,
-            position.y as f32 * TILE_SIZE,
-            position.z as f32 * TILE_SIZE,
-        ),
-        ..default()
-    })
+    crate::spawn_sprite(commands, sprite_sheet, zone.item_type.sprite_index())
     .insert(*position)
     .insert(position.to_transform_layer(0.5))
     .insert(Plant { growth: 0.4, plant_type: zone.item_type })
@@ -83,21 +73,11 @@ fn spawn_plant(
 fn spawn_building(
     commands: &mut Commands,
     position: &Position,
-    sprite_sheet: &Res<SpriteSheet>,
+    sprite_sheet: Option<&SpriteSheet>,
     zone: &Zone,
 ) {
     // commands.entity(foragable_entity).remove::<Foragable>();
-    let sprite =  TextureAtlasSprite::new(zone.item_type.sprite_index());
-    commands.spawn(SpriteSheetBundle {
-        sprite,
-        texture_atlas: sprite_sheet.0.clone(),
-        transform: Transform::from_xyz(
-            position.x as f32 * TILE_SIZE,
-            position.y as f32 * TILE_SIZE,
-            position.z as f32 * TILE_SIZE,
-        ),
-        ..default()
-    })
+    crate::spawn_sprite(commands, sprite_sheet, zone.item_type.sprite_index())
     .insert(*position)
     .insert(position.to_transform_layer(0.5))
     .insert(Object { itemtype: zone.item_type, under_construction: false, ..default() })
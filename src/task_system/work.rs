@@ -1,12 +1,13 @@
 use crate::prelude::*;
 
 pub fn task_system_work(
-    mut query: Query<(Entity, &mut Brain, &Position), Without<Targeting>>
+    mut query: Query<(Entity, &mut Brain, &Position), Without<Targeting>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
+    let rng = game_rng.stream(RngStream::Work);
     for (_entity, mut brain, _position) in query.iter_mut() {
         // There is no work right now.
         if brain.task != Some(Task::Work) { continue; }
-        let mut rng = rand::thread_rng();
         // Generate number between 0 and 10
         let number: u8 = rng.gen_range(0..5);
         match number {
@@ -1,12 +1,13 @@
 use bevy::prelude::*;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use crate::prelude::*;
 
 // ============================================================================
 // TECHNOLOGY RESEARCH SYSTEM - AoE2 / Master of Orion 2 style
 // ============================================================================
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct TechnologyTree {
     pub unlocked_techs: Vec<String>,
     pub researching: Option<ResearchProgress>,
@@ -18,386 +19,114 @@ impl Default for TechnologyTree {
         TechnologyTree {
             unlocked_techs: vec![],
             researching: None,
-            available_techs: Self::initialize_tech_tree(),
+            available_techs: vec![],
         }
     }
 }
 
 impl TechnologyTree {
-    pub fn initialize_tech_tree() -> Vec<Technology> {
-        vec![
-            // AGE 1: STONE AGE
-            Technology {
-                id: "stone_tools".to_string(),
-                name: "Stone Tools".to_string(),
-                description: "Basic stone implements for survival".to_string(),
-                age: TechAge::StoneAge,
-                category: TechCategory::Tools,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 50,
-                    time: 30.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Recipe("craft_stone_axe".to_string()),
-                    Unlock::Recipe("craft_stone_pickaxe".to_string()),
-                ],
-            },
-            Technology {
-                id: "fire".to_string(),
-                name: "Fire Making".to_string(),
-                description: "The ability to create and control fire".to_string(),
-                age: TechAge::StoneAge,
-                category: TechCategory::Survival,
-                research_cost: ResearchCost {
-                    resources: vec![("wood".to_string(), 10)],
-                    research_points: 100,
-                    time: 60.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Building("campfire".to_string()),
-                    Unlock::Building("torch".to_string()),
-                    Unlock::Recipe("cook_meat".to_string()),
-                ],
-            },
-            Technology {
-                id: "basic_farming".to_string(),
-                name: "Basic Farming".to_string(),
-                description: "Cultivate crops for sustainable food".to_string(),
-                age: TechAge::StoneAge,
-                category: TechCategory::Economy,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 150,
-                    time: 90.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Building("farm".to_string()),
-                    Unlock::Recipe("plant_wheat".to_string()),
-                    Unlock::Recipe("plant_vegetables".to_string()),
-                ],
-            },
-            Technology {
-                id: "hunting".to_string(),
-                name: "Hunting".to_string(),
-                description: "Track and hunt wild animals".to_string(),
-                age: TechAge::StoneAge,
-                category: TechCategory::Military,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 100,
-                    time: 60.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Recipe("craft_spear".to_string()),
-                    Unlock::Ability("tracking".to_string()),
-                ],
-            },
-
-            // AGE 2: BRONZE AGE
-            Technology {
-                id: "mining".to_string(),
-                name: "Mining".to_string(),
-                description: "Extract valuable ores from the earth".to_string(),
-                age: TechAge::BronzeAge,
-                category: TechCategory::Economy,
-                research_cost: ResearchCost {
-                    resources: vec![("wood".to_string(), 20)],
-                    research_points: 200,
-                    time: 120.0,
-                },
-                prerequisites: vec!["stone_tools".to_string()],
-                unlocks: vec![
-                    Unlock::Building("mine".to_string()),
-                    Unlock::Resource("copper_ore".to_string()),
-                    Unlock::Resource("tin_ore".to_string()),
-                ],
-            },
-            Technology {
-                id: "smelting".to_string(),
-                name: "Smelting".to_string(),
-                description: "Refine ores into metal bars".to_string(),
-                age: TechAge::BronzeAge,
-                category: TechCategory::Economy,
-                research_cost: ResearchCost {
-                    resources: vec![("wood".to_string(), 30), ("stone".to_string(), 20)],
-                    research_points: 250,
-                    time: 150.0,
-                },
-                prerequisites: vec!["mining".to_string(), "fire".to_string()],
-                unlocks: vec![
-                    Unlock::Building("furnace".to_string()),
-                    Unlock::Recipe("smelt_copper_bar".to_string()),
-                    Unlock::Recipe("smelt_bronze_bar".to_string()),
-                ],
-            },
-            Technology {
-                id: "bronze_working".to_string(),
-                name: "Bronze Working".to_string(),
-                description: "Craft with bronze, a strong alloy".to_string(),
-                age: TechAge::BronzeAge,
-                category: TechCategory::Tools,
-                research_cost: ResearchCost {
-                    resources: vec![("bronze_bar".to_string(), 10)],
-                    research_points: 300,
-                    time: 180.0,
-                },
-                prerequisites: vec!["smelting".to_string()],
-                unlocks: vec![
-                    Unlock::Recipe("craft_bronze_sword".to_string()),
-                    Unlock::Recipe("craft_bronze_armor".to_string()),
-                    Unlock::Recipe("craft_bronze_tools".to_string()),
-                ],
-            },
-            Technology {
-                id: "marksmanship".to_string(),
-                name: "Marksmanship".to_string(),
-                description: "Master ranged ballistic weapons".to_string(),
-                age: TechAge::BronzeAge,
-                category: TechCategory::Military,
-                research_cost: ResearchCost {
-                    resources: vec![("wood".to_string(), 25)],
-                    research_points: 200,
-                    time: 120.0,
-                },
-                prerequisites: vec!["hunting".to_string()],
-                unlocks: vec![
-                    Unlock::Recipe("craft_railgun".to_string()),
-                    Unlock::Recipe("craft_railgun_slugs".to_string()),
-                    Unlock::Unit("rifleman".to_string()),
-                ],
-            },
-            Technology {
-                id: "masonry".to_string(),
-                name: "Masonry".to_string(),
-                description: "Build with stone structures".to_string(),
-                age: TechAge::BronzeAge,
-                category: TechCategory::Construction,
-                research_cost: ResearchCost {
-                    resources: vec![("stone".to_string(), 50)],
-                    research_points: 250,
-                    time: 150.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Building("stone_wall".to_string()),
-                    Unlock::Building("stone_house".to_string()),
-                    Unlock::Building("watchtower".to_string()),
-                ],
-            },
-
-            // AGE 3: IRON AGE
-            Technology {
-                id: "ironworking".to_string(),
-                name: "Iron Working".to_string(),
-                description: "Smelt and forge iron, stronger than bronze".to_string(),
-                age: TechAge::IronAge,
-                category: TechCategory::Tools,
-                research_cost: ResearchCost {
-                    resources: vec![("iron_ore".to_string(), 30)],
-                    research_points: 500,
-                    time: 300.0,
-                },
-                prerequisites: vec!["smelting".to_string()],
-                unlocks: vec![
-                    Unlock::Recipe("smelt_iron_bar".to_string()),
-                    Unlock::Recipe("craft_iron_sword".to_string()),
-                    Unlock::Recipe("craft_iron_armor".to_string()),
-                    Unlock::Recipe("craft_iron_axe".to_string()),
-                    Unlock::Recipe("craft_iron_pickaxe".to_string()),
-                ],
-            },
-            Technology {
-                id: "advanced_weaponry".to_string(),
-                name: "Advanced Weaponry".to_string(),
-                description: "Craft superior weapons of war".to_string(),
-                age: TechAge::IronAge,
-                category: TechCategory::Military,
-                research_cost: ResearchCost {
-                    resources: vec![("iron_bar".to_string(), 50)],
-                    research_points: 600,
-                    time: 360.0,
-                },
-                prerequisites: vec!["ironworking".to_string()],
-                unlocks: vec![
-                    Unlock::Recipe("craft_war_axe".to_string()),
-                    Unlock::Recipe("craft_longsword".to_string()),
-                    Unlock::Recipe("craft_battle_hammer".to_string()),
-                    Unlock::Unit("swordsman".to_string()),
-                ],
-            },
-            Technology {
-                id: "precision_weapons".to_string(),
-                name: "Precision Weapons".to_string(),
-                description: "Develop gauss rifles and sniper systems".to_string(),
-                age: TechAge::IronAge,
-                category: TechCategory::Military,
-                research_cost: ResearchCost {
-                    resources: vec![("iron_bar".to_string(), 40), ("wood".to_string(), 40)],
-                    research_points: 550,
-                    time: 330.0,
-                },
-                prerequisites: vec!["marksmanship".to_string(), "ironworking".to_string()],
-                unlocks: vec![
-                    Unlock::Recipe("craft_gauss_rifle".to_string()),
-                    Unlock::Recipe("craft_gauss_charges".to_string()),
-                    Unlock::Unit("sniper".to_string()),
-                ],
-            },
-            Technology {
-                id: "defense_systems".to_string(),
-                name: "Defense Systems".to_string(),
-                description: "Build fortified command centers and defense structures".to_string(),
-                age: TechAge::IronAge,
-                category: TechCategory::Construction,
-                research_cost: ResearchCost {
-                    resources: vec![("stone".to_string(), 100), ("iron_bar".to_string(), 30)],
-                    research_points: 700,
-                    time: 420.0,
-                },
-                prerequisites: vec!["masonry".to_string()],
-                unlocks: vec![
-                    Unlock::Building("command_center".to_string()),
-                    Unlock::Building("gate".to_string()),
-                    Unlock::Building("defense_turret".to_string()),
-                ],
-            },
-            Technology {
-                id: "mechanized_units".to_string(),
-                name: "Mechanized Units".to_string(),
-                description: "Deploy combat vehicles and armored units".to_string(),
-                age: TechAge::IronAge,
-                category: TechCategory::Military,
-                research_cost: ResearchCost {
-                    resources: vec![("iron_bar".to_string(), 40)],
-                    research_points: 600,
-                    time: 360.0,
-                },
-                prerequisites: vec!["ironworking".to_string()],
-                unlocks: vec![
-                    Unlock::Building("vehicle_bay".to_string()),
-                    Unlock::Unit("armored_vehicle".to_string()),
-                    Unlock::Unit("assault_trooper".to_string()),
-                ],
-            },
-
-            // AGE 4: ELECTRONIC
-            Technology {
-                id: "steel_working".to_string(),
-                name: "Steel Working".to_string(),
-                description: "Master steel production for superior equipment".to_string(),
-                age: TechAge::Electronic,
-                category: TechCategory::Tools,
-                research_cost: ResearchCost {
-                    resources: vec![("iron_bar".to_string(), 100)],
-                    research_points: 1000,
-                    time: 600.0,
-                },
-                prerequisites: vec!["ironworking".to_string()],
-                unlocks: vec![
-                    Unlock::Recipe("smelt_steel_bar".to_string()),
-                    Unlock::Recipe("craft_steel_sword".to_string()),
-                    Unlock::Recipe("craft_steel_armor".to_string()),
-                ],
-            },
-            Technology {
-                id: "heavy_weapons".to_string(),
-                name: "Heavy Weapons".to_string(),
-                description: "Deploy artillery and siege mechs to break defenses".to_string(),
-                age: TechAge::Electronic,
-                category: TechCategory::Military,
-                research_cost: ResearchCost {
-                    resources: vec![("wood".to_string(), 100), ("iron_bar".to_string(), 80)],
-                    research_points: 900,
-                    time: 540.0,
-                },
-                prerequisites: vec!["defense_systems".to_string()],
-                unlocks: vec![
-                    Unlock::Building("heavy_weapons_facility".to_string()),
-                    Unlock::Unit("artillery".to_string()),
-                    Unlock::Unit("siege_mech".to_string()),
-                    Unlock::Unit("railgun_turret".to_string()),
-                ],
-            },
-            Technology {
-                id: "biochemistry".to_string(),
-                name: "Biochemistry".to_string(),
-                description: "Synthesize medical compounds and performance enhancers".to_string(),
-                age: TechAge::Electronic,
-                category: TechCategory::Science,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 800,
-                    time: 480.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Building("research_lab".to_string()),
-                    Unlock::Recipe("craft_medkit".to_string()),
-                    Unlock::Recipe("craft_energy_cell".to_string()),
-                    Unlock::Recipe("craft_stimpack".to_string()),
-                ],
-            },
-            Technology {
-                id: "trade".to_string(),
-                name: "Trade Networks".to_string(),
-                description: "Establish trade routes and commerce".to_string(),
-                age: TechAge::Electronic,
-                category: TechCategory::Economy,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 600,
-                    time: 360.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Building("market".to_string()),
-                    Unlock::Building("trade_post".to_string()),
-                    Unlock::Ability("trade_routes".to_string()),
-                ],
-            },
-
-            // SPECIAL TECHNOLOGIES
-            Technology {
-                id: "agriculture_advanced".to_string(),
-                name: "Advanced Agriculture".to_string(),
-                description: "Improve farming efficiency dramatically".to_string(),
-                age: TechAge::Electronic,
-                category: TechCategory::Economy,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 500,
-                    time: 300.0,
-                },
-                prerequisites: vec!["basic_farming".to_string()],
-                unlocks: vec![
-                    Unlock::Bonus("farm_yield_+50%".to_string()),
-                    Unlock::Recipe("crop_rotation".to_string()),
-                ],
-            },
-            Technology {
-                id: "medicine".to_string(),
-                name: "Medicine".to_string(),
-                description: "Advanced healing and surgery".to_string(),
-                age: TechAge::Electronic,
-                category: TechCategory::Science,
-                research_cost: ResearchCost {
-                    resources: vec![],
-                    research_points: 700,
-                    time: 420.0,
-                },
-                prerequisites: vec![],
-                unlocks: vec![
-                    Unlock::Building("hospital".to_string()),
-                    Unlock::Recipe("craft_medicine".to_string()),
-                    Unlock::Ability("surgery".to_string()),
-                ],
-            },
-        ]
+    // Every .ron file in the directory holds a list of technologies. Duplicate ids, missing
+    // prerequisites, prerequisite cycles and unknown buildings are rejected. Recipe unlocks are
+    // checked by the recipe registry, which loads after the tech tree.
+    pub fn load(directory: &Path) -> Result<Self, Vec<String>> {
+        let files = read_ron_files::<Vec<Technology>>(directory)?;
+        let mut tech_tree = TechnologyTree::default();
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut errors = Vec::new();
+        for (path, techs) in files {
+            for tech in techs {
+                if let Some(first) = sources.get(&tech.id) {
+                    errors.push(format!("{}: tech '{}': field `id` is already defined in {}", path.display(), tech.id, first.display()));
+                    continue;
+                }
+                sources.insert(tech.id.clone(), path.clone());
+                tech_tree.available_techs.push(tech);
+            }
+        }
+        for tech in &tech_tree.available_techs {
+            let path = sources[&tech.id].display();
+            for prereq in &tech.prerequisites {
+                if tech_tree.get(prereq).is_none() {
+                    errors.push(format!("{}: tech '{}': field `prerequisites` names unknown tech '{}'", path, tech.id, prereq));
+                }
+            }
+            for unlock in &tech.unlocks {
+                if let Unlock::Building(building_id) = unlock {
+                    if BuildingType::from_id(building_id).is_none() {
+                        errors.push(format!("{}: tech '{}': field `unlocks` names unknown building '{}'", path, tech.id, building_id));
+                    }
+                }
+            }
+        }
+        if let Some(cycle) = tech_tree.find_cycle() {
+            errors.push(format!("{}: prerequisites form a cycle: {}", sources[&cycle[0]].display(), cycle.join(" -> ")));
+        }
+        if errors.is_empty() { Ok(tech_tree) } else { Err(errors) }
+    }
+
+    pub fn get(&self, tech_id: &str) -> Option<&Technology> {
+        self.available_techs.iter().find(|t| t.id == tech_id)
+    }
+
+    // Returns the first cycle found, as a path of tech ids that ends where it started.
+    fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut finished: Vec<&str> = Vec::new();
+        let mut path: Vec<&str> = Vec::new();
+        for tech in &self.available_techs {
+            if let Some(cycle) = self.visit_prerequisites(&tech.id, &mut finished, &mut path) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    fn visit_prerequisites<'a>(&'a self, tech_id: &'a str, finished: &mut Vec<&'a str>, path: &mut Vec<&'a str>) -> Option<Vec<String>> {
+        if finished.contains(&tech_id) {
+            return None;
+        }
+        if let Some(start) = path.iter().position(|id| *id == tech_id) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|id| id.to_string()).collect();
+            cycle.push(tech_id.to_string());
+            return Some(cycle);
+        }
+        path.push(tech_id);
+        if let Some(tech) = self.get(tech_id) {
+            for prereq in &tech.prerequisites {
+                if let Some(cycle) = self.visit_prerequisites(prereq, finished, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        finished.push(tech_id);
+        None
+    }
+
+    // Graphviz export: one cluster per age, arrows from prerequisite to tech.
+    pub fn to_dot(&self) -> String {
+        let quote = |text: &str| format!("\"{}\"", text.replace('"', "\\\""));
+        let mut ages: Vec<&TechAge> = Vec::new();
+        for tech in &self.available_techs {
+            if !ages.contains(&&tech.age) {
+                ages.push(&tech.age);
+            }
+        }
+        let mut dot = String::from("digraph tech_tree {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (i, age) in ages.iter().enumerate() {
+            dot.push_str(&format!("    subgraph cluster_{} {{\n        label={};\n", i, quote(&format!("{:?}", age))));
+            for tech in self.available_techs.iter().filter(|tech| &&tech.age == age) {
+                let label = format!("{}\\n{:?}", tech.name, tech.category);
+                dot.push_str(&format!("        {} [label={}];\n", quote(&tech.id), quote(&label)));
+            }
+            dot.push_str("    }\n");
+        }
+        for tech in &self.available_techs {
+            for prereq in &tech.prerequisites {
+                dot.push_str(&format!("    {} -> {};\n", quote(prereq), quote(&tech.id)));
+            }
+        }
+        dot.push_str("}\n");
+        dot
     }
 
     pub fn can_research(&self, tech_id: &str) -> bool {
@@ -450,7 +179,7 @@ impl TechnologyTree {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Technology {
     pub id: String,
     pub name: String,
@@ -462,7 +191,7 @@ pub struct Technology {
     pub unlocks: Vec<Unlock>,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TechAge {
     StoneAge,
     BronzeAge,
@@ -473,7 +202,7 @@ pub enum TechAge {
     Modern,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TechCategory {
     Military,
     Economy,
@@ -483,14 +212,14 @@ pub enum TechCategory {
     Survival,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ResearchCost {
     pub resources: Vec<(String, u32)>,
     pub research_points: i32,
     pub time: f32, // Seconds
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Unlock {
     Recipe(String),
     Building(String),
@@ -500,6 +229,7 @@ pub enum Unlock {
     Bonus(String),
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ResearchProgress {
     pub tech_id: String,
     pub time_remaining: f32,
@@ -519,7 +249,7 @@ pub struct Civilization {
     pub unique_techs: Vec<String>,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum CivilizationType {
     Humans,
     Cyborgs,
@@ -593,16 +323,18 @@ pub enum CivBonus {
 // TECH PLUGIN
 // ============================================================================
 
+pub const TECH_DIRECTORY: &str = "assets/techs";
+
 pub struct TechPlugin;
 
 impl Plugin for TechPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(TechnologyTree::default())
+        let tech_tree = TechnologyTree::load(&asset_path(TECH_DIRECTORY))
+            .unwrap_or_else(|errors| exit_with_data_errors("techs", &errors));
+        app.insert_resource(tech_tree)
             .add_systems(
-                Update,
-                research_progress_system.run_if(bevy::time::common_conditions::on_timer(
-                    bevy::utils::Duration::from_secs(1),
-                )),
+                SimTick,
+                research_progress_system.run_if(every_seconds(1.0)),
             );
     }
 }
@@ -618,3 +350,95 @@ fn research_progress_system(mut tech_tree: ResMut<TechnologyTree>) {
         }
     }
 }
+
+// `--export-tech-dot PATH` writes the tree as Graphviz DOT, e.g. `dot -Tpng tech.dot -o tech.png`.
+pub fn export_tech_dot_from_args(args: &[String]) -> bool {
+    let Some(path) = args.iter()
+        .position(|arg| arg == "--export-tech-dot")
+        .and_then(|i| args.get(i + 1)) else { return false; };
+    let tech_tree = TechnologyTree::load(&asset_path(TECH_DIRECTORY))
+        .unwrap_or_else(|errors| exit_with_data_errors("techs", &errors));
+    if let Err(e) = std::fs::write(path, tech_tree.to_dot()) {
+        eprintln!("Could not write {}: {}", path, e);
+        std::process::exit(1);
+    }
+    println!("Wrote tech tree to {}", path);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tech(id: &str, prerequisites: &[&str]) -> String {
+        tech_unlocking(id, prerequisites, "")
+    }
+
+    fn tech_unlocking(id: &str, prerequisites: &[&str], unlocks: &str) -> String {
+        format!(
+            "(id: \"{}\", name: \"{}\", description: \"\", age: StoneAge, category: Tools, \
+             research_cost: (resources: [], research_points: 10, time: 1.0), prerequisites: {:?}, unlocks: [{}])",
+            id, id, prerequisites, unlocks,
+        )
+    }
+
+    fn techs(list: &[String]) -> String {
+        format!("[{}]", list.join(",\n"))
+    }
+
+    #[test]
+    fn shipped_techs_load() {
+        let tech_tree = TechnologyTree::load(&asset_path(TECH_DIRECTORY)).unwrap();
+        assert!(!tech_tree.available_techs.is_empty());
+    }
+
+    #[test]
+    fn find_cycle_returns_the_loop() {
+        let directory = scratch_directory("tech-cycle", &[("techs.ron", &techs(&[
+            tech("fire", &[]),
+            tech("a", &["fire", "c"]),
+            tech("b", &["a"]),
+            tech("c", &["b"]),
+        ]))]);
+        let errors = TechnologyTree::load(&directory).err().expect("tech tree should not load");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("a -> c -> b -> a"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn find_cycle_accepts_shared_prerequisites() {
+        let tech_tree = TechnologyTree {
+            available_techs: [tech("fire", &[]), tech("a", &["fire"]), tech("b", &["fire", "a"])]
+                .iter().map(|text| ron::from_str(text).unwrap()).collect(),
+            ..default()
+        };
+        assert_eq!(tech_tree.find_cycle(), None);
+        let looped = TechnologyTree {
+            available_techs: vec![ron::from_str(&tech("a", &["a"])).unwrap()],
+            ..default()
+        };
+        assert_eq!(looped.find_cycle(), Some(vec!["a".to_string(), "a".to_string()]));
+    }
+
+    #[test]
+    fn duplicate_and_unknown_techs_are_rejected() {
+        let directory = scratch_directory("tech-references", &[
+            ("a.ron", &techs(&[tech("fire", &[]), tech("wheel", &["fire", "roads"])])),
+            ("b.ron", &techs(&[tech("fire", &[])])),
+        ]);
+        let errors = TechnologyTree::load(&directory).err().expect("tech tree should not load");
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.contains("b.ron: tech 'fire': field `id` is already defined in")));
+        assert!(errors.iter().any(|e| e.contains("names unknown tech 'roads'")));
+    }
+
+    #[test]
+    fn unknown_buildings_are_rejected() {
+        let directory = scratch_directory("tech-buildings", &[("techs.ron", &techs(&[
+            tech_unlocking("masonry", &[], "Building(\"wall\"), Building(\"watchtower\")"),
+        ]))]);
+        let errors = TechnologyTree::load(&directory).err().expect("tech tree should not load");
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("tech 'masonry': field `unlocks` names unknown building 'watchtower'"), "{}", errors[0]);
+    }
+}
@@ -53,15 +53,6 @@ pub struct ObjectText;
 #[derive(Component)]
 pub struct FpsText;
 
-#[derive(Component)]
-struct AnimateTranslation;
-
-#[derive(Component)]
-struct AnimateRotation;
-
-#[derive(Component)]
-struct AnimateScale;
-
 pub fn text_test(
     
 ) {
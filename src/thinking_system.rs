@@ -7,15 +7,13 @@ impl Plugin for ThinkingPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(
-            Update,
+            SimTick,
             (
             thinking_system
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(0.5)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(0.5))
             ,
             remotivate_system
-            .run_if(bevy::time::common_conditions::on_timer(bevy::utils::Duration::from_secs_f32(2.5)))
-            .run_if(in_state(GameState::InGame))
+            .run_if(every_seconds(2.5))
             )
         )
         ;
@@ -34,7 +32,7 @@ pub fn thinking_system(
         // CHOOSE A MOTIVATION
         // 0. Is having a crisis.
         // 1. Has been given an order AND there's a danger or emergency.
-        // Needs order: Food, Health, Sleep, Entertainment.
+        // Needs order: Food, Water, Health, Sleep, Entertainment.
         // 2. Has been given an order AND there's a matching need.
         // Is sick or injured and is trying to go to the hospital.
         // 3. Has an urgently unmet need.
@@ -92,6 +90,22 @@ pub fn thinking_system(
                     }
                 }
             }
+            // WATER
+            if brain.motivation.is_none() {
+                if let Some(n) = &physical_body.needs_water {
+                    if n.current < n.low {
+                        if let Some(order) = &brain.order {
+                            if *order == Order::Drink {
+                                brain.motivation = Some(Motivation::Order);
+                            } else {
+                                brain.motivation = Some(Motivation::Thirst);
+                            }
+                        } else {
+                            brain.motivation = Some(Motivation::Thirst);
+                        }
+                    }
+                }
+            }
             // HOSPITAL
             if brain.motivation.is_none() && physical_body.injured {
                 if let Some(order) = &brain.order {
@@ -193,6 +207,8 @@ pub fn thinking_system(
                 }
             } else if m == Motivation::Hunger {
                 brain.task = Some(Task::Eat);
+            } else if m == Motivation::Thirst {
+                brain.task = Some(Task::Drink);
             } else if m == Motivation::Injured {
                 brain.task = Some(Task::Hospital);
             } else if m == Motivation::Tired {
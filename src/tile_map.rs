@@ -0,0 +1,376 @@
+use std::collections::HashSet;
+use crate::prelude::*;
+
+// ============================================================================
+// TILE MAP - Chunked tile storage and streaming of tile sprites near the camera
+// ============================================================================
+
+pub const CHUNK_SIZE: i32 = 16;
+pub const MAX_MAP_SIZE: i32 = 512;
+
+// Chunks this far past the edge of the screen stay spawned, so scrolling doesn't show gaps.
+const CHUNK_MARGIN: i32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ChunkPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+// Underground levels are solid rock until caves, dens or digging open them up.
+pub const UNDERGROUND_ROCK: TileType = TileType::WallStone;
+pub const UNDERGROUND_FLOOR: TileType = TileType::Gravel;
+// What's left where rock was mined out.
+pub const MINED_FLOOR: TileType = TileType::Gravel;
+
+// The ground of the whole map, one grid per level. Chunks are stored row by row, and so are
+// the tiles inside each chunk; tiles past the map edge in the last row or column of chunks
+// are never read.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct TileMap {
+    width: i32,
+    length: i32,
+    // levels[0] is the surface (z = 0), levels[1] is z = -1, and so on down.
+    levels: Vec<Vec<Vec<TileType>>>,
+}
+
+impl TileMap {
+    // Each level's `tiles` are row-major: index = y * width + x. The surface comes first.
+    pub fn from_levels(width: i32, length: i32, levels: Vec<Vec<TileType>>) -> Self {
+        let chunks_wide = (width + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let chunks_long = (length + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let levels = levels.into_iter().map(|tiles| {
+            let mut chunks = vec![vec![TileType::WallGame; (CHUNK_SIZE * CHUNK_SIZE) as usize]; (chunks_wide * chunks_long) as usize];
+            for (index, tile_type) in tiles.into_iter().enumerate() {
+                let (x, y) = (index as i32 % width, index as i32 / width);
+                chunks[((y / CHUNK_SIZE) * chunks_wide + x / CHUNK_SIZE) as usize][Self::tile_index(x, y)] = tile_type;
+            }
+            chunks
+        }).collect();
+        TileMap { width, length, levels }
+    }
+
+    pub fn width(&self) -> i32 { self.width }
+    pub fn length(&self) -> i32 { self.length }
+    pub fn lowest_level(&self) -> i32 { 1 - self.levels.len() as i32 }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        position.z <= 0 && position.z >= self.lowest_level()
+            && position.x >= 0 && position.x < self.width && position.y >= 0 && position.y < self.length
+    }
+
+    pub fn get(&self, position: &Position) -> Option<&TileType> {
+        if !self.contains(position) { return None; }
+        self.levels[-position.z as usize][self.chunk_index(position.x, position.y)].get(Self::tile_index(position.x, position.y))
+    }
+
+    // Returns false for positions off the map.
+    pub fn set(&mut self, position: &Position, tile_type: TileType) -> bool {
+        if !self.contains(position) { return false; }
+        let chunk = self.chunk_index(position.x, position.y);
+        self.levels[-position.z as usize][chunk][Self::tile_index(position.x, position.y)] = tile_type;
+        true
+    }
+
+    pub fn is_wall(&self, position: &Position) -> bool {
+        self.get(position).is_none_or(|tile_type| tile_type.is_wall())
+    }
+
+    pub fn is_water(&self, position: &Position) -> bool {
+        self.get(position) == Some(&TileType::Water)
+    }
+
+    pub fn movement_cost(&self, position: &Position) -> i32 {
+        self.get(position).map_or(BASE_MOVEMENT_COST, |tile_type| tile_type.movement_cost())
+    }
+
+    // Where taking the stairs on this tile leads, if there are any.
+    pub fn stairs_exit(&self, position: &Position) -> Option<Position> {
+        let exit = match self.get(position)? {
+            TileType::StairsDown => Position { z: position.z - 1, ..*position },
+            TileType::StairsUp => Position { z: position.z + 1, ..*position },
+            _ => return None,
+        };
+        self.contains(&exit).then_some(exit)
+    }
+
+    // The tiles that change when `position` is mined, for `SetTileEvent`s. Mining a wall leaves
+    // rubble. Mining rocky ground digs stairs down and clears the rock around the foot of the
+    // stairs, or leaves rubble if there's no level below. Empty if there's nothing to mine.
+    pub fn mining_changes(&self, position: &Position) -> Vec<(Position, TileType)> {
+        let Some(tile_type) = self.get(position) else { return vec![]; };
+        if !tile_type.is_mineable() { return vec![]; }
+        let below = Position { z: position.z - 1, ..*position };
+        if tile_type.is_wall() || !self.contains(&below) {
+            return vec![(*position, MINED_FLOOR)];
+        }
+        let mut changes = vec![(*position, TileType::StairsDown), (below, TileType::StairsUp)];
+        for (dx, dy) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy))) {
+            let around = Position { x: below.x + dx, y: below.y + dy, z: below.z };
+            if around != below && self.get(&around) == Some(&UNDERGROUND_ROCK) {
+                changes.push((around, UNDERGROUND_FLOOR));
+            }
+        }
+        changes
+    }
+
+    // Where a worker coming from `from` stands to work on `target`: on it if it can be walked on,
+    // otherwise the closest open tile beside it.
+    pub fn work_spot(&self, from: &Position, target: &Position) -> Option<Position> {
+        if !self.is_wall(target) { return Some(*target); }
+        [(1, 0), (-1, 0), (0, 1), (0, -1)].iter()
+            .map(|(dx, dy)| Position { x: target.x + dx, y: target.y + dy, z: target.z })
+            .filter(|spot| !self.is_wall(spot))
+            .min_by_key(|spot| from.distance(spot))
+    }
+
+    pub fn chunks_wide(&self) -> i32 { (self.width + CHUNK_SIZE - 1) / CHUNK_SIZE }
+    pub fn chunks_long(&self) -> i32 { (self.length + CHUNK_SIZE - 1) / CHUNK_SIZE }
+
+    // Every tile of one chunk of level `z` that lies on the map.
+    pub fn chunk_tiles(&self, chunk: ChunkPosition, z: i32) -> impl Iterator<Item = (Position, &TileType)> {
+        let (min_x, min_y) = (chunk.x * CHUNK_SIZE, chunk.y * CHUNK_SIZE);
+        let (max_x, max_y) = ((min_x + CHUNK_SIZE).min(self.width), (min_y + CHUNK_SIZE).min(self.length));
+        (min_y..max_y)
+            .flat_map(move |y| (min_x..max_x).map(move |x| Position { x, y, z }))
+            .filter_map(move |position| self.get(&position).map(|tile_type| (position, tile_type)))
+    }
+
+    fn chunk_index(&self, x: i32, y: i32) -> usize {
+        ((y / CHUNK_SIZE) * self.chunks_wide() + x / CHUNK_SIZE) as usize
+    }
+
+    fn tile_index(x: i32, y: i32) -> usize {
+        ((y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE) as usize
+    }
+}
+
+// ============================================================================
+// MINING DESIGNATIONS
+// ============================================================================
+
+// Tiles the player has marked for mining, with the work already put into each. Kept by
+// position rather than on tile entities, which only exist while their chunk is near the camera.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct MiningDesignations {
+    sites: HashMap<Position, MiningProgress>,
+}
+
+impl MiningDesignations {
+    pub fn designate(&mut self, position: Position) {
+        self.sites.entry(position).or_default();
+    }
+
+    pub fn remove(&mut self, position: &Position) {
+        self.sites.remove(position);
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.sites.contains_key(position)
+    }
+
+    pub fn progress_mut(&mut self, position: &Position) -> Option<&mut MiningProgress> {
+        self.sites.get_mut(position)
+    }
+
+    pub fn sites(&self) -> impl Iterator<Item = &Position> {
+        self.sites.keys()
+    }
+}
+
+// ============================================================================
+// TILE CHANGES
+// ============================================================================
+
+// Asks for one tile of the map to become `tile_type`. Everything that changes the ground after
+// world generation (mining, construction, explosions, terraforming) goes through this, so the
+// tile map, tile sprites and the caches built on the map stay in step.
+#[derive(Event, Clone)]
+pub struct SetTileEvent {
+    pub position: Position,
+    pub tile_type: TileType,
+}
+
+// Sent once a tile has actually changed. Caches built from the tile map listen for this
+// rather than rebuilding from scratch.
+#[derive(Event, Clone)]
+pub struct TileChangedEvent {
+    pub position: Position,
+    pub old: TileType,
+    pub new: TileType,
+}
+
+pub struct TileMapPlugin;
+
+impl Plugin for TileMapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<SetTileEvent>()
+        .add_event::<TileChangedEvent>()
+        .init_resource::<MiningDesignations>()
+        .add_systems(SimTick, apply_tile_changes)
+        ;
+    }
+}
+
+pub fn apply_tile_changes(
+    mut requests: EventReader<SetTileEvent>,
+    mut changes: EventWriter<TileChangedEvent>,
+    mut tile_map: ResMut<TileMap>,
+    mut designations: ResMut<MiningDesignations>,
+    mut tiles: Query<(&Position, &mut TileType, Option<&mut TextureAtlasSprite>), With<MapTile>>,
+) {
+    let mut changed = HashMap::new();
+    for request in requests.iter() {
+        let Some(old) = tile_map.get(&request.position).cloned() else { continue; };
+        if old == request.tile_type { continue; }
+        tile_map.set(&request.position, request.tile_type.clone());
+        if !request.tile_type.is_mineable() {
+            designations.remove(&request.position);
+        }
+        changed.insert(request.position, request.tile_type.clone());
+        changes.send(TileChangedEvent { position: request.position, old, new: request.tile_type.clone() });
+    }
+    if changed.is_empty() { return; }
+    // Tiles that aren't spawned pick up the new type when their chunk streams in.
+    for (position, mut tile_type, sprite) in tiles.iter_mut() {
+        let Some(new_type) = changed.get(position) else { continue; };
+        *tile_type = new_type.clone();
+        if let Some(mut sprite) = sprite {
+            sprite.index = new_type.sprite_index();
+        }
+    }
+}
+
+// ============================================================================
+// CHUNK STREAMING
+// ============================================================================
+
+// The map level the camera shows. The fog of war hides everything on other levels.
+#[derive(Resource, Default)]
+pub struct ViewLevel {
+    pub z: i32,
+}
+
+// Only chunks of the viewed level on or near the screen have tile entities. They are only for
+// show: anything the simulation needs to know about a tile lives in the TileMap, in a
+// resource keyed by position such as MiningDesignations, or on an entity of its own like a Zone.
+#[derive(Resource, Default)]
+pub struct TileChunks {
+    level: i32,
+    loaded: HashMap<ChunkPosition, Vec<Entity>>,
+}
+
+pub struct TileChunkPlugin;
+
+impl Plugin for TileChunkPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<TileChunks>()
+        .init_resource::<ViewLevel>()
+        .add_systems(Update, (stream_tile_chunks, mark_mining_designations).chain())
+        ;
+    }
+}
+
+pub fn stream_tile_chunks(
+    mut commands: Commands,
+    camera: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+    tile_map: Option<Res<TileMap>>,
+    view_level: Res<ViewLevel>,
+    mut tile_chunks: ResMut<TileChunks>,
+    sprite_sheet: Option<Res<SpriteSheet>>,
+) {
+    let Some(tile_map) = tile_map else { return; };
+    let Ok((transform, projection)) = camera.get_single() else { return; };
+    let chunk_pixels = CHUNK_SIZE as f32 * TILE_SIZE;
+    let to_chunk = |pixels: f32| (pixels / chunk_pixels).floor() as i32;
+    let min_x = to_chunk(transform.translation.x + projection.area.min.x) - CHUNK_MARGIN;
+    let max_x = to_chunk(transform.translation.x + projection.area.max.x) + CHUNK_MARGIN;
+    let min_y = to_chunk(transform.translation.y + projection.area.min.y) - CHUNK_MARGIN;
+    let max_y = to_chunk(transform.translation.y + projection.area.max.y) + CHUNK_MARGIN;
+    let wanted: HashSet<ChunkPosition> = (min_y.max(0)..=max_y.min(tile_map.chunks_long() - 1))
+        .flat_map(|y| (min_x.max(0)..=max_x.min(tile_map.chunks_wide() - 1)).map(move |x| ChunkPosition { x, y }))
+        .collect();
+
+    let changed_level = tile_chunks.level != view_level.z;
+    tile_chunks.level = view_level.z;
+    let leaving: Vec<ChunkPosition> = tile_chunks.loaded.keys().filter(|chunk| changed_level || !wanted.contains(chunk)).copied().collect();
+    for chunk in leaving {
+        for entity in tile_chunks.loaded.remove(&chunk).unwrap_or_default() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for chunk in wanted {
+        if tile_chunks.loaded.contains_key(&chunk) { continue; }
+        let entities = tile_map.chunk_tiles(chunk, view_level.z)
+            .map(|(position, tile_type)| crate::spawn_tile(&mut commands, position, tile_type.clone(), sprite_sheet.as_deref()))
+            .collect();
+        tile_chunks.loaded.insert(chunk, entities);
+    }
+}
+
+// Puts an X on loaded tiles marked for mining, including ones that just streamed in, and takes
+// it off those that aren't any more.
+pub fn mark_mining_designations(
+    mut commands: Commands,
+    designations: Res<MiningDesignations>,
+    tiles: Query<(Entity, &Position, Option<&Children>), With<MapTile>>,
+    new_tiles: Query<(), Added<MapTile>>,
+    workmarkers: Query<(), With<WorkMarker>>,
+    font: Option<Res<MyFont>>,
+) {
+    if !designations.is_changed() && new_tiles.is_empty() { return; }
+    let Some(font) = font else { return; };
+    for (entity, position, children) in tiles.iter() {
+        let markers: Vec<Entity> = children.into_iter().flatten().copied().filter(|child| workmarkers.contains(*child)).collect();
+        match (designations.contains(position), markers.is_empty()) {
+            (true, true) => crate::create_marker(&mut commands, &entity, font.0.clone()),
+            (false, false) => for marker in markers { commands.entity(marker).despawn(); },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_tile_events_keep_the_map_sprites_and_grid_in_step() {
+        let mut world = World::new();
+        let tile_map = TileMap::from_levels(5, 5, vec![vec![TileType::Grass; 25]]);
+        world.insert_resource(crate::PassabilityGrid::from_tile_map(&tile_map));
+        world.insert_resource(tile_map);
+        world.insert_resource(crate::small_scenario());
+        world.init_resource::<Weather>();
+        world.init_resource::<crate::Rooms>();
+        let mut designations = MiningDesignations::default();
+        designations.designate(Position { x: 3, y: 3, z: 0 });
+        world.insert_resource(designations);
+        world.init_resource::<Events<SetTileEvent>>();
+        world.init_resource::<Events<TileChangedEvent>>();
+        let wall = Position { x: 2, y: 2, z: 0 };
+        let tile = world.spawn((MapTile, wall, TileType::Grass, TextureAtlasSprite::new(TileType::Grass.sprite_index()))).id();
+        let mut schedule = Schedule::new();
+        schedule.add_systems((apply_tile_changes, crate::update_passability).chain());
+        schedule.run(&mut world);
+
+        let walker = MovementProfile::default();
+        assert!(world.resource::<crate::PassabilityGrid>().can_enter(&wall, &walker));
+        world.send_event(SetTileEvent { position: wall, tile_type: TileType::WallStone });
+        // Tiles without an entity, such as ones in chunks that aren't loaded, change too.
+        world.send_event(SetTileEvent { position: Position { x: 3, y: 3, z: 0 }, tile_type: TileType::Water });
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<TileMap>().get(&wall), Some(&TileType::WallStone));
+        assert_eq!(world.get::<TileType>(tile), Some(&TileType::WallStone));
+        assert_eq!(world.get::<TextureAtlasSprite>(tile).unwrap().index, TileType::WallStone.sprite_index());
+        let grid = world.resource::<crate::PassabilityGrid>();
+        assert!(!grid.can_enter(&wall, &walker));
+        assert!(!grid.can_enter(&Position { x: 3, y: 3, z: 0 }, &MovementProfile { swims: false, ..default() }));
+        // Water can't be mined, so its designation is dropped.
+        assert!(!world.resource::<MiningDesignations>().contains(&Position { x: 3, y: 3, z: 0 }));
+        assert_eq!(world.resource::<Events<TileChangedEvent>>().len(), 2);
+    }
+}
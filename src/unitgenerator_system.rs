@@ -1,33 +1,40 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use crate::prelude::*;
 
+// ============================================================================
+// UNIT TEMPLATES - Creature and colonist definitions loaded from assets/units
+// ============================================================================
+
+pub const UNIT_DIRECTORY: &str = "assets/units";
+
 // Make plugin
 pub struct UnitGeneratorPlugin;
 
 impl Plugin for UnitGeneratorPlugin {
-    fn build(&self, _app: &mut App) {
-        
+    fn build(&self, app: &mut App) {
+        let registry = UnitRegistry::load(&asset_path(UNIT_DIRECTORY), app.world.resource::<ItemRegistry>())
+            .unwrap_or_else(|errors| exit_with_data_errors("unit templates", &errors));
+        app.insert_resource(registry);
     }
 }
 
 pub fn spawn_unit_from_template(
     commands: &mut Commands,
     position: Position,
-    sprite_sheet: &Res<SpriteSheet>,
+    sprite_sheet: Option<&SpriteSheet>,
     template: &UnitTemplate,
+    rng: &mut SimRng,
 ) -> Entity {
-    let sprite =  TextureAtlasSprite::new(template.actor_type.sprite_index());
-    let entity = commands
-        .spawn(SpriteSheetBundle {
-            sprite,
-            texture_atlas: sprite_sheet.0.clone(),
-            ..default()
-        })
+    let entity = crate::spawn_sprite(commands, sprite_sheet, template.actor_type.sprite_index())
         .insert(position)
         .insert(position.to_transform_layer(1.0))
+        .insert(template.actor_type)
         .insert( PhysicalBody {
-            needs_food: template.food_need.map(Into::into),
-            needs_entertainment: template.entertainment_need.map(Into::into),
-            needs_sleep: template.sleep_need.map(Into::into),
+            needs_food: template.food_need.map(|need| need.roll(rng)),
+            needs_entertainment: template.entertainment_need.map(|need| need.roll(rng)),
+            needs_sleep: template.sleep_need.map(|need| need.roll(rng)),
+            needs_water: template.water_need.map(|need| need.roll(rng)),
             index: 0,
             crisis: None,
             danger: None,
@@ -40,27 +47,51 @@ pub fn spawn_unit_from_template(
             personality: template.personality.clone(),
             ..default()
         } )
+        .insert(template.movement)
         .id();
-    for builder in &template.component_builders {
-        builder(commands, entity);
-    };
+    let mut entity_commands = commands.entity(entity);
+    if let Some(name) = &template.name {
+        entity_commands.insert(HasName { name: name.clone() });
+    }
+    if let Some(inventory) = &template.inventory {
+        entity_commands.insert(inventory.clone());
+    }
+    if let Some(equipment) = &template.equipment {
+        entity_commands.insert(equipment.clone());
+    }
+    for marker in &template.markers {
+        match marker {
+            UnitMarker::Attackable => { entity_commands.insert(Attackable); }
+            UnitMarker::GiveMeAName => { entity_commands.insert(GiveMeAName); }
+            UnitMarker::Huntable => { entity_commands.insert(Huntable); }
+            UnitMarker::MoveRandom => { entity_commands.insert(MoveRandom); }
+            UnitMarker::MoveTowardsNearestAttackable => { entity_commands.insert(MoveTowardsNearestAttackable); }
+            UnitMarker::SetNest => { entity_commands.insert(SetNest); }
+        }
+    }
     entity
 }
 
-type ComponentBuilder = fn(&mut Commands, Entity);
-
+// One rolled unit, ready to spawn. Scenario overrides are applied to this before spawning.
+#[derive(Clone)]
 pub struct UnitTemplate {
     pub actor_type: ActorType,
+    pub name: Option<String>,
     pub food_need: Option<NeedExample>,
     pub entertainment_need: Option<NeedExample>,
     pub sleep_need: Option<NeedExample>,
+    pub water_need: Option<NeedExample>,
     pub personality: Vec<PersonalityTrait>,
     pub skillset: Skillset,
     pub attributes: Attributeset,
     pub afflictions: Vec<Affliction>,
-    pub component_builders: Vec<ComponentBuilder>,
+    pub inventory: Option<Inventory>,
+    pub equipment: Option<Equipment>,
+    pub movement: MovementProfile,
+    pub markers: Vec<UnitMarker>,
 }
-#[derive(Copy, Clone)]
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct NeedExample {
     pub current: f32,
     pub max: f32,
@@ -70,9 +101,9 @@ pub struct NeedExample {
     pub high: f32,
     pub variance: f32,
 }
-impl From<NeedExample> for Need {
-    fn from(need_example: NeedExample) -> Self {
-        let mut rng = rand::thread_rng();
+impl NeedExample {
+    pub fn roll(&self, rng: &mut impl Rng) -> Need {
+        let need_example = self;
         let variance = need_example.variance;
 
         // Calculate random adjustments for each field
@@ -84,7 +115,7 @@ impl From<NeedExample> for Need {
         let high_adjustment = rng.gen_range(-variance..variance);
 
         // Create the Need struct with the adjusted values
-        Self {
+        Need {
             current: need_example.current + current_adjustment,
             max: need_example.max + max_adjustment,
             rate: need_example.rate + rate_adjustment,
@@ -95,254 +126,358 @@ impl From<NeedExample> for Need {
     }
 }
 
-impl UnitTemplate {
-    pub fn human() -> Self {
-        let actor_type = ActorType::Man;
-        let random_afflictions = Self::random_afflictions_humanoid();
-        Self {
-            actor_type,
-            food_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            entertainment_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            sleep_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            personality: vec![PersonalityTrait::Human],
-            skillset: Self::random_skillset_humanoid(),
-            attributes: Self::random_attributeset_humanoid(),
-            afflictions: random_afflictions.to_vec(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(GiveMeAName); },
-            ],
+// Components a template can attach that carry no data.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum UnitMarker {
+    Attackable,
+    GiveMeAName,
+    Huntable,
+    MoveRandom,
+    MoveTowardsNearestAttackable,
+    SetNest,
+}
+
+// Ranges are (min, max), both inclusive.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum SkillDistribution {
+    // Every skill starts at zero.
+    #[default]
+    Untrained,
+    // Each range is rolled once and the results are dealt out to the skills in a random order,
+    // so every unit is good at something different. Skills left over get 100 experience.
+    Shuffled {
+        experience: Vec<(i32, i32)>,
+        exp_lost: Vec<(i32, i32)>,
+    },
+    // Experience ranges for named skills; the rest start at zero.
+    PerSkill(BTreeMap<String, (i32, i32)>),
+}
+
+// Draws `rolls` afflictions from each pool, then keeps a random `count` of them.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct AfflictionRoll {
+    pub pools: Vec<AfflictionPool>,
+    pub count: (usize, usize),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AfflictionPool {
+    pub types: Vec<AfflictionType>,
+    pub locations: Vec<AfflictionLocation>,
+    pub rolls: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnitDefinition {
+    pub id: String,
+    pub actor_type: ActorType,
+    // A name is picked from this list; leave it empty and add GiveMeAName for generated names.
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub food_need: Option<NeedExample>,
+    #[serde(default)]
+    pub entertainment_need: Option<NeedExample>,
+    #[serde(default)]
+    pub sleep_need: Option<NeedExample>,
+    #[serde(default)]
+    pub water_need: Option<NeedExample>,
+    #[serde(default)]
+    pub personality: Vec<PersonalityTrait>,
+    #[serde(default)]
+    pub skills: SkillDistribution,
+    // Flat experience added after the distribution is rolled.
+    #[serde(default)]
+    pub skill_bonuses: BTreeMap<String, i32>,
+    // Attributes not listed keep their defaults.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, (i32, i32)>,
+    #[serde(default)]
+    pub afflictions: AfflictionRoll,
+    #[serde(default)]
+    pub inventory: Vec<(String, u32)>,
+    #[serde(default)]
+    pub equipment: Vec<String>,
+    // Fields left out keep the defaults: walks and swims, ignores zones and can't get past gates.
+    #[serde(default)]
+    pub movement: MovementProfile,
+    #[serde(default)]
+    pub markers: Vec<UnitMarker>,
+}
+
+// Starting gear is built once at load time and cloned into every unit.
+struct UnitKit {
+    inventory: Option<Inventory>,
+    equipment: Option<Equipment>,
+}
+
+#[derive(Resource, Default)]
+pub struct UnitRegistry {
+    units: HashMap<String, (UnitDefinition, UnitKit)>,
+}
+
+impl UnitRegistry {
+    // Every .ron file in the directory holds a list of unit definitions. Starting items
+    // are checked against the item registry.
+    pub fn load(directory: &Path, items: &ItemRegistry) -> Result<Self, Vec<String>> {
+        let files = read_ron_files::<Vec<UnitDefinition>>(directory)?;
+        let mut registry = UnitRegistry::default();
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut errors = Vec::new();
+        for (path, definitions) in files {
+            for definition in definitions {
+                let kit = match definition.validate(items) {
+                    Ok(kit) => kit,
+                    Err(unit_errors) => {
+                        for e in unit_errors {
+                            errors.push(format!("{}: unit '{}': {}", path.display(), definition.id, e));
+                        }
+                        continue;
+                    }
+                };
+                if let Some(first) = sources.get(&definition.id) {
+                    errors.push(format!("{}: unit '{}': field `id` is already defined in {}", path.display(), definition.id, first.display()));
+                    continue;
+                }
+                sources.insert(definition.id.clone(), path.clone());
+                registry.units.insert(definition.id.clone(), (definition, kit));
+            }
         }
+        if errors.is_empty() { Ok(registry) } else { Err(errors) }
     }
-    pub fn augmented() -> Self {
-        let actor_type = ActorType::Augmented;
-        let random_afflictions = Self::random_afflictions_humanoid();
-        Self {
-            actor_type,
-            food_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            entertainment_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            sleep_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            personality: vec![PersonalityTrait::Human],
-            skillset: Self::random_skillset_humanoid(),
-            attributes: Self::random_attributeset_humanoid(),
-            afflictions: random_afflictions.to_vec(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(GiveMeAName); },
-            ],
-        }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.units.contains_key(id)
     }
-    pub fn miner() -> Self {
-        let actor_type = ActorType::Miner;
-        let random_afflictions = Self::random_afflictions_humanoid();
-        Self {
-            actor_type,
-            food_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            entertainment_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            sleep_need: Some(NeedExample { current: 90.0, max: 100.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 5.0 }),
-            personality: vec![PersonalityTrait::Human],
-            skillset: Self::random_skillset_humanoid(),
-            attributes: Self::random_attributeset_humanoid(),
-            afflictions: random_afflictions.to_vec(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(GiveMeAName); },
-            ],
-        }
+
+    // Rolls a fresh unit from the template with the given id.
+    pub fn roll(&self, id: &str, rng: &mut impl Rng) -> Option<UnitTemplate> {
+        let (definition, kit) = self.units.get(id)?;
+        Some(UnitTemplate {
+            actor_type: definition.actor_type,
+            name: definition.names.choose(rng).cloned(),
+            food_need: definition.food_need,
+            entertainment_need: definition.entertainment_need,
+            sleep_need: definition.sleep_need,
+            water_need: definition.water_need,
+            personality: definition.personality.clone(),
+            skillset: definition.roll_skillset(rng),
+            attributes: definition.roll_attributes(rng),
+            afflictions: definition.afflictions.roll(rng),
+            inventory: kit.inventory.clone(),
+            equipment: kit.equipment.clone(),
+            movement: definition.movement,
+            markers: definition.markers.clone(),
+        })
     }
-    pub fn rat() -> Self {
-        let actor_type = ActorType::Rat;
-        let random_afflictions = vec![];//Self::random_afflictions_animal();
-        Self {
-            actor_type,
-            food_need: None,
-            entertainment_need: None,
-            sleep_need: None,
-            personality: vec![PersonalityTrait::Creature, PersonalityTrait::Territorial],
-            afflictions: random_afflictions.to_vec(),
-            skillset: Skillset::default(),
-            attributes: Attributeset::default(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(HasName { name: "Rat".to_string() }); },
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(SetNest); }
-            ],
+}
+
+impl UnitDefinition {
+    fn validate(&self, items: &ItemRegistry) -> Result<UnitKit, Vec<String>> {
+        let mut errors = Vec::new();
+        if self.id.is_empty() {
+            errors.push("field `id` is empty".to_string());
+        }
+        for (field, need) in [("food_need", &self.food_need), ("entertainment_need", &self.entertainment_need), ("sleep_need", &self.sleep_need), ("water_need", &self.water_need)] {
+            if let Some(need) = need {
+                if need.variance <= 0.0 {
+                    errors.push(format!("field `{}.variance` is {}, it must be positive", field, need.variance));
+                }
+                if need.max <= 0.0 {
+                    errors.push(format!("field `{}.max` is {}, it must be positive", field, need.max));
+                }
+            }
+        }
+        match &self.skills {
+            SkillDistribution::Untrained => {}
+            SkillDistribution::Shuffled { experience, exp_lost } => {
+                let skill_count = Skillset::default().all_mut().len();
+                for (field, ranges) in [("experience", experience), ("exp_lost", exp_lost)] {
+                    if ranges.len() > skill_count {
+                        errors.push(format!("field `skills.{}` has {} ranges, but there are only {} skills", field, ranges.len(), skill_count));
+                    }
+                    for (min, max) in ranges {
+                        if min > max {
+                            errors.push(format!("field `skills.{}` has range ({}, {}), min is above max", field, min, max));
+                        }
+                    }
+                }
+            }
+            SkillDistribution::PerSkill(ranges) => {
+                for (skill_name, (min, max)) in ranges {
+                    if Skillset::default().get(skill_name).is_none() {
+                        errors.push(format!("field `skills` names unknown skill '{}'", skill_name));
+                    }
+                    if min > max {
+                        errors.push(format!("field `skills` has range ({}, {}) for '{}', min is above max", min, max, skill_name));
+                    }
+                }
+            }
+        }
+        for skill_name in self.skill_bonuses.keys() {
+            if Skillset::default().get(skill_name).is_none() {
+                errors.push(format!("field `skill_bonuses` names unknown skill '{}'", skill_name));
+            }
+        }
+        for (attribute_name, (min, max)) in &self.attributes {
+            if Attributeset::default().get_mut(attribute_name).is_none() {
+                errors.push(format!("field `attributes` names unknown attribute '{}'", attribute_name));
+            }
+            if min > max {
+                errors.push(format!("field `attributes` has range ({}, {}) for '{}', min is above max", min, max, attribute_name));
+            }
+        }
+        let (min_count, max_count) = self.afflictions.count;
+        if min_count > max_count {
+            errors.push(format!("field `afflictions.count` is ({}, {}), min is above max", min_count, max_count));
+        }
+        for pool in &self.afflictions.pools {
+            if pool.rolls > 0 && (pool.types.is_empty() || pool.locations.is_empty()) {
+                errors.push("field `afflictions.pools` has a pool with no types or no locations".to_string());
+            }
+        }
+
+        let inventory = if self.inventory.is_empty() && self.equipment.is_empty() { None } else { Some(Inventory::default()) };
+        let equipment = if self.inventory.is_empty() && self.equipment.is_empty() { None } else { Some(Equipment::default()) };
+        let mut kit = UnitKit { inventory, equipment };
+        for (item_id, quantity) in &self.inventory {
+            let Some(definition) = items.get(item_id) else {
+                errors.push(format!("field `inventory` names unknown item '{}'", item_id));
+                continue;
+            };
+            if *quantity == 0 || *quantity > definition.max_stack {
+                errors.push(format!("field `inventory` has {} '{}', it must be between 1 and {}", quantity, item_id, definition.max_stack));
+                continue;
+            }
+            let added = match (&mut kit.inventory, items.create_item(item_id, *quantity)) {
+                (Some(inventory), Some(item)) => inventory.add_item(item),
+                _ => false,
+            };
+            if !added {
+                errors.push(format!("field `inventory` has '{}', but it doesn't fit", item_id));
+            }
         }
+        for item_id in &self.equipment {
+            let Some(item) = items.create_item(item_id, 1) else {
+                errors.push(format!("field `equipment` names unknown item '{}'", item_id));
+                continue;
+            };
+            if let Some(equipment) = &mut kit.equipment {
+                if equipment.equip(item).is_err() {
+                    errors.push(format!("field `equipment` has '{}', which can't be worn or whose slot is already taken", item_id));
+                }
+            }
+        }
+        if errors.is_empty() { Ok(kit) } else { Err(errors) }
     }
-    pub fn spider() -> Self {
-        let actor_type = ActorType::Spider;
-        let random_afflictions = vec![];//Self::random_afflictions_animal();
-        Self {
-            actor_type,
-            food_need: None,
-            entertainment_need: None,
-            sleep_need: None,
-            personality: vec![PersonalityTrait::Creature, PersonalityTrait::Territorial],
-            afflictions: random_afflictions.to_vec(),
-            skillset: Skillset::default(),
-            attributes: Attributeset::default(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(HasName { name: "Spider".to_string() }); },
-                |commands: &mut Commands, entity: Entity| { commands.entity(entity).insert(SetNest); }
-            ],
+
+    fn roll_skillset(&self, rng: &mut impl Rng) -> Skillset {
+        let mut skillset = Skillset::default();
+        match &self.skills {
+            SkillDistribution::Untrained => {}
+            SkillDistribution::Shuffled { experience, exp_lost } => {
+                let mut values: Vec<i32> = experience.iter().map(|(min, max)| rng.gen_range(*min..=*max)).collect();
+                let mut loss_values: Vec<i32> = exp_lost.iter().map(|(min, max)| rng.gen_range(*min..=*max)).collect();
+                values.shuffle(rng);
+                for skill in skillset.all_mut() {
+                    skill.experience = values.pop().unwrap_or(100);
+                    skill.exp_lost = loss_values.pop().unwrap_or(0);
+                }
+            }
+            SkillDistribution::PerSkill(ranges) => {
+                for (skill_name, (min, max)) in ranges {
+                    if let Some(skill) = skillset.get_mut(skill_name) {
+                        skill.experience = rng.gen_range(*min..=*max);
+                    }
+                }
+            }
         }
+        for (skill_name, bonus) in &self.skill_bonuses {
+            if let Some(skill) = skillset.get_mut(skill_name) {
+                skill.experience += bonus;
+            }
+        }
+        skillset
     }
-    pub fn crab() -> Self {
-        let actor_type = ActorType::Crab;
-        let random_afflictions = vec![];//Self::random_afflictions_animal();
-        Self {
-            actor_type,
-            food_need: None,
-            entertainment_need: None,
-            sleep_need: None,
-            personality: vec![PersonalityTrait::Creature],
-            afflictions: random_afflictions.to_vec(),
-            skillset: Skillset::default(),
-            attributes: Attributeset::default(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| {
-                    commands.entity(entity).insert(HasName { name: ["Crab", "Carl", "Rusty"][rand::thread_rng().gen_range(0..3)].to_string() });
-                },
-            ],
+
+    fn roll_attributes(&self, rng: &mut impl Rng) -> Attributeset {
+        let mut attributes = Attributeset::default();
+        for (attribute_name, (min, max)) in &self.attributes {
+            if let Some(value) = attributes.get_mut(attribute_name) {
+                *value = rng.gen_range(*min..=*max);
+            }
         }
+        attributes
     }
-    pub fn titan_mech() -> Self {
-        let actor_type = ActorType::TitanMech;
-        let random_afflictions = vec![];//Self::random_afflictions_animal();
-        Self {
-            actor_type,
-            food_need: None,
-            entertainment_need: None,
-            sleep_need: None,
-            personality: vec![PersonalityTrait::Creature, PersonalityTrait::Vicious],
-            afflictions: random_afflictions.to_vec(),
-            skillset: Skillset::default(),
-            attributes: Attributeset::default(),
-            component_builders: vec![
-                |commands: &mut Commands, entity: Entity| {
-                    commands.entity(entity).insert(HasName { name: "Cyclops".to_string() });
-                },
-            ],
+}
+
+impl AfflictionRoll {
+    fn roll(&self, rng: &mut impl Rng) -> Vec<Affliction> {
+        let mut afflictions = Vec::new();
+        for pool in &self.pools {
+            for _ in 0..pool.rolls {
+                let (Some(affliction_type), Some(affliction_location)) = (pool.types.choose(rng), pool.locations.choose(rng)) else { continue; };
+                afflictions.push(Affliction {
+                    affliction_type: *affliction_type,
+                    affliction_location: *affliction_location,
+                    duration: 0,
+                    severity: 1,
+                    worsening: false,
+                });
+            }
         }
+        afflictions.shuffle(rng);
+        let (min_count, max_count) = self.count;
+        afflictions.truncate(rng.gen_range(min_count..=max_count));
+        afflictions
     }
-    pub fn random_afflictions_humanoid() -> Vec<Affliction> {
-        ////////////////////////////
-        // Select some Afflictions
-        let random_affliction_pair = || {
-            let affliction_locations = vec![
-                AfflictionLocation::Head,
-                AfflictionLocation::LeftArm,
-                AfflictionLocation::RightArm,
-                AfflictionLocation::LeftLeg,
-                AfflictionLocation::RightLeg,
-                AfflictionLocation::Torso,
-            ];
-            let affliction_types = vec![
-                AfflictionType::BrokenBone,
-                AfflictionType::Cut,
-                AfflictionType::Disease,
-                AfflictionType::Frostbite,
-                AfflictionType::Infection
-            ];
-            let mut rng = rand::thread_rng();
-            let i1 = rng.gen_range(0..affliction_types.len());
-            let i2 = rng.gen_range(0..affliction_locations.len());
-            (affliction_types[i1], affliction_locations[i2])
-        };
-        let a = random_affliction_pair();
-        let b = random_affliction_pair();
-        let random_affliction_pair = || {
-            let affliction_locations = vec![
-                AfflictionLocation::Heart,
-                AfflictionLocation::Stomach,
-                AfflictionLocation::Liver,
-                AfflictionLocation::Spleen,
-                AfflictionLocation::Kidneys,
-                AfflictionLocation::Bladder,
-                AfflictionLocation::Intestines,
-                AfflictionLocation::Genitals,
-            ];
-            let affliction_types = vec![
-                AfflictionType::Pain,
-                AfflictionType::Inflammation,
-                AfflictionType::Disease,
-                AfflictionType::Wound,
-            ];
-            let mut rng = rand::thread_rng();
-            let i1 = rng.gen_range(0..affliction_types.len());
-            let i2 = rng.gen_range(0..affliction_locations.len());
-            (affliction_types[i1], affliction_locations[i2])
-        };
-        let c = random_affliction_pair();
-        let d = random_affliction_pair();
-        let affliction_list = vec![
-            Affliction {
-                affliction_type: a.0,
-                affliction_location: a.1,
-                duration: 0,
-                severity: 1,
-                worsening: false,
-            },
-            Affliction {
-                affliction_type: b.0,
-                affliction_location: b.1,
-                duration: 0,
-                severity: 1,
-                worsening: false,
-            },
-            Affliction {
-                affliction_type: c.0,
-                affliction_location: c.1,
-                duration: 0,
-                severity: 1,
-                worsening: false,
-            },
-            Affliction {
-                affliction_type: d.0,
-                affliction_location: d.1,
-                duration: 0,
-                severity: 1,
-                worsening: false,
-            },
-        ];
-        let mut rng = rand::thread_rng();
-        // Shuffle the affliction_list randomly
-        let mut shuffled_afflictions = affliction_list.clone();
-        shuffled_afflictions.shuffle(&mut rng);
-        // Take a random number of afflictions (up to 2)
-        let num_afflictions = rng.gen_range(0..=2);
-        let selected_afflictions = &shuffled_afflictions[..num_afflictions];
-        selected_afflictions.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipped_items() -> ItemRegistry {
+        ItemRegistry::load(&asset_path(ITEM_DIRECTORY)).unwrap()
     }
-    pub fn random_skillset_humanoid() -> Skillset {
-        let mut rng = rand::thread_rng();
-        let ranges = [5000..7000, 5000..7000, 3000..4000, 2000..3000, 1000..2000, 500..1000, 500..1000, 400..800, 300..600, 300..600, 200..500, 200..500, 100..300];
-        let mut values: Vec<i32> = ranges.iter().map(|range| rng.gen_range(range.clone())).collect();
-        let losses = [1000..2000, 1000..2000, 200..500, 200..500, 100..300, 100..300, 100..300, 100..300, 100..300, 100..300, 100..300, 100..300, 100..300];
-        let mut loss_values: Vec<i32> = losses.iter().map(|range| rng.gen_range(range.clone())).collect();
-        values.shuffle(&mut rng);
-        Skillset {
-            animal_raising: Skill   { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            brawling: Skill         { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            construction: Skill     { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            cooking: Skill          { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            crafting: Skill         { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            doctoring: Skill        { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            farming: Skill          { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            fishing: Skill          { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            foraging: Skill         { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            hunting: Skill          { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            mining: Skill           { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            social: Skill           { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-            woodcutting: Skill      { experience: values.pop().unwrap_or(100), exp_lost: loss_values.pop().unwrap_or(0) },
-        }
+
+    #[test]
+    fn shipped_units_load_and_roll() {
+        let units = UnitRegistry::load(&asset_path(UNIT_DIRECTORY), &shipped_items()).unwrap();
+        let mut rng = GameRng::new(1);
+        let human = units.roll("human", rng.stream(RngStream::Startup)).unwrap();
+        assert_eq!(human.attributes.strength, 3);
+        assert_eq!(human.inventory.unwrap().count_item("bread"), 2);
+        assert!(units.roll("dragon", rng.stream(RngStream::Startup)).is_none());
     }
-    
-    pub fn random_attributeset_humanoid() -> Attributeset {
-        Attributeset {
-            health: 100,
-            strength: 3,    // Represents ability to do physical work.
-            dexterity: 3,   // Represents ability to do fine work, and affects speed.
-            constitution: 3,// Represents ability to resist disease, poison, and damage.
-            intelligence: 3,// Represents ability to learn and remember.
-            wisdom: 3,      // Represents ability to make good decisions.
-            charisma: 4,    // Represents ability to influence others.
+
+    #[test]
+    fn bad_templates_are_rejected() {
+        let need = "Some((current: 50.0, max: 0.0, rate: 0.1, low: 10.0, normal: 25.0, high: 80.0, variance: 0.0))";
+        let directory = scratch_directory("units-bad", &[
+            ("a.ron", &format!(
+                "[(id: \"rat\", actor_type: Man), (id: \"ghoul\", actor_type: Man, food_need: {}, \
+                 skills: PerSkill({{\"juggling\": (1, 2), \"mining\": (5, 1)}}), attributes: {{\"luck\": (1, 1)}}, \
+                 inventory: [(\"gold\", 1), (\"bread\", 0)], equipment: [\"bread\"])]",
+                need,
+            )),
+            ("b.ron", "[(id: \"rat\", actor_type: Man)]"),
+        ]);
+        let errors = UnitRegistry::load(&directory, &shipped_items()).err().expect("units should not load");
+        let expected = [
+            "a.ron: unit 'ghoul': field `food_need.variance` is 0, it must be positive",
+            "a.ron: unit 'ghoul': field `food_need.max` is 0, it must be positive",
+            "a.ron: unit 'ghoul': field `skills` names unknown skill 'juggling'",
+            "a.ron: unit 'ghoul': field `skills` has range (5, 1) for 'mining', min is above max",
+            "a.ron: unit 'ghoul': field `attributes` names unknown attribute 'luck'",
+            "a.ron: unit 'ghoul': field `inventory` names unknown item 'gold'",
+            "a.ron: unit 'ghoul': field `inventory` has 0 'bread', it must be between 1 and",
+            "a.ron: unit 'ghoul': field `equipment` has 'bread', which can't be worn",
+            "b.ron: unit 'rat': field `id` is already defined in",
+        ];
+        assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+        for error in expected {
+            assert!(errors.iter().any(|e| e.contains(error)), "missing {:?} in {:?}", error, errors);
         }
     }
-}
\ No newline at end of file
+}
@@ -7,7 +7,7 @@ use crate::inventory_system::Equipment;
 // WEATHER SYSTEM - The Long Dark inspired survival mechanics
 // ============================================================================
 
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct Weather {
     pub current_weather: WeatherType,
     pub temperature: f32, // In Celsius
@@ -32,7 +32,7 @@ impl Default for Weather {
     }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum WeatherType {
     Clear,
     PartlyCloudy,
@@ -104,6 +104,25 @@ impl WeatherType {
             _ => 0.0,
         }
     }
+
+    // Extra movement cost of every open-air surface tile, on the TileType::movement_cost scale.
+    pub fn snow_cost(&self) -> i32 {
+        match self {
+            WeatherType::LightSnow => 3,
+            WeatherType::HeavySnow => 8,
+            WeatherType::Blizzard => 15,
+            _ => 0,
+        }
+    }
+
+    // Extra movement cost of open-air dirt and grass, turned to mud by the rain.
+    pub fn mud_cost(&self) -> i32 {
+        match self {
+            WeatherType::LightRain => 2,
+            WeatherType::HeavyRain | WeatherType::Thunderstorm => 6,
+            _ => 0,
+        }
+    }
 }
 
 // ============================================================================
@@ -157,7 +176,7 @@ pub struct Shelter {
 // TIME OF DAY
 // ============================================================================
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct TimeOfDay {
     pub hour: f32, // 0.0 to 24.0
     pub day: i32,
@@ -174,7 +193,7 @@ impl Default for TimeOfDay {
     }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Season {
     Spring,
     Summer,
@@ -217,12 +236,15 @@ pub struct AmbientLight {
 
 pub struct WeatherPlugin;
 
+// Game seconds between weather and clock updates.
+pub const WEATHER_UPDATE_SECONDS: f32 = 2.0;
+
 impl Plugin for WeatherPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Weather::default())
             .insert_resource(TimeOfDay::default())
             .add_systems(
-                Update,
+                SimTick,
                 (
                     weather_update_system,
                     time_of_day_system,
@@ -232,43 +254,29 @@ impl Plugin for WeatherPlugin {
                     hyperthermia_system,
                     wind_chill_system,
                 )
-                    .run_if(bevy::time::common_conditions::on_timer(
-                        bevy::utils::Duration::from_secs(2),
-                    )),
+                    .run_if(every_seconds(WEATHER_UPDATE_SECONDS)),
             )
             .add_systems(Update, day_night_lighting_system);
     }
 }
 
 // System to update weather conditions
-fn weather_update_system(mut weather: ResMut<Weather>, time: Res<Time>) {
-    weather.time_until_change -= time.delta_seconds();
+fn weather_update_system(mut weather: ResMut<Weather>, biome: Res<Biome>, mut game_rng: ResMut<GameRng>) {
+    weather.time_until_change -= WEATHER_UPDATE_SECONDS;
 
     if weather.time_until_change <= 0.0 {
-        let mut rng = rand::thread_rng();
-
-        // Pick new weather
-        weather.current_weather = match rng.gen_range(0..10) {
-            0 => WeatherType::Clear,
-            1 => WeatherType::PartlyCloudy,
-            2 => WeatherType::Cloudy,
-            3 => WeatherType::LightRain,
-            4 => WeatherType::HeavyRain,
-            5 => WeatherType::LightSnow,
-            6 => WeatherType::HeavySnow,
-            7 => WeatherType::Fog,
-            8 => WeatherType::Thunderstorm,
-            9 => WeatherType::Blizzard,
-            _ => WeatherType::Clear,
-        };
+        let rng = game_rng.stream(RngStream::Weather);
+
+        // Pick new weather, as common as the biome's climate makes it
+        weather.current_weather = biome.pick_weather(rng);
 
         // Set temperature
         let (min_temp, max_temp) = weather.current_weather.temperature_range();
-        weather.temperature = rng.gen_range(min_temp..max_temp);
+        weather.temperature = rng.gen_range(min_temp..max_temp) + biome.climate.temperature_offset;
 
         // Set wind
         let (min_wind, max_wind) = weather.current_weather.wind_speed_range();
-        weather.wind_speed = rng.gen_range(min_wind..max_wind);
+        weather.wind_speed = rng.gen_range(min_wind..max_wind) * biome.climate.wind_multiplier;
         weather.wind_direction = rng.gen_range(0.0..360.0);
 
         // Set precipitation and visibility
@@ -283,10 +291,9 @@ fn weather_update_system(mut weather: ResMut<Weather>, time: Res<Time>) {
 // System to advance time
 fn time_of_day_system(
     mut time_of_day: ResMut<TimeOfDay>,
-    time: Res<Time>,
 ) {
-    // Advance time (1 real second = 1 game minute)
-    time_of_day.hour += time.delta_seconds() / 60.0;
+    // Advance time (1 game second = 1 game minute)
+    time_of_day.hour += WEATHER_UPDATE_SECONDS / 60.0;
 
     if time_of_day.hour >= 24.0 {
         time_of_day.hour -= 24.0;
@@ -299,15 +306,24 @@ fn time_of_day_system(
     }
 }
 
+// Air temperature out in the open, before anything on the map warms or cools it.
+pub fn outdoor_temperature(weather: &Weather, time_of_day: &TimeOfDay) -> f32 {
+    weather.temperature + time_of_day.season.temperature_modifier()
+}
+
 // System to apply temperature effects to characters
 fn temperature_effect_system(
-    mut query: Query<(&mut Temperature, &Equipment, Option<&Shelter>)>,
+    mut query: Query<(&mut Temperature, &Equipment, Option<&Shelter>, &Position)>,
+    tile_temperatures: Res<crate::TileTemperatures>,
     weather: Res<Weather>,
     time_of_day: Res<TimeOfDay>,
 ) {
-    let ambient_temp = weather.temperature + time_of_day.season.temperature_modifier();
+    let outdoor_temp = outdoor_temperature(&weather, &time_of_day);
+
+    for (mut temp, equipment, shelter, position) in query.iter_mut() {
+        // The air where the character stands
+        let ambient_temp = tile_temperatures.at(position).unwrap_or(outdoor_temp);
 
-    for (mut temp, equipment, shelter) in query.iter_mut() {
         // Calculate warmth from equipment
         temp.warmth_bonus = equipment.total_warmth();
 
@@ -332,13 +348,14 @@ fn temperature_effect_system(
 
 // System to calculate wind chill
 fn wind_chill_system(
-    mut query: Query<&mut Temperature>,
+    mut query: Query<(&mut Temperature, Option<&Shelter>)>,
     weather: Res<Weather>,
 ) {
-    for mut temp in query.iter_mut() {
+    for (mut temp, shelter) in query.iter_mut() {
+        let exposure = 1.0 - shelter.map_or(0.0, |shelter| shelter.protection_from_wind);
         // Wind chill formula (simplified)
-        if weather.wind_speed > 5.0 {
-            let wind_chill = -1.5 * (weather.wind_speed / 10.0);
+        if weather.wind_speed > 5.0 && exposure > 0.0 {
+            let wind_chill = -1.5 * (weather.wind_speed / 10.0) * exposure;
             temp.feels_like = temp.core_temp + wind_chill;
         } else {
             temp.feels_like = temp.core_temp;
@@ -421,7 +438,7 @@ fn day_night_lighting_system(
     }
 }
 
-fn calculate_brightness(hour: f32) -> f32 {
+pub fn calculate_brightness(hour: f32) -> f32 {
     // Simple brightness curve
     // Night: 0-6, 20-24 = dark
     // Dawn: 6-8 = getting brighter